@@ -2,9 +2,10 @@ use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::Value;
 use std::path::Path;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use std::pin::Pin;
-use shared_protocol_objects::Role;
+use std::sync::Arc;
+use shared_protocol_objects::{Role, ToolInfo};
 
 #[derive(Debug, Clone)]
 pub enum StreamEvent {
@@ -34,6 +35,34 @@ pub enum StreamEvent {
 
 pub type StreamResult = Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>;
 
+/// The AI client in use, shared with the tasks that answer servers' sampling requests and
+/// replaced when the user switches models
+pub type SharedAIClient = Arc<std::sync::RwLock<Option<Arc<dyn AIClient>>>>;
+
+/// A stream of response text chunks in the order the model produced them
+pub type TextStream = Pin<Box<dyn Stream<Item = Result<String>> + Send>>;
+
+/// Result of a request made through a provider's native tool-calling API
+#[derive(Debug, Clone)]
+pub enum AIResponse {
+    Text(String),
+    ToolCall {
+        name: String,
+        id: String,
+        arguments: Value,
+    },
+}
+
+impl AIResponse {
+    /// What the model replied, as text to count its tokens
+    pub fn reply_text(&self) -> String {
+        match self {
+            AIResponse::Text(text) => text.clone(),
+            AIResponse::ToolCall { name, arguments, .. } => format!("{} {}", name, arguments),
+        }
+    }
+}
+
 /// Content types that can be sent to AI models
 #[derive(Debug, Clone)]
 pub enum Content {
@@ -101,6 +130,48 @@ pub trait AIClient: Send + Sync {
     
     /// Get the model's name/identifier
     fn model_name(&self) -> String;
+
+    /// Whether `stream` yields output incrementally for this client
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    /// Whether `execute_with_tools` uses the provider's structured tool-calling API. The
+    /// chat loop then prefers it over `stream`, so these clients' replies arrive whole: a
+    /// streamed reply can't carry a structured tool call.
+    fn supports_native_tools(&self) -> bool {
+        false
+    }
+
+    /// Send `messages` along with `tools` definitions and return either text or a tool call
+    async fn execute_with_tools(&self, _messages: Vec<(Role, String)>, _tools: &[ToolInfo]) -> Result<AIResponse> {
+        Err(anyhow::anyhow!("{} does not support native tool calling", self.model_name()))
+    }
+
+    /// Send `messages` as a streaming request and yield the response text as it arrives
+    async fn stream(&self, messages: Vec<(Role, String)>) -> Result<TextStream> {
+        let mut builder = self.raw_builder().streaming(true);
+        for (role, content) in messages {
+            builder = match role {
+                Role::System => builder.system(content),
+                Role::User => builder.user(content),
+                Role::Assistant => builder.assistant(content),
+            };
+        }
+
+        let events = builder.execute_streaming().await?;
+        let chunks = events.filter_map(|event| async move {
+            match event {
+                Ok(StreamEvent::ContentDelta { text, .. }) if !text.is_empty() => Some(Ok(text)),
+                Ok(StreamEvent::Error { error_type, message }) => {
+                    Some(Err(anyhow::anyhow!("{}: {}", error_type, message)))
+                }
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            }
+        });
+        Ok(Box::pin(chunks))
+    }
 }
 
 /// Capabilities of an AI model
@@ -135,10 +206,18 @@ pub struct AIClientFactory;
 impl AIClientFactory {
     pub fn create(provider: &str, config: Value) -> Result<Box<dyn AIClient>> {
         match provider {
+            "deepseek" => {
+                let api_key = config["api_key"].as_str()
+                    .ok_or_else(|| anyhow::anyhow!("DeepSeek API key not provided"))?;
+                let model = config["model"].as_str().unwrap_or("deepseek-chat");
+                let client = crate::deepseek::DeepSeekClient::new(api_key.to_string(), model.to_string());
+                Ok(Box::new(client))
+            }
             "gemini" => {
                 let api_key = config["api_key"].as_str()
                     .ok_or_else(|| anyhow::anyhow!("Gemini API key not provided"))?;
-                let client = crate::gemini::GeminiClient::new(api_key.to_string(), "gemini-pro".to_string());
+                let model = config["model"].as_str().unwrap_or("gemini-pro");
+                let client = crate::gemini::GeminiClient::new(api_key.to_string(), model.to_string());
                 Ok(Box::new(client))
             }
             "anthropic" => {
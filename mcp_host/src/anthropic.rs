@@ -1,11 +1,12 @@
 use anyhow::{Result, Context};
 use async_trait::async_trait;
 use reqwest::Client;
-use serde_json::json;
-use crate::ai_client::{AIClient, AIRequestBuilder, GenerationConfig, StreamResult};
+use serde_json::{json, Value};
+use crate::ai_client::{AIClient, AIRequestBuilder, AIResponse, GenerationConfig, StreamResult};
+use crate::conversation_state::{split_native_tool_call, split_tool_result};
 use crate::streaming::parse_sse_stream;
 
-use shared_protocol_objects::Role;
+use shared_protocol_objects::{Role, ToolInfo};
 
 #[derive(Debug, Clone)]
 pub struct AnthropicClient {
@@ -19,6 +20,69 @@ impl AnthropicClient {
     }
 }
 
+fn role_name(role: &Role) -> &'static str {
+    match role {
+        Role::User => "user",
+        Role::Assistant => "assistant",
+        Role::System => unreachable!("system messages go in the `system` field"),
+    }
+}
+
+/// The non-system messages of a conversation in the Messages API format. With `tool_blocks`,
+/// a native tool call recorded by `handle_assistant_response` becomes a `tool_use` block and
+/// the result recorded right after it a `tool_result` block answering it. The API only accepts
+/// those blocks in requests that define tools, so without `tool_blocks` everything stays text.
+fn to_anthropic_messages(messages: &[&(Role, String)], tool_blocks: bool) -> Vec<Value> {
+    let mut converted: Vec<Value> = Vec::new();
+
+    for (index, (role, content)) in messages.iter().enumerate() {
+        let call = converted.last().and_then(unanswered_tool_use).cloned();
+        let (role, block) = match (tool_blocks, call) {
+            (true, Some(call)) if split_tool_result(content).is_some_and(|(tool_name, _)| call["name"] == tool_name) => {
+                let (tool_name, result) = split_tool_result(content).unwrap();
+                let is_error = content.starts_with(&format!("Tool '{}' error: ", tool_name));
+                ("user", json!({ "type": "tool_result", "tool_use_id": call["id"], "content": result, "is_error": is_error }))
+            }
+            _ => match split_native_tool_call(content).filter(|_| tool_blocks) {
+                Some((tool_name, input)) => {
+                    let id = format!("toolu_{}", index);
+                    ("assistant", json!({ "type": "tool_use", "id": id, "name": tool_name, "input": input }))
+                }
+                None => (role_name(role), json!({ "type": "text", "text": content })),
+            },
+        };
+
+        if block["type"] != "tool_result" {
+            demote_unanswered_tool_use(&mut converted);
+        }
+        // Consecutive messages of one role are merged, since the roles have to alternate
+        match converted.last_mut() {
+            Some(last) if last["role"] == role => last["content"].as_array_mut().unwrap().push(block),
+            _ => converted.push(json!({ "role": role, "content": [block] })),
+        }
+    }
+    demote_unanswered_tool_use(&mut converted);
+    converted
+}
+
+/// The `tool_use` block ending `message`, if any
+fn unanswered_tool_use(message: &Value) -> Option<&Value> {
+    message["content"].as_array()?.last().filter(|block| block["type"] == "tool_use")
+}
+
+/// A `tool_use` without a `tool_result` right after it is rejected, so send it as the text
+/// it was recorded as
+fn demote_unanswered_tool_use(converted: &mut [Value]) {
+    let Some(last) = converted.last_mut() else { return };
+    let Some(call) = unanswered_tool_use(last).cloned() else { return };
+    let blocks = last["content"].as_array_mut().unwrap();
+    blocks.pop();
+    blocks.push(json!({
+        "type": "text",
+        "text": format!("Calling tool '{}' with arguments: {}", call["name"].as_str().unwrap_or_default(), call["input"]),
+    }));
+}
+
 #[async_trait]
 impl AIClient for AnthropicClient {
     fn model_name(&self) -> String {
@@ -37,6 +101,79 @@ impl AIClient for AnthropicClient {
     fn raw_builder(&self) -> Box<dyn AIRequestBuilder> {
         self.builder()
     }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        true
+    }
+
+    async fn execute_with_tools(&self, messages: Vec<(Role, String)>, tools: &[ToolInfo]) -> Result<AIResponse> {
+        let (system_message, other_messages): (Vec<_>, Vec<_>) = messages.iter()
+            .partition(|(role, _)| matches!(role, Role::System));
+
+        let tools_json: Vec<Value> = tools.iter().map(|tool| {
+            // Anthropic rejects schemas that aren't objects
+            let input_schema = if tool.input_schema.get("type").is_some() {
+                tool.input_schema.clone()
+            } else {
+                json!({ "type": "object", "properties": {} })
+            };
+            json!({
+                "name": tool.name,
+                "description": tool.description.clone().unwrap_or_default(),
+                "input_schema": input_schema
+            })
+        }).collect();
+
+        let mut payload = json!({
+            "model": self.model,
+            "messages": to_anthropic_messages(&other_messages, true),
+            "tools": tools_json,
+            "max_tokens": 1024
+        });
+
+        if let Some((_, system_content)) = system_message.first() {
+            payload.as_object_mut().unwrap()
+                .insert("system".to_string(), json!(system_content));
+        }
+
+        let client = Client::new();
+        let response = client
+            .post("https://api.anthropic.com/v1/messages")
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(&payload)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error = response.text().await?;
+            return Err(anyhow::anyhow!("Anthropic API error: {}", error));
+        }
+
+        let response_json = response.json::<Value>().await?;
+        let blocks = response_json["content"]
+            .as_array()
+            .context("Response has no content blocks")?;
+
+        if let Some(tool_use) = blocks.iter().find(|block| block["type"] == "tool_use") {
+            return Ok(AIResponse::ToolCall {
+                name: tool_use["name"].as_str().unwrap_or_default().to_string(),
+                id: tool_use["id"].as_str().unwrap_or_default().to_string(),
+                arguments: tool_use["input"].clone(),
+            });
+        }
+
+        let text = blocks.iter()
+            .filter_map(|block| block["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("");
+        Ok(AIResponse::Text(text))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -99,16 +236,7 @@ impl AIRequestBuilder for AnthropicCompletionBuilder {
         // Build the payload
         let mut payload = json!({
             "model": self.client.model,
-            "messages": other_messages.iter().map(|(role, content)| {
-                json!({
-                    "role": match role {
-                        Role::User => "user",
-                        Role::Assistant => "assistant",
-                        _ => unreachable!()
-                    },
-                    "content": content
-                })
-            }).collect::<Vec<_>>(),
+            "messages": to_anthropic_messages(&other_messages, false),
             "stream": true,
             "max_tokens": 1024
         });
@@ -157,16 +285,7 @@ impl AIRequestBuilder for AnthropicCompletionBuilder {
         // Build the payload
         let mut payload = json!({
             "model": self.client.model,
-            "messages": other_messages.iter().map(|(role, content)| {
-                json!({
-                    "role": match role {
-                        Role::User => "user",
-                        Role::Assistant => "assistant",
-                        _ => unreachable!()
-                    },
-                    "content": content
-                })
-            }).collect::<Vec<_>>(),
+            "messages": to_anthropic_messages(&other_messages, false),
             "stream": self.stream,
             "max_tokens": 1024
         });
@@ -0,0 +1,144 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use shared_protocol_objects::JsonRpcResponse;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+lazy_static! {
+    static ref AUDIT_LOG: Option<Mutex<RollingFileAppender>> = open_from_env();
+}
+
+/// One line of the audit log. Holds no response content, only what is needed to see which
+/// servers and tools are slow or failing. Tool calls also keep the tool name, and their
+/// arguments when `MCP_AUDIT_LOG_ARGS=1`, so the `history` command can replay them.
+#[derive(Debug, Serialize)]
+struct AuditEntry<'a> {
+    timestamp: DateTime<Utc>,
+    server: &'a str,
+    method: &'a str,
+    request_id: &'a str,
+    request_params_hash: String,
+    response_status: &'static str,
+    duration_ms: u64,
+    error_code: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    arguments: Option<&'a Value>,
+}
+
+/// A tool call read back from the audit log
+#[derive(Debug, Clone, Deserialize)]
+pub struct ToolCallEntry {
+    pub timestamp: DateTime<Utc>,
+    pub server: String,
+    pub tool: String,
+    /// Missing unless the call was made with `MCP_AUDIT_LOG_ARGS=1`
+    #[serde(default)]
+    pub arguments: Option<Value>,
+    pub response_status: String,
+    pub duration_ms: u64,
+}
+
+/// `MCP_AUDIT_LOG` split into the directory and the file name prefix of the daily files
+fn configured_location() -> Option<(PathBuf, PathBuf, String)> {
+    let path = PathBuf::from(std::env::var_os("MCP_AUDIT_LOG")?);
+    let file_name = path.file_name()?.to_string_lossy().into_owned();
+    let dir = path.parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+        .to_path_buf();
+    Some((path, dir, file_name))
+}
+
+/// Tool arguments can hold file contents or credentials, so they are only logged on request
+fn arguments_logged() -> bool {
+    std::env::var("MCP_AUDIT_LOG_ARGS").is_ok_and(|value| value == "1")
+}
+
+/// The audit log is written only when `MCP_AUDIT_LOG` names a file. A new file with the
+/// date appended to the name is started every day.
+fn open_from_env() -> Option<Mutex<RollingFileAppender>> {
+    let (path, dir, file_name) = configured_location()?;
+
+    match RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(file_name)
+        .build(&dir)
+    {
+        Ok(appender) => {
+            log::info!("Writing the request audit log to {}", path.display());
+            Some(Mutex::new(appender))
+        }
+        Err(e) => {
+            log::warn!("Failed to open audit log {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Append the outcome of a request to the audit log, if one is configured
+pub fn record(
+    server: &str,
+    method: &str,
+    request_id: &str,
+    params_hash: u64,
+    tool_call: Option<(&str, &Value)>,
+    result: &Result<JsonRpcResponse>,
+    duration: Duration,
+) {
+    let Some(audit_log) = AUDIT_LOG.as_ref() else { return };
+
+    let error_code = match result {
+        Ok(response) => response.error.as_ref().map(|error| error.code.code()),
+        Err(_) => None,
+    };
+    let succeeded = matches!(result, Ok(response) if response.error.is_none());
+    let entry = AuditEntry {
+        timestamp: Utc::now(),
+        server,
+        method,
+        request_id,
+        request_params_hash: format!("{:x}", params_hash),
+        response_status: if succeeded { "ok" } else { "error" },
+        duration_ms: duration.as_millis() as u64,
+        error_code,
+        tool: tool_call.map(|(tool, _)| tool),
+        arguments: tool_call.map(|(_, arguments)| arguments).filter(|_| arguments_logged()),
+    };
+
+    let Ok(line) = serde_json::to_string(&entry) else { return };
+    let mut audit_log = audit_log.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Err(e) = writeln!(audit_log, "{}", line) {
+        log::warn!("Failed to write audit log entry: {}", e);
+    }
+}
+
+/// Every tool call in the audit log files, oldest first. Lines that aren't tool calls, or
+/// can't be parsed, are skipped.
+pub fn read_tool_calls() -> Result<Vec<ToolCallEntry>> {
+    let (_, dir, file_name) = configured_location()
+        .ok_or_else(|| anyhow!("No audit log configured; set MCP_AUDIT_LOG to record one"))?;
+
+    // Daily files are named `<file_name>.<yyyy-mm-dd>`, so sorting by name sorts by date
+    let prefix = format!("{}.", file_name);
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .map(|entry| entry.path())
+        .collect();
+    files.sort();
+
+    let mut calls = Vec::new();
+    for file in files {
+        let contents = std::fs::read_to_string(&file)?;
+        calls.extend(contents.lines().filter_map(|line| serde_json::from_str::<ToolCallEntry>(line).ok()));
+    }
+    Ok(calls)
+}
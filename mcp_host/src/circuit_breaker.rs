@@ -0,0 +1,135 @@
+use std::time::{Duration, Instant};
+
+/// Consecutive failed requests after which a server's circuit opens
+pub const FAILURE_THRESHOLD: u32 = 5;
+
+/// How long an open circuit rejects requests, unless `MCP_CIRCUIT_COOLDOWN_SECS` is set
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+pub fn cooldown_from_env() -> Duration {
+    std::env::var("MCP_CIRCUIT_COOLDOWN_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_COOLDOWN)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CircuitState {
+    Closed,
+    /// Requests are rejected until the cooldown ends
+    Open { until: Instant },
+    /// One trial request has been let through and decides whether the circuit closes again.
+    /// If it never reports back, another trial is allowed at `retry_at`.
+    HalfOpen { retry_at: Instant },
+}
+
+/// Tracks the consecutive failures of one server so a server that keeps failing is rejected
+/// right away instead of every request waiting for its timeout
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    state: CircuitState,
+    consecutive_failures: u32,
+    cooldown: Duration,
+}
+
+impl CircuitBreaker {
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            cooldown,
+        }
+    }
+
+    /// Whether a request may be sent now. Once the cooldown has passed the first caller gets
+    /// the trial request; others are rejected while it runs.
+    pub fn allow_request(&mut self) -> bool {
+        let now = Instant::now();
+        match self.state {
+            CircuitState::Closed => true,
+            CircuitState::Open { until: retry_at } | CircuitState::HalfOpen { retry_at } if now < retry_at => false,
+            CircuitState::Open { .. } | CircuitState::HalfOpen { .. } => {
+                self.state = CircuitState::HalfOpen { retry_at: now + self.cooldown };
+                true
+            }
+        }
+    }
+
+    /// Whether the request just allowed is the trial after a cooldown
+    pub fn is_half_open(&self) -> bool {
+        matches!(self.state, CircuitState::HalfOpen { .. })
+    }
+
+    pub fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.state = CircuitState::Closed;
+    }
+
+    /// Count a failed request. Returns true if this opened the circuit.
+    pub fn record_failure(&mut self) -> bool {
+        self.consecutive_failures += 1;
+        let open = matches!(self.state, CircuitState::HalfOpen { .. })
+            || self.consecutive_failures >= FAILURE_THRESHOLD;
+        if open {
+            self.state = CircuitState::Open { until: Instant::now() + self.cooldown };
+        }
+        open
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_millis(50);
+
+    fn open_breaker() -> CircuitBreaker {
+        let mut breaker = CircuitBreaker::new(COOLDOWN);
+        for _ in 1..FAILURE_THRESHOLD {
+            assert!(!breaker.record_failure());
+        }
+        assert!(breaker.record_failure());
+        breaker
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let mut breaker = open_breaker();
+        assert!(!breaker.allow_request());
+    }
+
+    #[test]
+    fn success_resets_the_failure_count() {
+        let mut breaker = CircuitBreaker::new(COOLDOWN);
+        for _ in 1..FAILURE_THRESHOLD {
+            breaker.record_failure();
+        }
+        breaker.record_success();
+        assert!(!breaker.record_failure());
+        assert!(breaker.allow_request());
+    }
+
+    #[test]
+    fn one_trial_after_the_cooldown() {
+        let mut breaker = open_breaker();
+        std::thread::sleep(COOLDOWN);
+        assert!(breaker.allow_request());
+        assert!(breaker.is_half_open());
+        // Others wait for the trial
+        assert!(!breaker.allow_request());
+
+        breaker.record_success();
+        assert!(!breaker.is_half_open());
+        assert!(breaker.allow_request());
+    }
+
+    #[test]
+    fn failed_trial_opens_the_circuit_again() {
+        let mut breaker = open_breaker();
+        std::thread::sleep(COOLDOWN);
+        assert!(breaker.allow_request());
+        assert!(breaker.record_failure());
+        assert!(!breaker.allow_request());
+    }
+}
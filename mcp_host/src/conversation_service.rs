@@ -3,7 +3,7 @@ use axum::extract::ws::{Message, WebSocket};
 use serde_json::Value;
 use crate::MCPHost;
 use crate::conversation_state::ConversationState;
-use crate::ai_client::{ AIClient };
+use crate::ai_client::{ AIClient, AIResponse };
 use console::style;
 
 use lazy_static::lazy_static;
@@ -87,59 +87,196 @@ fn extract_balanced_braces(text: &str, open_brace_index: usize) -> Option<(Strin
     None
 }
 
+/// Parses every tool call in `response`, in the order they appear. Calls whose JSON can't be
+/// read are returned as near-miss feedback instead.
+pub fn parse_tool_calls(response: &str, tool_names: &[String]) -> (Vec<(String, Value)>, Vec<String>) {
+    let dynamic_regex = build_tool_call_regex(tool_names);
+    let mut calls = Vec::new();
+    let mut near_misses = Vec::new();
+    let mut offset = 0;
+
+    while let Some(caps) = response.get(offset..).and_then(|rest| dynamic_regex.captures(rest)) {
+        let tool_name = caps[1].to_string();
+        let brace_start_index = offset + caps.get(2).unwrap().start();
+
+        let Some((json_str, end_index)) = extract_balanced_braces(response, brace_start_index) else {
+            near_misses.push(format!(
+                "Found tool call for '{tool_name}', but could not read balanced braces from the text."
+            ));
+            break;
+        };
+        match serde_json::from_str::<Value>(&json_str) {
+            Ok(args) => calls.push((tool_name, args)),
+            Err(e) => near_misses.push(format!(
+                "Found tool call for '{tool_name}', but JSON parse failed: {e}\nJSON block was: {json_str}"
+            )),
+        }
+        offset = end_index;
+    }
+
+    log::debug!("Parsed {} tool call(s) from response", calls.len());
+    (calls, near_misses)
+}
+
+/// Whether the calls can run at the same time. A call depends on another when its arguments
+/// hold a `{{...}}` placeholder for an earlier result or mention one of the other tools.
+fn calls_are_independent(calls: &[(String, Value)]) -> bool {
+    calls.iter().enumerate().all(|(i, (_, args))| {
+        let args = args.to_string();
+        !args.contains("{{")
+            && calls.iter().enumerate().all(|(j, (other, _))| i == j || !args.contains(other.as_str()))
+    })
+}
+
+/// Show a tool's result and add it to the conversation
+async fn record_tool_result(
+    state: &mut ConversationState,
+    socket: Option<&mut WebSocket>,
+    tool_name: &str,
+    result: Result<String>,
+) {
+    match result {
+        Ok(result) => {
+            if let Some(ws) = socket {
+                let end_msg = serde_json::json!({
+                    "type": "tool_call_end",
+                    "tool_name": tool_name
+                });
+                let _ = ws.send(Message::Text(end_msg.to_string())).await;
+            }
+
+            println!(
+                "{}",
+                crate::conversation_state::format_tool_response(tool_name, &result)
+            );
+            let combo = format!("Tool '{tool_name}' returned: {}", result.trim());
+            state.add_assistant_message(&combo);
+        }
+        Err(e) => {
+            let error_msg = format!("Tool '{tool_name}' error: {e}");
+            state.add_assistant_message(&error_msg);
+            log::error!("{}", error_msg);
+        }
+    }
+}
+
+/// Tool calls a native client may chain from one assistant response before it has to answer
+const MAX_TOOL_ROUNDS: usize = 5;
+
 pub async fn handle_assistant_response(
     host: &MCPHost,
-    incoming_response: &str,
+    incoming_response: AIResponse,
+    server_name: &str,
+    state: &mut ConversationState,
+    client: &dyn AIClient,
+    socket: Option<&mut WebSocket>
+) -> Result<()> {
+    handle_response_round(host, incoming_response, server_name, state, client, socket, 0).await
+}
+
+/// `handle_assistant_response` for the `round`th response of the turn, counting from 0
+async fn handle_response_round(
+    host: &MCPHost,
+    incoming_response: AIResponse,
     server_name: &str,
     state: &mut ConversationState,
-    client: &Box<dyn AIClient>,
-    mut socket: Option<&mut WebSocket>
+    client: &dyn AIClient,
+    mut socket: Option<&mut WebSocket>,
+    round: usize,
 ) -> Result<()> {
-    // Record the incoming response
-    state.add_assistant_message(incoming_response);
-
-    let tool_names: Vec<String> = state.tools.iter().map(|t| t.name.clone()).collect();
-    if let Some((tool_name, args)) = match parse_tool_call(incoming_response, &tool_names) {
-        ToolCallResult::Success(name, a) => Some((name, a)),
-        ToolCallResult::NearMiss(feedback) => {
-            let joined = feedback.join("\n");
-            state.add_assistant_message(&joined);
-            None
+    let tool_calls = match incoming_response {
+        // Structured tool calls need no parsing
+        AIResponse::ToolCall { name, id, arguments } => {
+            log::debug!("Native tool call {} for '{}'", id, name);
+            state.add_model_message(&format!("Calling tool '{}' with arguments: {}", name, arguments), client.model_name());
+            vec![(name, arguments)]
+        }
+        AIResponse::Text(text) => {
+            // Record the incoming response
+            state.add_model_message(&text, client.model_name());
+
+            // Clients with native tool calling would have returned a ToolCall instead
+            if client.supports_native_tools() {
+                Vec::new()
+            } else {
+                let tool_names: Vec<String> = state.tools.iter().map(|t| t.name.clone()).collect();
+                let (calls, near_misses) = parse_tool_calls(&text, &tool_names);
+                if !near_misses.is_empty() {
+                    state.add_assistant_message(&near_misses.join("\n"));
+                }
+                calls
+            }
         }
-        ToolCallResult::NoMatch => None,
-    } {
-        // If we found a valid tool call, handle it
-        if let Some(ref mut ws) = socket {
-            let start_msg = serde_json::json!({ "type": "tool_call_start", "tool_name": &tool_name });
+    };
+
+    if let Some(ref mut ws) = socket {
+        for (tool_name, _) in &tool_calls {
+            let start_msg = serde_json::json!({ "type": "tool_call_start", "tool_name": tool_name });
             let _ = ws.send(Message::Text(start_msg.to_string())).await;
         }
+    }
 
-        match host.call_tool(server_name, &tool_name, args).await {
-            Ok(result) => {
-                if let Some(ref mut ws) = socket {
-                    let end_msg = serde_json::json!({
-                        "type": "tool_call_end",
-                        "tool_name": &tool_name
-                    });
-                    let _ = ws.send(Message::Text(end_msg.to_string())).await;
-                }
-                
+    let called_tools = !tool_calls.is_empty();
+    if tool_calls.len() > 1 && calls_are_independent(&tool_calls) {
+        let calls = tool_calls.iter()
+            .map(|(tool_name, args)| (server_name.to_string(), tool_name.clone(), args.clone()))
+            .collect();
+        let message = format!("Calling {} tools in parallel...", tool_calls.len());
+        let results = crate::with_progress(message, host.call_tool_parallel(calls)).await;
+        for ((tool_name, _), result) in tool_calls.iter().zip(results) {
+            record_tool_result(state, socket.as_deref_mut(), tool_name, result).await;
+        }
+    } else {
+        for (tool_name, args) in tool_calls {
+            // Long bash or scrape calls report progress, shown next to the spinner
+            let (progress_tx, progress_rx) = tokio::sync::mpsc::channel(16);
+            let listener = crate::progress::ProgressListener::new(progress_tx);
+            let call = host.call_tool_with_progress(server_name, &tool_name, args, Some(listener));
+            let message = format!("Calling tool '{tool_name}'...");
+            let result = crate::with_live_progress(message, Some(progress_rx), call).await;
+            record_tool_result(state, socket.as_deref_mut(), &tool_name, result).await;
+        }
+    }
+
+    // The next user message asks whether to go on
+    if state.over_budget() {
+        println!("\n{}", style("Cost budget reached, not asking the model about the tool results").yellow());
+        return Ok(());
+    }
+
+    // Native clients get the results back as structured tool results, and may call another tool
+    if client.supports_native_tools() && called_tools {
+        let messages: Vec<(Role, String)> = state.messages.iter().map(|msg| (msg.role.clone(), msg.content.clone())).collect();
+        let call = client.execute_with_tools(messages.clone(), &state.tools);
+        let response = crate::with_progress("Waiting for response...".to_string(), call).await;
+        if let Ok(response) = &response {
+            state.record_ai_call(&client.model_name(), &messages, &response.reply_text());
+        }
+        match response {
+            Ok(AIResponse::Text(final_answer)) => {
                 println!(
-                    "{}",
-                    crate::conversation_state::format_tool_response(&tool_name, &result)
+                    "\n{}",
+                    crate::conversation_state::format_chat_message(&Role::Assistant, &final_answer)
                 );
-                let combo = format!("Tool '{tool_name}' returned: {}", result.trim());
-                state.add_assistant_message(&combo);
+                state.add_model_message(&final_answer, client.model_name());
+                if let Some(ref mut ws) = socket {
+                    let _ = ws.send(Message::Text(final_answer)).await;
+                }
+            }
+            Ok(response) if round + 1 < MAX_TOOL_ROUNDS => {
+                return Box::pin(handle_response_round(host, response, server_name, state, client, socket, round + 1)).await;
             }
-            Err(e) => {
-                let error_msg = format!("Tool '{tool_name}' error: {e}");
-                state.add_assistant_message(&error_msg);
-                log::error!("{}", error_msg);
+            Ok(_) => {
+                log::warn!("Stopped after {} tool calls in one turn", MAX_TOOL_ROUNDS);
+                println!("\n{}", style(format!("Stopped after {} tool calls in one turn", MAX_TOOL_ROUNDS)).yellow());
             }
+            Err(e) => log::error!("Error requesting final answer: {}", e),
         }
+        return Ok(());
     }
 
     // Now generate the final answer again with full conversation context
+    let sent: Vec<(Role, String)> = state.messages.iter().map(|msg| (msg.role.clone(), msg.content.clone())).collect();
     let mut builder = client.raw_builder();
     for msg in &state.messages {
         match msg.role {
@@ -165,12 +302,14 @@ pub async fn handle_assistant_response(
         }
     };
 
+    state.record_ai_call(&client.model_name(), &sent, &final_answer);
+
     // Display the final text
     println!(
         "\n{}",
         crate::conversation_state::format_chat_message(&Role::Assistant, &final_answer)
     );
-    state.add_assistant_message(&final_answer);
+    state.add_model_message(&final_answer, client.model_name());
 
     // Send the final text to client
     if let Some(ref mut ws) = socket {
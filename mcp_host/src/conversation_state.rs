@@ -1,6 +1,11 @@
+use crate::ai_client::AIClient;
+use crate::cost::CostEstimator;
 use shared_protocol_objects::{Role, ToolInfo};
 use console::style;
+use serde::{Deserialize, Serialize};
 use serde_json;
+use std::fs;
+use std::path::Path;
 
 pub fn format_json_output(json_str: &str) -> String {
     if let Ok(value) = serde_json::from_str::<serde_json::Value>(json_str) {
@@ -64,17 +69,73 @@ pub fn format_chat_message(role: &Role, content: &str) -> String {
     format!("{}: {}\n", role_style, format_markdown(content))
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub role: Role,
     pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<MessageMetadata>,
 }
 
-#[derive(Debug, Clone)]
+/// Where an assistant turn came from, for comparing what each model cost or did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageMetadata {
+    pub model: String,
+}
+
+/// Most recent user/assistant messages that `trim_to_fit` never removes
+const KEEP_RECENT_MESSAGES: usize = 4;
+
+/// Starts the system message that `summarize_and_compress` replaces older messages with
+const SUMMARY_PREFIX: &str = "[SUMMARY OF EARLIER CONVERSATION]";
+
+/// Share of `max_tokens` at which `needs_compression` suggests summarizing
+const COMPRESS_AT_FRACTION: f64 = 0.8;
+
+const SUMMARY_PROMPT: &str = "Summarize the conversation below so it can be continued without it. \
+Keep the user's goals, decisions made, facts learned, tool results that still matter, file names and open questions. \
+Reply with only the summary.";
+
+#[cfg(not(feature = "tiktoken"))]
+pub(crate) fn estimate_tokens(text: &str) -> usize {
+    // Roughly four characters per token for English text
+    text.len() / 4
+}
+
+#[cfg(feature = "tiktoken")]
+pub(crate) fn estimate_tokens(text: &str) -> usize {
+    tiktoken_rs::cl100k_base_singleton()
+        .lock()
+        .encode_with_special_tokens(text)
+        .len()
+}
+
+pub(crate) fn max_cost_from_env() -> Option<f64> {
+    std::env::var("MCP_MAX_COST_USD").ok().and_then(|v| v.parse().ok())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversationState {
     pub messages: Vec<Message>,
     pub system_prompt: String,
     pub tools: Vec<ToolInfo>,
+    /// Token budget for `messages`; older messages are trimmed once it is exceeded.
+    /// Defaults to `MCP_MAX_CONTEXT_TOKENS` when set.
+    #[serde(default)]
+    pub max_tokens: Option<usize>,
+    /// Tokens sent to and received from the AI over the whole conversation, estimated from
+    /// the text since the clients don't report usage
+    #[serde(default)]
+    pub total_tokens_in: usize,
+    #[serde(default)]
+    pub total_tokens_out: usize,
+    /// What those tokens cost, priced by the model of each call
+    #[serde(default)]
+    pub total_cost_usd: f64,
+    /// No more AI calls are made once `total_cost_usd` exceeds this, unless the user agrees.
+    /// Defaults to `MCP_MAX_COST_USD` when set.
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
 }
 
 impl ConversationState {
@@ -83,6 +144,11 @@ impl ConversationState {
             messages: Vec::new(),
             system_prompt: system_prompt.clone(),
             tools,
+            max_tokens: std::env::var("MCP_MAX_CONTEXT_TOKENS").ok().and_then(|v| v.parse().ok()),
+            total_tokens_in: 0,
+            total_tokens_out: 0,
+            total_cost_usd: 0.0,
+            max_cost_usd: max_cost_from_env(),
         };
 
         // Add the system prompt as the first system message
@@ -94,6 +160,7 @@ impl ConversationState {
         self.messages.push(Message {
             role: Role::System,
             content: content.to_string(),
+            metadata: None,
         });
     }
 
@@ -101,13 +168,357 @@ impl ConversationState {
         self.messages.push(Message {
             role: Role::User,
             content: content.to_string(),
+            metadata: None,
         });
+        self.enforce_token_limit();
     }
 
     pub fn add_assistant_message(&mut self, content: &str) {
         self.messages.push(Message {
             role: Role::Assistant,
             content: content.to_string(),
+            metadata: None,
+        });
+        self.enforce_token_limit();
+    }
+
+    /// An assistant turn written by `model`, as opposed to tool results recorded for it
+    pub fn add_model_message(&mut self, content: &str, model: String) {
+        self.messages.push(Message {
+            role: Role::Assistant,
+            content: content.to_string(),
+            metadata: Some(MessageMetadata { model }),
+        });
+        self.enforce_token_limit();
+    }
+
+    /// Estimated number of tokens across all messages
+    pub fn token_count(&self) -> usize {
+        self.messages.iter().map(|msg| estimate_tokens(&msg.content)).sum()
+    }
+
+    /// Count one AI call, given the messages it was sent and the reply, towards the totals
+    pub fn record_ai_call(&mut self, model: &str, sent: &[(Role, String)], reply: &str) {
+        let tokens_in = sent.iter().map(|(_, content)| estimate_tokens(content)).sum();
+        let tokens_out = estimate_tokens(reply);
+        self.total_tokens_in += tokens_in;
+        self.total_tokens_out += tokens_out;
+        self.total_cost_usd += CostEstimator::default().estimate_cost(model, tokens_in, tokens_out);
+    }
+
+    /// Whether the conversation has cost more than `max_cost_usd`
+    pub fn over_budget(&self) -> bool {
+        self.max_cost_usd.is_some_and(|max| self.total_cost_usd > max)
+    }
+
+    /// Allow another `MCP_MAX_COST_USD` of spending on top of what was spent so far
+    pub fn extend_budget(&mut self) {
+        if let Some(max) = max_cost_from_env().or(self.max_cost_usd) {
+            self.max_cost_usd = Some(self.total_cost_usd + max);
+        }
+    }
+
+    /// Drop the oldest non-system messages until the conversation fits in `max` tokens,
+    /// always keeping system messages and the most recent exchanges.
+    pub fn trim_to_fit(&mut self, max: usize) {
+        let mut total = self.token_count();
+        while total > max {
+            let non_system = self.messages.iter().filter(|msg| msg.role != Role::System).count();
+            if non_system <= KEEP_RECENT_MESSAGES {
+                break;
+            }
+            let Some(oldest) = self.messages.iter().position(|msg| msg.role != Role::System) else {
+                break;
+            };
+            let removed = self.messages.remove(oldest);
+            total -= estimate_tokens(&removed.content);
+        }
+
+        if total > max {
+            log::warn!("Conversation still uses ~{} tokens after trimming (limit {})", total, max);
+        }
+    }
+
+    /// Whether the conversation is close enough to `max_tokens` to summarize it before it
+    /// has to be trimmed
+    pub fn needs_compression(&self) -> bool {
+        self.max_tokens
+            .is_some_and(|max| self.token_count() as f64 >= max as f64 * COMPRESS_AT_FRACTION)
+    }
+
+    /// Replace all but the most recent messages, and any earlier summary, with a system message
+    /// holding a summary of them written by `client`. Other system messages are kept as they are.
+    pub async fn summarize_and_compress(&mut self, client: &dyn AIClient) -> anyhow::Result<()> {
+        let is_summary = |msg: &Message| msg.role == Role::System && msg.content.starts_with(SUMMARY_PREFIX);
+        let conversational: Vec<usize> = self.messages.iter()
+            .enumerate()
+            .filter(|(_, msg)| msg.role != Role::System)
+            .map(|(index, _)| index)
+            .collect();
+        let Some(keep_from) = conversational.len().checked_sub(KEEP_RECENT_MESSAGES)
+            .filter(|older| *older > 0)
+            .map(|older| conversational[older])
+        else {
+            return Ok(());
+        };
+
+        let compressed: Vec<usize> = (0..keep_from)
+            .filter(|&index| self.messages[index].role != Role::System || is_summary(&self.messages[index]))
+            .collect();
+        let transcript = compressed.iter()
+            .map(|&index| {
+                let msg = &self.messages[index];
+                let speaker = match msg.role {
+                    Role::System => "Earlier summary",
+                    Role::User => "User",
+                    Role::Assistant => "Assistant",
+                };
+                format!("{}: {}", speaker, msg.content.trim_start_matches(SUMMARY_PREFIX).trim_start())
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        let summary = client.raw_builder()
+            .system(SUMMARY_PROMPT.to_string())
+            .user(transcript.clone())
+            .execute()
+            .await?;
+        self.record_ai_call(
+            &client.model_name(),
+            &[(Role::System, SUMMARY_PROMPT.to_string()), (Role::User, transcript)],
+            &summary,
+        );
+
+        let before = self.token_count();
+        let position = compressed[0];
+        for &index in compressed.iter().rev() {
+            self.messages.remove(index);
+        }
+        self.messages.insert(position, Message {
+            role: Role::System,
+            content: format!("{}\n{}", SUMMARY_PREFIX, summary.trim()),
+            metadata: Some(MessageMetadata { model: client.model_name() }),
         });
+        log::info!(
+            "Summarized {} messages, the conversation went from ~{} to ~{} tokens",
+            compressed.len(), before, self.token_count()
+        );
+        Ok(())
+    }
+
+    fn enforce_token_limit(&mut self) {
+        if let Some(max) = self.max_tokens {
+            if self.token_count() > max {
+                self.trim_to_fit(max);
+            }
+        }
+    }
+
+    /// Write the conversation to `path` as JSON so it can be resumed later
+    pub fn save_to_file(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Restore a conversation previously written by `save_to_file`
+    pub fn load_from_file(path: &Path) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Render the conversation as Markdown for people to read. Tool calls found in assistant
+    /// messages are shown as JSON blocks under the tool's name, followed by their results.
+    pub fn to_markdown(&self) -> String {
+        let tool_names: Vec<String> = self.tools.iter().map(|t| t.name.clone()).collect();
+        let mut markdown = String::from("# Conversation\n\n");
+
+        for message in &self.messages {
+            let content = message.content.trim();
+            match message.role {
+                Role::System => {
+                    for line in content.lines() {
+                        markdown.push_str(format!("> {}", line).trim_end());
+                        markdown.push('\n');
+                    }
+                    markdown.push('\n');
+                }
+                Role::User => markdown.push_str(&format!("**User:** {}\n\n", content)),
+                Role::Assistant => {
+                    if let Some((tool_name, result)) = split_tool_result(content) {
+                        markdown.push_str(&format!("**Tool Result:** `{}`\n\n{}\n\n", tool_name, fenced(result)));
+                    } else if let Some((tool_name, args)) = split_native_tool_call(content) {
+                        markdown.push_str(&tool_call_markdown(tool_name, &args));
+                    } else {
+                        markdown.push_str(&format!("**Assistant:** {}\n\n", content));
+                        let (calls, _) = crate::conversation_service::parse_tool_calls(content, &tool_names);
+                        for (tool_name, args) in calls {
+                            markdown.push_str(&tool_call_markdown(&tool_name, &args));
+                        }
+                    }
+                }
+            }
+        }
+        markdown
+    }
+}
+
+/// `Tool '<name>' returned: <result>` or `Tool '<name>' error: <error>`, as recorded by
+/// `handle_assistant_response`
+pub(crate) fn split_tool_result(content: &str) -> Option<(&str, &str)> {
+    let rest = content.strip_prefix("Tool '")?;
+    rest.split_once("' returned: ").or_else(|| rest.split_once("' error: "))
+}
+
+/// `Calling tool '<name>' with arguments: <json>`, recorded for native tool calls
+pub(crate) fn split_native_tool_call(content: &str) -> Option<(&str, serde_json::Value)> {
+    let (tool_name, args) = content.strip_prefix("Calling tool '")?.split_once("' with arguments: ")?;
+    Some((tool_name, serde_json::from_str(args).ok()?))
+}
+
+fn tool_call_markdown(tool_name: &str, args: &serde_json::Value) -> String {
+    let args = serde_json::to_string_pretty(args).unwrap_or_default();
+    format!("#### {}\n\n```json\n{}\n```\n\n", tool_name, args)
+}
+
+/// A code block for `text`, fenced with more backticks than it contains in a row
+fn fenced(text: &str) -> String {
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    let language = if serde_json::from_str::<serde_json::Value>(text).is_ok() { "json" } else { "" };
+    format!("{fence}{language}\n{text}\n{fence}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai_client::{AIRequestBuilder, GenerationConfig, StreamResult};
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+
+    /// Answers every request with a fixed summary and keeps the transcript it was sent
+    struct SummaryClient {
+        transcript: Arc<Mutex<String>>,
+    }
+
+    struct SummaryBuilder {
+        transcript: Arc<Mutex<String>>,
+    }
+
+    #[async_trait]
+    impl AIRequestBuilder for SummaryBuilder {
+        fn system(self: Box<Self>, _content: String) -> Box<dyn AIRequestBuilder> {
+            self
+        }
+
+        fn user(self: Box<Self>, content: String) -> Box<dyn AIRequestBuilder> {
+            *self.transcript.lock().unwrap() = content;
+            self
+        }
+
+        fn user_with_image(self: Box<Self>, text: String, _image_path: &Path) -> anyhow::Result<Box<dyn AIRequestBuilder>> {
+            Ok(self.user(text))
+        }
+
+        fn user_with_image_url(self: Box<Self>, text: String, _image_url: String) -> Box<dyn AIRequestBuilder> {
+            self.user(text)
+        }
+
+        fn assistant(self: Box<Self>, _content: String) -> Box<dyn AIRequestBuilder> {
+            self
+        }
+
+        fn config(self: Box<Self>, _config: GenerationConfig) -> Box<dyn AIRequestBuilder> {
+            self
+        }
+
+        async fn execute(self: Box<Self>) -> anyhow::Result<String> {
+            Ok("the summary".to_string())
+        }
+
+        async fn execute_streaming(self: Box<Self>) -> anyhow::Result<StreamResult> {
+            Err(anyhow::anyhow!("not streamed"))
+        }
+
+        fn streaming(self: Box<Self>, _enabled: bool) -> Box<dyn AIRequestBuilder> {
+            self
+        }
+    }
+
+    impl SummaryClient {
+        fn new() -> Self {
+            Self { transcript: Arc::default() }
+        }
+    }
+
+    #[async_trait]
+    impl AIClient for SummaryClient {
+        fn builder(&self) -> Box<dyn AIRequestBuilder> {
+            self.raw_builder()
+        }
+
+        fn raw_builder(&self) -> Box<dyn AIRequestBuilder> {
+            Box::new(SummaryBuilder { transcript: Arc::clone(&self.transcript) })
+        }
+
+        fn model_name(&self) -> String {
+            "stub".to_string()
+        }
+    }
+
+    fn conversation(turns: usize) -> ConversationState {
+        let mut state = ConversationState::new("prompt".to_string(), Vec::new());
+        for turn in 0..turns {
+            state.add_user_message(&format!("question {}", turn));
+            state.add_assistant_message(&format!("answer {}", turn));
+        }
+        state
+    }
+
+    fn contents(state: &ConversationState) -> Vec<&str> {
+        state.messages.iter().map(|msg| msg.content.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn short_conversations_are_left_alone() {
+        let client = SummaryClient::new();
+        let mut state = conversation(2);
+        state.summarize_and_compress(&client).await.unwrap();
+        assert_eq!(state.messages.len(), 5);
+        assert!(client.transcript.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn older_messages_become_one_summary() {
+        let client = SummaryClient::new();
+        let mut state = conversation(3);
+        state.summarize_and_compress(&client).await.unwrap();
+        let summary = format!("{}\nthe summary", SUMMARY_PREFIX);
+        assert_eq!(contents(&state), [
+            "prompt", summary.as_str(), "question 1", "answer 1", "question 2", "answer 2",
+        ]);
+        assert_eq!(*client.transcript.lock().unwrap(), "User: question 0\n\nAssistant: answer 0");
+    }
+
+    #[tokio::test]
+    async fn earlier_summary_is_folded_in_and_system_messages_kept() {
+        let client = SummaryClient::new();
+        let mut state = conversation(3);
+        state.summarize_and_compress(&client).await.unwrap();
+        state.add_system_message("note");
+        state.add_user_message("question 3");
+        state.add_assistant_message("answer 3");
+        state.summarize_and_compress(&client).await.unwrap();
+
+        let summary = format!("{}\nthe summary", SUMMARY_PREFIX);
+        assert_eq!(contents(&state), [
+            "prompt", summary.as_str(), "question 2", "answer 2", "note", "question 3", "answer 3",
+        ]);
+        assert_eq!(
+            *client.transcript.lock().unwrap(),
+            "Earlier summary: the summary\n\nUser: question 1\n\nAssistant: answer 1"
+        );
     }
 }
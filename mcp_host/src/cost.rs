@@ -0,0 +1,93 @@
+/// USD per million tokens for models whose name starts with `model`
+#[derive(Debug, Clone, Copy)]
+pub struct ModelPrice {
+    pub model: &'static str,
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+const fn model_price(model: &'static str, input_per_million: f64, output_per_million: f64) -> ModelPrice {
+    ModelPrice { model, input_per_million, output_per_million }
+}
+
+/// List prices of common models. Dated or suffixed names such as `gpt-4o-2024-08-06` match
+/// the longest listed prefix, so `gpt-4o-mini` isn't billed as `gpt-4o`.
+const DEFAULT_PRICES: &[ModelPrice] = &[
+    model_price("gpt-4o-mini", 0.15, 0.60),
+    model_price("gpt-4o", 2.50, 10.00),
+    model_price("gpt-4-turbo", 10.00, 30.00),
+    model_price("gpt-4", 30.00, 60.00),
+    model_price("gpt-3.5-turbo", 0.50, 1.50),
+    model_price("o1-mini", 3.00, 12.00),
+    model_price("o1", 15.00, 60.00),
+    model_price("claude-3-opus", 15.00, 75.00),
+    model_price("claude-opus-4", 15.00, 75.00),
+    model_price("claude-3-sonnet", 3.00, 15.00),
+    model_price("claude-3-5-sonnet", 3.00, 15.00),
+    model_price("claude-3-7-sonnet", 3.00, 15.00),
+    model_price("claude-sonnet-4", 3.00, 15.00),
+    model_price("claude-3-haiku", 0.25, 1.25),
+    model_price("claude-3-5-haiku", 0.80, 4.00),
+    model_price("gemini-pro", 0.50, 1.50),
+    model_price("gemini-1.5-pro", 1.25, 5.00),
+    model_price("gemini-1.5-flash", 0.075, 0.30),
+    model_price("deepseek-chat", 0.27, 1.10),
+    model_price("deepseek-reasoner", 0.55, 2.19),
+];
+
+/// Turns token counts into what a call to a model cost
+#[derive(Debug, Clone)]
+pub struct CostEstimator {
+    prices: Vec<ModelPrice>,
+}
+
+impl Default for CostEstimator {
+    fn default() -> Self {
+        Self { prices: DEFAULT_PRICES.to_vec() }
+    }
+}
+
+impl CostEstimator {
+    pub fn price(&self, model: &str) -> Option<&ModelPrice> {
+        self.prices.iter()
+            .filter(|price| model.starts_with(price.model))
+            .max_by_key(|price| price.model.len())
+    }
+
+    /// Cost in USD. Models without a known price are counted as free.
+    pub fn estimate_cost(&self, model: &str, input_tokens: usize, output_tokens: usize) -> f64 {
+        let Some(price) = self.price(model) else {
+            log::debug!("No price known for model '{}', not counting its cost", model);
+            return 0.0;
+        };
+        (input_tokens as f64 * price.input_per_million + output_tokens as f64 * price.output_per_million) / 1_000_000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_listed_prefix_wins() {
+        let estimator = CostEstimator::default();
+        assert_eq!(estimator.price("gpt-4o-mini-2024-07-18").unwrap().model, "gpt-4o-mini");
+        assert_eq!(estimator.price("gpt-4o-2024-08-06").unwrap().model, "gpt-4o");
+        assert_eq!(estimator.price("gpt-4-0613").unwrap().model, "gpt-4");
+        assert_eq!(estimator.price("claude-3-5-sonnet-20241022").unwrap().model, "claude-3-5-sonnet");
+    }
+
+    #[test]
+    fn unknown_models_are_free() {
+        let estimator = CostEstimator::default();
+        assert!(estimator.price("llama-3").is_none());
+        assert_eq!(estimator.estimate_cost("llama-3", 1_000_000, 1_000_000), 0.0);
+    }
+
+    #[test]
+    fn cost_is_per_million_tokens() {
+        let estimator = CostEstimator::default();
+        let cost = estimator.estimate_cost("gpt-4o", 1_000_000, 500_000);
+        assert!((cost - 7.5).abs() < 1e-9, "{}", cost);
+    }
+}
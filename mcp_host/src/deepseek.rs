@@ -7,14 +7,15 @@ use async_openai::{
         ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestSystemMessageArgs,
         ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequest, 
         CreateChatCompletionRequestArgs, ChatCompletionResponseStream,
+        ChatCompletionTool, ChatCompletionToolChoiceOption, ChatCompletionToolType, FunctionObject,
     },
     Client,
 };
 use futures::StreamExt;
-use log::{debug, error};
+use log::debug;
 use serde_json::Value;
-use crate::ai_client::{AIClient, AIRequestBuilder, GenerationConfig, StreamResult};
-use shared_protocol_objects::Role;
+use crate::ai_client::{AIClient, AIRequestBuilder, AIResponse, GenerationConfig, StreamResult};
+use shared_protocol_objects::{Role, ToolInfo};
 use std::pin::Pin;
 use std::task::{Context as TaskContext, Poll};
 use futures::Stream;
@@ -58,6 +59,61 @@ impl AIClient for DeepSeekClient {
     fn raw_builder(&self) -> Box<dyn AIRequestBuilder> {
         self.builder()
     }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        true
+    }
+
+    async fn execute_with_tools(&self, messages: Vec<(Role, String)>, tools: &[ToolInfo]) -> Result<AIResponse> {
+        let client = self.create_inner_client().await;
+        let mut request = build_deepseek_request(&self.model, &messages, None, /* streaming */ false)?;
+        // The API rejects an empty tool list
+        if !tools.is_empty() {
+            request.tools = Some(tools.iter().map(to_openai_tool).collect());
+            request.tool_choice = Some(ChatCompletionToolChoiceOption::Auto);
+        }
+
+        let response = client.chat().create(request).await?;
+        let message = response.choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message)
+            .context("DeepSeek returned no choices")?;
+
+        let mut tool_calls = message.tool_calls.unwrap_or_default().into_iter();
+        if let Some(call) = tool_calls.next() {
+            let skipped = tool_calls.count();
+            if skipped > 0 {
+                debug!("Running only the first of {} tool calls, the model can ask for the rest next turn", skipped + 1);
+            }
+            let arguments: Value = serde_json::from_str(&call.function.arguments)
+                .with_context(|| format!("Invalid tool arguments: {}", call.function.arguments))?;
+            return Ok(AIResponse::ToolCall {
+                name: call.function.name,
+                id: call.id,
+                arguments,
+            });
+        }
+
+        Ok(AIResponse::Text(message.content.unwrap_or_default()))
+    }
+}
+
+/// An MCP tool as an OpenAI-style function definition
+fn to_openai_tool(tool: &ToolInfo) -> ChatCompletionTool {
+    ChatCompletionTool {
+        r#type: ChatCompletionToolType::Function,
+        function: FunctionObject {
+            name: tool.name.clone(),
+            description: tool.description.clone(),
+            parameters: Some(tool.input_schema.clone()),
+            strict: None,
+        },
+    }
 }
 
 /// A builder struct implementing `AIRequestBuilder` for DeepSeek
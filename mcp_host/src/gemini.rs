@@ -6,7 +6,54 @@ use async_trait::async_trait;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use std::path::Path;
 use std::fs;
-use crate::ai_client::StreamResult;
+use crate::ai_client::{StreamEvent, StreamResult};
+use futures::StreamExt;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+/// Text chunks buffered between the response reader and a slow consumer
+const STREAM_CHANNEL_CAPACITY: usize = 32;
+
+/// The first complete event in `buffer` and what follows it. Events end with a blank line,
+/// with either LF or CRLF line endings.
+fn split_sse_event(buffer: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (end, separator_len) = buffer.iter().enumerate().find_map(|(i, _)| {
+        let rest = &buffer[i..];
+        if rest.starts_with(b"\n\n") {
+            Some((i, 2))
+        } else if rest.starts_with(b"\r\n\r\n") {
+            Some((i, 4))
+        } else {
+            None
+        }
+    })?;
+    Some((&buffer[..end], &buffer[end + separator_len..]))
+}
+
+/// The text of the partial candidate in one SSE event, each a `GenerateContentResponse`.
+/// None for events without text, such as the last one carrying only the finish reason.
+fn sse_event_text(event: &str) -> Option<Result<String>> {
+    let data: String = event.lines()
+        .filter_map(|line| line.trim_end_matches('\r').strip_prefix("data:"))
+        .map(str::trim_start)
+        .collect();
+    if data.is_empty() {
+        return None;
+    }
+    let chunk: serde_json::Value = match serde_json::from_str(&data) {
+        Ok(chunk) => chunk,
+        Err(e) => return Some(Err(anyhow::anyhow!("Invalid streamed chunk from Gemini: {}: {}", e, data))),
+    };
+    if let Some(error) = chunk.get("error") {
+        return Some(Err(anyhow::anyhow!("Gemini API error: {}", error)));
+    }
+    let text: String = chunk.pointer("/candidates/0/content/parts")?
+        .as_array()?
+        .iter()
+        .filter_map(|part| part["text"].as_str())
+        .collect();
+    (!text.is_empty()).then_some(Ok(text))
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeminiContentPart {
@@ -87,6 +134,57 @@ impl GeminiClient {
         }
     }
 
+    /// Send `request` to `streamGenerateContent` with `alt=sse` and pass the text of each
+    /// partial candidate on as it arrives. The channel closes once the response is complete;
+    /// an error ends it early.
+    pub async fn stream_execute(&self, request: &GeminiRequest) -> Result<mpsc::Receiver<Result<String>>> {
+        debug!("Sending streaming request to Gemini API");
+        let response = reqwest::Client::new()
+            .post(format!("{}?alt=sse", self.endpoint))
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", &self.api_key))
+            .json(request)
+            .send()
+            .await?;
+
+        debug!("Response received, status: {}", response.status());
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.context("Failed to read error response")?;
+            error!("API error response: {}", error_text);
+            return Err(anyhow::anyhow!("API request failed with status {}: {}", status, error_text));
+        }
+
+        let (tx, rx) = mpsc::channel(STREAM_CHANNEL_CAPACITY);
+        let mut body = response.bytes_stream();
+        tokio::spawn(async move {
+            // Bytes rather than text, since a chunk may end inside a multi-byte character
+            let mut buffer: Vec<u8> = Vec::new();
+            while let Some(chunk) = body.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        let _ = tx.send(Err(e.into())).await;
+                        return;
+                    }
+                };
+                buffer.extend_from_slice(&chunk);
+                while let Some((event, rest)) = split_sse_event(&buffer) {
+                    let text = sse_event_text(&String::from_utf8_lossy(event));
+                    buffer = rest.to_vec();
+                    let failed = matches!(text, Some(Err(_)));
+                    if let Some(text) = text {
+                        if tx.send(text).await.is_err() || failed {
+                            // Nobody is listening any more, or the API reported an error
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+        Ok(rx)
+    }
+
     fn default_safety_settings() -> Vec<SafetySetting> {
         vec![
             SafetySetting {
@@ -139,6 +237,10 @@ impl<'a> AIClient for GeminiClient {
             stream: false,
         })
     }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -158,41 +260,14 @@ impl AIRequestBuilder for GeminiCompletionBuilder {
     }
 
     async fn execute_streaming(self: Box<Self>) -> Result<StreamResult> {
-        let mut config = self.generation_config.unwrap_or_default();
-        if config.top_p.is_none() {
-            config.top_p = Some(0.95);
-        }
-    
-        let request = GeminiRequest {
-            contents: self.contents,
-            system_instruction: self.system_instruction.clone(),
-            generation_config: Some(config),
-            safety_settings: Some(GeminiClient::default_safety_settings()),
-        };
-    
-        debug!("Sending streaming request to Gemini API");
-        let client = reqwest::Client::new();
-        let response = client
-            .post(&self.client.endpoint)
-            .header("Content-Type", "application/json") 
-            .header("Authorization", format!("Bearer {}", &self.client.api_key))
-            .json(&request)
-            .send()
-            .await?;
-    
-        debug!("Response received, status: {}", response.status());
-        let status = response.status().clone();
-        let status_two = response.status().clone();
-        if !status.is_success() {
-            let error_text = response.text().await?;
-            error!("API error response: {}", error_text);
-            return Err(anyhow::anyhow!("API request failed with status {}: {}", 
-            status_two, error_text));
-        }
-
-        let stream = response.bytes_stream();
-        Ok(crate::streaming::parse_sse_stream(stream))
+        let client = self.client.clone();
+        let chunks = client.stream_execute(&self.into_request()).await?;
+        let events = ReceiverStream::new(chunks)
+            .map(|chunk| chunk.map(|text| StreamEvent::ContentDelta { index: 0, text }))
+            .chain(futures::stream::once(async { Ok(StreamEvent::MessageStop) }));
+        Ok(Box::pin(events))
     }
+
     fn system(mut self: Box<Self>, content: String) -> Box<dyn AIRequestBuilder> {
         let system_instruction = GeminiSystemInstruction {
             parts: vec![GeminiContentPart {
@@ -281,24 +356,15 @@ impl AIRequestBuilder for GeminiCompletionBuilder {
     }
 
     async fn execute(self: Box<Self>) -> Result<String> {
-        let mut config = self.generation_config.unwrap_or_default();
-        if config.top_p.is_none() {
-            config.top_p = Some(0.95);
-        }
-    
-        let request = GeminiRequest {
-            contents: self.contents,
-            system_instruction: self.system_instruction.clone(),
-            generation_config: Some(config),
-            safety_settings: Some(GeminiClient::default_safety_settings()),
-        };
+        let gemini = self.client.clone();
+        let request = self.into_request();
     
         debug!("Sending request to Gemini API");
         let client = reqwest::Client::new();
         let response = client
-            .post(&self.client.endpoint)
+            .post(&gemini.endpoint)
             .header("Content-Type", "application/json") 
-            .header("Authorization", format!("Bearer {}", &self.client.api_key))
+            .header("Authorization", format!("Bearer {}", &gemini.api_key))
             .json(&request)
             .send()
             .await?;
@@ -345,6 +411,19 @@ impl AIRequestBuilder for GeminiCompletionBuilder {
 }
 
 impl GeminiCompletionBuilder {
+    fn into_request(self) -> GeminiRequest {
+        let mut config = self.generation_config.unwrap_or_default();
+        if config.top_p.is_none() {
+            config.top_p = Some(0.95);
+        }
+        GeminiRequest {
+            contents: self.contents,
+            system_instruction: self.system_instruction,
+            generation_config: Some(config),
+            safety_settings: Some(GeminiClient::default_safety_settings()),
+        }
+    }
+
     // pub fn user(mut self, content: impl Into<String>) -> Self {
     //     let c = content.into();
     //     debug!("GeminiCompletionBuilder: Adding user message: {}", c);
@@ -445,3 +524,48 @@ impl GeminiCompletionBuilder {
         Ok(content.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_events_on_blank_lines() {
+        let (event, rest) = split_sse_event(b"data: 1\n\ndata: 2").unwrap();
+        assert_eq!(event, b"data: 1");
+        assert_eq!(rest, b"data: 2");
+        // The second event isn't complete yet
+        assert!(split_sse_event(rest).is_none());
+    }
+
+    #[test]
+    fn splits_crlf_events() {
+        let (event, rest) = split_sse_event(b"data: 1\r\n\r\ndata: 2\r\n\r\n").unwrap();
+        assert_eq!(event, b"data: 1");
+        assert_eq!(split_sse_event(rest).unwrap(), (&b"data: 2"[..], &b""[..]));
+    }
+
+    #[test]
+    fn event_text_joins_the_parts_of_the_first_candidate() {
+        let event = r#"data: {"candidates":[{"content":{"parts":[{"text":"Hel"},{"text":"lo"}]}}]}"#;
+        assert_eq!(sse_event_text(event).unwrap().unwrap(), "Hello");
+    }
+
+    #[test]
+    fn event_data_may_span_lines() {
+        let event = "data: {\"candidates\":[{\"content\":\r\ndata: {\"parts\":[{\"text\":\"hi\"}]}}]}";
+        assert_eq!(sse_event_text(event).unwrap().unwrap(), "hi");
+    }
+
+    #[test]
+    fn events_without_text_are_skipped() {
+        assert!(sse_event_text(": keepalive").is_none());
+        assert!(sse_event_text(r#"data: {"candidates":[{"finishReason":"STOP"}]}"#).is_none());
+    }
+
+    #[test]
+    fn errors_and_bad_json_are_reported() {
+        assert!(sse_event_text(r#"data: {"error":{"code":429}}"#).unwrap().is_err());
+        assert!(sse_event_text("data: {not json").unwrap().is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
+use log::{debug, error, info, warn};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use shared_protocol_objects::{JsonRpcRequest, JsonRpcResponse, ServerCapabilities};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// An MCP server running as a standalone HTTP service. Requests are POSTed to
+/// `{base_url}/jsonrpc`, and unsolicited notifications arrive over SSE from `{base_url}/events`.
+#[derive(Debug)]
+#[allow(dead_code)]
+pub struct HttpManagedServer {
+    pub name: String,
+    pub base_url: String,
+    headers: HeaderMap,
+    client: reqwest::Client,
+    pub capabilities: Option<ServerCapabilities>,
+    pub initialized: bool,
+    pub tool_timeouts: HashMap<String, u64>,
+    pub default_timeout_secs: Option<u64>,
+    events: JoinHandle<()>,
+}
+
+impl HttpManagedServer {
+    pub fn new(
+        name: &str,
+        base_url: &str,
+        headers: &HashMap<String, String>,
+        tool_timeouts: HashMap<String, u64>,
+        default_timeout_secs: Option<u64>,
+    ) -> Result<Self> {
+        let mut header_map = HeaderMap::new();
+        for (key, value) in headers {
+            header_map.insert(HeaderName::try_from(key.as_str())?, HeaderValue::try_from(value.as_str())?);
+        }
+
+        let base_url = base_url.trim_end_matches('/').to_string();
+        let client = reqwest::Client::new();
+        let events = tokio::spawn(listen_for_events(
+            name.to_string(),
+            client.clone(),
+            format!("{}/events", base_url),
+            header_map.clone(),
+        ));
+
+        Ok(HttpManagedServer {
+            name: name.to_string(),
+            base_url,
+            headers: header_map,
+            client,
+            capabilities: None,
+            initialized: false,
+            tool_timeouts,
+            default_timeout_secs,
+            events,
+        })
+    }
+
+    /// Build the POST for `request` up front so the caller does not have to hold the
+    /// server map lock while waiting for the response.
+    pub fn send_request(
+        &self,
+        request: &JsonRpcRequest,
+        request_timeout: Duration,
+    ) -> impl Future<Output = Result<JsonRpcResponse>> + Send + 'static {
+        let id = request.id.clone();
+        let builder = self.client
+            .post(format!("{}/jsonrpc", self.base_url))
+            .headers(self.headers.clone())
+            .timeout(request_timeout)
+            .json(request);
+
+        async move {
+            let response = builder.send().await.map_err(|e| {
+                if e.is_timeout() {
+                    anyhow!("Request timed out")
+                } else {
+                    anyhow!("HTTP request failed: {}", e)
+                }
+            })?;
+
+            let status = response.status();
+            let body = response.text().await?;
+            if !status.is_success() {
+                return Err(anyhow!("Server returned {}: {}", status, body));
+            }
+            debug!("DEBUG: Received response: {}", body.trim());
+
+            // Notifications are typically acknowledged with an empty 202
+            if body.trim().is_empty() {
+                return Ok(JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    id,
+                    result: None,
+                    error: None,
+                });
+            }
+
+            serde_json::from_str(&body)
+                .map_err(|e| anyhow!("Failed to parse response '{}': {}", body.trim(), e))
+        }
+    }
+}
+
+impl Drop for HttpManagedServer {
+    fn drop(&mut self) {
+        self.events.abort();
+    }
+}
+
+/// Read the server's SSE stream and log each notification it pushes. Log messages go to the
+/// host log at their own level.
+async fn listen_for_events(name: String, client: reqwest::Client, url: String, headers: HeaderMap) {
+    let response = match client.get(&url).headers(headers).header("Accept", "text/event-stream").send().await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            warn!("Server '{}' did not open an event stream at {}: {}", name, url, response.status());
+            return;
+        }
+        Err(e) => {
+            warn!("Failed to connect to event stream of server '{}': {}", name, e);
+            return;
+        }
+    };
+
+    let mut stream = response.bytes_stream();
+    let mut buffer = String::new();
+    while let Some(chunk) = stream.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                error!("Event stream of server '{}' failed: {}", name, e);
+                return;
+            }
+        };
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+        // Events are separated by a blank line; keep any partial event for the next chunk
+        while let Some(end) = buffer.find("\n\n") {
+            let event: String = buffer.drain(..end + 2).collect();
+            let data = event.lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(str::trim_start)
+                .collect::<Vec<_>>()
+                .join("\n");
+            if data.is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str::<serde_json::Value>(&data) {
+                Ok(message) => {
+                    let method = message.get("method").and_then(|m| m.as_str()).unwrap_or("unknown");
+                    if method == "notifications/message" {
+                        crate::server_logging::log_server_message(&name, message.get("params").unwrap_or(&serde_json::Value::Null));
+                        continue;
+                    }
+                    if method == "notifications/progress" {
+                        crate::progress::route(&name, message.get("params").unwrap_or(&serde_json::Value::Null));
+                        continue;
+                    }
+                    info!("Notification from server '{}': {} {}", name, method, message.get("params").unwrap_or(&serde_json::Value::Null));
+                }
+                Err(e) => warn!("Ignoring malformed event from server '{}': {}", name, e),
+            }
+        }
+    }
+    info!("Event stream of server '{}' closed", name);
+}
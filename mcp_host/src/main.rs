@@ -3,6 +3,7 @@ use console::{style, Term};
 use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::fs;
+use std::path::Path;
 use serde::{Deserialize, Serialize};
 
 use crate::conversation_service::handle_assistant_response;
@@ -12,33 +13,78 @@ mod anthropic;
 mod deepseek;
 mod gemini;
 mod streaming;
+mod http_transport;
 mod conversation_service;
+mod sampling;
+mod tool_chain;
+mod telemetry;
+mod circuit_breaker;
+mod server_logging;
+mod audit_log;
+mod result_format;
+mod tool_activity;
+mod progress;
+mod cost;
 mod my_regex;
+mod stdio_transport;
 
 use crate::my_regex::build_tool_call_regex;
+use crate::result_format::OutputFormat;
+use crate::tool_activity::{ServerActivity, ToolCounters};
+use crate::progress::ProgressListener;
+use crate::stdio_transport::{ReaderContext, StdioConnection};
 
 
 
 
 use crate::deepseek::DeepSeekClient;
+use crate::http_transport::HttpManagedServer;
 
 use log::{info,warn};
 use tokio::time::Duration;
 
-#[derive(Debug, Deserialize, Serialize)]
+/// How the host talks to a server. Configs with a `command` start a child process, configs
+/// with a `url` connect to an already running HTTP service.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+enum TransportKind {
+    Stdio {
+        command: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+        #[serde(default)]
+        env: HashMap<String, String>,
+    },
+    Http {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 struct ServerConfig {
-    command: String,
+    #[serde(flatten)]
+    transport: TransportKind,
+    /// Per-tool `tools/call` timeouts in seconds, keyed by tool name
+    #[serde(default)]
+    tool_timeouts: HashMap<String, u64>,
+    /// Timeout in seconds for tools missing from `tool_timeouts`
     #[serde(default)]
-    env: HashMap<String, String>,
+    default_timeout_secs: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 struct Config {
     #[serde(rename = "mcpServers")]
     servers: HashMap<String, ServerConfig>,
+    /// Named lists of servers that can be started, stopped and queried together
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    groups: HashMap<String, Vec<String>>,
 }
 
-use ai_client::AIClient;
+use ai_client::{AIClient, AIClientFactory, AIResponse, SharedAIClient};
+use circuit_breaker::CircuitBreaker;
 
 
 mod conversation_state;
@@ -48,16 +94,28 @@ use anyhow::anyhow;
 use log::{error,debug};
 use std::process::{Child, Command, Stdio};
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::{ChildStdin, ChildStdout};
 use tokio::sync::Mutex;
 use tokio::sync::mpsc;
-use tokio::time::timeout;
+use futures::future::{BoxFuture, FutureExt, Shared};
 use uuid::Uuid;
 use regex::Regex;
 use lazy_static::lazy_static;
 
 async fn with_progress<F, T>(msg: String, future: F) -> T 
+where
+    F: std::future::Future<Output = T>,
+{
+    with_live_progress(msg, None, future).await
+}
+
+/// `with_progress`, also showing the latest of the progress `updates` after the message
+async fn with_live_progress<F, T>(
+    msg: String,
+    mut updates: Option<mpsc::Receiver<shared_protocol_objects::ProgressNotification>>,
+    future: F,
+) -> T
 where
     F: std::future::Future<Output = T>,
 {
@@ -70,9 +128,14 @@ where
     let progress_term = term.clone();
     
     let handle = tokio::spawn(async move {
+        let mut status = String::new();
         loop {
+            while let Some(update) = updates.as_mut().and_then(|updates| updates.try_recv().ok()) {
+                status = format_progress(&update);
+            }
             // Write the spinner and message, staying on same line
-            progress_term.write_str(&format!("\r{} {}", spinner[i], progress_msg))
+            progress_term.clear_line().unwrap_or_default();
+            progress_term.write_str(&format!("\r{} {}{}", spinner[i], progress_msg, status))
                 .unwrap_or_default();
             // Ensure the line is flushed
             progress_term.flush().unwrap_or_default();
@@ -89,6 +152,63 @@ where
     result
 }
 
+/// A progress update as shown after the spinner message, e.g. ` [3/10] Fetching page`
+fn format_progress(update: &shared_protocol_objects::ProgressNotification) -> String {
+    let mut status = match update.total {
+        Some(total) => format!(" [{}/{}]", update.progress, total),
+        None => format!(" [{}]", update.progress),
+    };
+    if let Some(message) = update.message.as_deref().map(str::trim).filter(|message| !message.is_empty()) {
+        // Long lines would wrap and break the redraw
+        let message: String = message.chars().take(PROGRESS_MESSAGE_WIDTH).collect();
+        status.push(' ');
+        status.push_str(&message);
+    }
+    status
+}
+
+/// Characters of a progress message shown next to the spinner
+const PROGRESS_MESSAGE_WIDTH: usize = 60;
+
+/// Tell the user the chat went over its cost budget and ask whether to keep going. Going on
+/// allows the same budget again.
+fn confirm_over_budget(state: &mut ConversationState) -> Result<bool> {
+    println!(
+        "\n{} this conversation has cost about ${:.4} ({} tokens in, {} out), over the budget of ${:.4}.",
+        style("Budget reached:").red().bold(),
+        state.total_cost_usd,
+        state.total_tokens_in,
+        state.total_tokens_out,
+        state.max_cost_usd.unwrap_or_default()
+    );
+    println!("Continue anyway? [y/N]");
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    let go_on = matches!(answer.trim(), "y" | "Y" | "yes");
+    if go_on {
+        state.extend_budget();
+    }
+    Ok(go_on)
+}
+
+/// Stream a response to stdout chunk by chunk, returning the full text once done
+async fn stream_to_stdout(client: &dyn AIClient, messages: Vec<(Role, String)>) -> Result<String> {
+    use futures::StreamExt;
+    use std::io::Write;
+
+    let mut chunks = client.stream(messages).await?;
+    print!("\n{}: ", style("Assistant").cyan().bold());
+    let mut full = String::new();
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk?;
+        print!("{}", chunk);
+        io::stdout().flush()?;
+        full.push_str(&chunk);
+    }
+    println!();
+    Ok(full)
+}
+
 // Helper functions for parsing tool calls
 fn extract_json_after_position(text: &str, pos: usize) -> Option<Value> {
     if let Some(json_start) = text[pos..].find('{') {
@@ -180,8 +300,8 @@ fn infer_tool_from_json(json: &Value) -> Option<(String, Value)> {
 
 
 use shared_protocol_objects::{
-    JsonRpcRequest, JsonRpcResponse, ServerCapabilities, Implementation,
-    ToolInfo, CallToolResult, RequestId, ListToolsResult, Role
+    create_notification, CallToolParams, JsonRpcRequest, JsonRpcResponse, ServerCapabilities, Implementation,
+    ToolInfo, CallToolResult, RequestId, ListToolsResult, Role, ToolResponseContent
 };
 
 // Server Management Types
@@ -190,17 +310,322 @@ use shared_protocol_objects::{
 struct ManagedServer {
     name: String, 
     process: Child,
-    stdin: Arc<Mutex<ChildStdin>>,
-    stdout: Arc<Mutex<ChildStdout>>,
+    connection: Arc<StdioConnection>,
     capabilities: Option<ServerCapabilities>,
     initialized: bool,
+    missed_pings: u32,
+    tool_timeouts: HashMap<String, u64>,
+    default_timeout_secs: Option<u64>,
+    /// Command the server was started with, re-run if the process crashes
+    restart_cmd: Command,
+    restart_times: Vec<std::time::Instant>,
+    permanently_failed: bool,
+}
+
+/// How often server processes are checked for crashes
+const MONITOR_INTERVAL: Duration = Duration::from_secs(5);
+/// Restarts allowed within `RESTART_WINDOW` before a server is given up on
+const MAX_RESTARTS: usize = 3;
+const RESTART_WINDOW: Duration = Duration::from_secs(60);
+/// How long a stopped server gets to exit on its own before it is killed
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+
+lazy_static! {
+    static ref ENV_VAR_REGEX: Regex =
+        Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}|\$([A-Za-z_][A-Za-z0-9_]*)").unwrap();
+}
+
+/// Replace `$VAR` and `${VAR}` with environment values and a leading `~` with the home
+/// directory. Undefined variables expand to an empty string.
+fn expand_env(s: &str) -> String {
+    let expanded = ENV_VAR_REGEX.replace_all(s, |caps: &regex::Captures| {
+        let name = caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str());
+        std::env::var(name).unwrap_or_else(|_| {
+            warn!("Environment variable {} is not set, expanding '{}' to an empty string", name, &caps[0]);
+            String::new()
+        })
+    });
+
+    match expanded.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            let home = std::env::var("HOME").unwrap_or_else(|_| {
+                warn!("HOME is not set, expanding '~' to an empty string");
+                String::new()
+            });
+            format!("{}{}", home, rest)
+        }
+        _ => expanded.into_owned(),
+    }
+}
+
+/// Spawn `command` and take ownership of its stdin/stdout for JSON-RPC traffic
+fn spawn_server_process(name: &str, command: &mut Command, context: ReaderContext) -> Result<(Child, Arc<StdioConnection>)> {
+    let mut child = command.spawn()?;
+    let stdin = ChildStdin::from_std(child.stdin.take().expect("Failed to get stdin"))?;
+    let stdout = ChildStdout::from_std(child.stdout.take().expect("Failed to get stdout"))?;
+    Ok((child, Arc::new(StdioConnection::start(name, stdin, stdout, context))))
+}
+
+/// How often each initialized server is pinged
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+/// How long to wait for the answer to a ping before counting it as missed
+const PONG_TIMEOUT: Duration = Duration::from_secs(10);
+/// Consecutive missed pings after which a server is considered dead
+const MAX_MISSED_PINGS: u32 = 3;
+
+/// Background task that sends a `ping` request to every initialized server and marks
+/// servers that stop answering as uninitialized. Pings share the server's connection with
+/// other requests, so a busy server is pinged like an idle one.
+async fn run_keepalive(servers: Arc<Mutex<HashMap<String, ManagedServer>>>) {
+    let mut interval = tokio::time::interval(KEEPALIVE_INTERVAL);
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        let targets: Vec<_> = {
+            let servers = servers.lock().await;
+            servers.iter()
+                .filter(|(_, server)| server.initialized)
+                .map(|(name, server)| (name.clone(), Arc::clone(&server.connection)))
+                .collect()
+        };
+
+        for (name, connection) in targets {
+            let ping = JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: RequestId::String(Uuid::new_v4().to_string()).into(),
+                method: "ping".to_string(),
+                params: None,
+            };
+            // Any answer, even an error, shows the server is alive
+            let answered = connection.request(&ping, PONG_TIMEOUT).await.is_ok();
+
+            let mut servers = servers.lock().await;
+            let Some(server) = servers.get_mut(&name) else { continue };
+            if answered {
+                server.missed_pings = 0;
+            } else {
+                server.missed_pings += 1;
+                warn!("Server '{}' missed keepalive ping ({}/{})", name, server.missed_pings, MAX_MISSED_PINGS);
+                if server.missed_pings >= MAX_MISSED_PINGS && server.initialized {
+                    server.initialized = false;
+                    error!("Server '{}' did not answer {} consecutive pings, marking it as not initialized", name, MAX_MISSED_PINGS);
+                }
+            }
+        }
+    }
+}
+
+type CoalescedResponse = Shared<BoxFuture<'static, std::result::Result<JsonRpcResponse, Arc<anyhow::Error>>>>;
+
+/// Key identifying identical requests: `server_name::method::params_hash`
+fn coalesce_key(server_name: &str, request: &JsonRpcRequest) -> String {
+    format!("{}::{}::{:x}", server_name, request.method, params_hash(request))
+}
+
+fn params_hash(request: &JsonRpcRequest) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    serde_json::to_string(&request.params).unwrap_or_default().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Tool lists per server and when they were fetched, see `list_server_tools`
+type CapabilityCache = Arc<Mutex<HashMap<String, (std::time::Instant, Vec<ToolInfo>)>>>;
+
+/// How long a cached tool list is used, unless `MCP_CAPABILITY_CACHE_TTL_SECS` is set
+const DEFAULT_CAPABILITY_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// The method of a notification, either a plain JSON-RPC one or the null-id response with a
+/// `result.method` that mcp_tools wraps its notifications in
+fn notification_method(message: &Value) -> Option<&str> {
+    if !message.get("id").is_none_or(Value::is_null) {
+        return None;
+    }
+    message.get("method")
+        .or_else(|| message.get("result").and_then(|result| result.get("method")))
+        .and_then(Value::as_str)
+}
+
+/// The params of a notification, in either of the forms `notification_method` accepts
+fn notification_params(message: &Value) -> &Value {
+    message.get("params")
+        .or_else(|| message.get("result").and_then(|result| result.get("params")))
+        .unwrap_or(&Value::Null)
+}
+
+/// The tool name and arguments of a `tools/call` request
+fn tool_call_of(request: &JsonRpcRequest) -> Option<(String, Value)> {
+    if request.method != "tools/call" {
+        return None;
+    }
+    let params = request.params.as_ref()?;
+    let tool = params.get("name")?.as_str()?.to_string();
+    Some((tool, params.get("arguments").cloned().unwrap_or(Value::Null)))
+}
+
+/// Send `request` to the named server over whichever transport it uses. Takes owned handles
+/// so the returned future is `'static` and can be shared between callers.
+async fn dispatch_request(
+    servers: Arc<Mutex<HashMap<String, ManagedServer>>>,
+    http_servers: Arc<Mutex<HashMap<String, HttpManagedServer>>>,
+    server_name: String,
+    request: JsonRpcRequest,
+    request_timeout: std::time::Duration,
+) -> Result<JsonRpcResponse> {
+    let request_id = match &request.id {
+        Value::String(id) => id.clone(),
+        id => id.to_string(),
+    };
+    let attributes = [
+        ("mcp.server", server_name.clone()),
+        ("mcp.method", request.method.clone()),
+        ("mcp.request_id", request_id.clone()),
+    ];
+    let name = format!("send_request {}", request.method);
+    let method = request.method.clone();
+    let params_hash = params_hash(&request);
+    let tool_call = tool_call_of(&request);
+
+    let started = std::time::Instant::now();
+    let send = send_to_server(servers, http_servers, server_name.clone(), request, request_timeout);
+    let result = telemetry::in_span(name, &attributes, send).await;
+    let tool_call = tool_call.as_ref().map(|(tool, arguments)| (tool.as_str(), arguments));
+    audit_log::record(&server_name, &method, &request_id, params_hash, tool_call, &result, started.elapsed());
+    result
+}
+
+async fn send_to_server(
+    servers: Arc<Mutex<HashMap<String, ManagedServer>>>,
+    http_servers: Arc<Mutex<HashMap<String, HttpManagedServer>>>,
+    server_name: String,
+    request: JsonRpcRequest,
+    request_timeout: std::time::Duration,
+) -> Result<JsonRpcResponse> {
+    debug!("\n=== Starting send_request ===");
+    debug!("Server: {}", server_name);
+    debug!("Request method: {}", request.method);
+
+    // HTTP servers answer each POST directly, no pipes to coordinate
+    let http_request = {
+        let http_servers = http_servers.lock().await;
+        http_servers.get(&server_name).map(|server| server.send_request(&request, request_timeout))
+    };
+    if let Some(http_request) = http_request {
+        return http_request.await;
+    }
+
+    let connection = {
+        let servers = servers.lock().await;
+        let server = servers.get(&server_name)
+            .ok_or_else(|| anyhow::anyhow!("Server not found: {}", server_name))?;
+        Arc::clone(&server.connection)
+    };
+    connection.request(&request, request_timeout).await
+}
+
+/// Attempts `send_request_with_retry` callers make before giving up on a transient failure
+const REQUEST_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// Whether a failed request is worth sending again: it timed out or the pipe or connection
+/// broke. Errors the server answered with, such as -32600 to -32603, arrive as responses and
+/// are never retried.
+fn is_transient_error(error: &anyhow::Error) -> bool {
+    const TRANSIENT: [&str; 6] = [
+        "Request timed out",
+        "Response channel closed",
+        "Failed to write to stdin",
+        "Failed to flush stdin",
+        "Failed to read response",
+        "HTTP request failed",
+    ];
+    let message = error.to_string();
+    TRANSIENT.iter().any(|prefix| message.starts_with(prefix))
+}
+
+/// Exponential backoff from `RETRY_BASE_DELAY` up to `RETRY_MAX_DELAY`. Half of the delay is
+/// random so concurrent retries don't hit the server at the same moment.
+fn backoff_delay(retry: u32) -> Duration {
+    let delay = RETRY_BASE_DELAY.saturating_mul(1 << retry.min(16)).min(RETRY_MAX_DELAY);
+    let half_millis = delay.as_millis() as u64 / 2;
+    let jitter = (Uuid::new_v4().as_u128() % (half_millis as u128 + 1)) as u64;
+    Duration::from_millis(half_millis + jitter)
+}
+
+/// Whether a failed request never reached the server or the connection to it broke. Unlike
+/// a timeout, this doesn't mean the server may still be working on it.
+fn is_connection_error(error: &anyhow::Error) -> bool {
+    is_transient_error(error) && !error.to_string().starts_with("Request timed out")
+}
+
+async fn retry_with_backoff<F, Fut, T>(max_attempts: u32, attempt: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    retry_with_backoff_if(max_attempts, is_transient_error, attempt).await
+}
+
+/// `retry_with_backoff` for the failures `should_retry` accepts
+async fn retry_with_backoff_if<F, Fut, T>(
+    max_attempts: u32,
+    should_retry: fn(&anyhow::Error) -> bool,
+    mut attempt: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let mut retry = 0;
+    loop {
+        match attempt().await {
+            Err(e) if retry + 1 < max_attempts && should_retry(&e) => {
+                let delay = backoff_delay(retry);
+                warn!("Request failed ({}), retrying in {:?}", e, delay);
+                tokio::time::sleep(delay).await;
+                retry += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// A client for `provider`, with the API key taken from `<PROVIDER>_API_KEY`, e.g.
+/// `ANTHROPIC_API_KEY`. Without a model the provider's default is used.
+fn create_ai_client(provider: &str, model: Option<&str>) -> Result<Arc<dyn AIClient>> {
+    let config = json!({
+        "api_key": std::env::var(format!("{}_API_KEY", provider.to_uppercase())).ok(),
+        "model": model,
+    });
+    Ok(Arc::from(AIClientFactory::create(provider, config)?))
 }
 
 pub struct MCPHost {
     servers: Arc<Mutex<HashMap<String, ManagedServer>>>,
+    http_servers: Arc<Mutex<HashMap<String, HttpManagedServer>>>,
+    /// Identical requests currently awaiting a response, see `send_request_coalesced`
+    in_flight: Arc<Mutex<HashMap<String, CoalescedResponse>>>,
     client_info: Implementation,
     request_timeout: std::time::Duration, 
-    ai_client: Option<Box<dyn AIClient>>,
+    /// Replaced by `set_ai_model`, so read it through `ai_client()`
+    ai_client: SharedAIClient,
+    /// File passed to `load_config`, kept up to date when servers are started or stopped
+    config_path: Mutex<Option<String>>,
+    /// Server definitions from the loaded config and servers started from the CLI, used to
+    /// start group members and written back by `save_config`
+    server_configs: Mutex<HashMap<String, ServerConfig>>,
+    groups: Mutex<HashMap<String, Vec<String>>>,
+    capability_cache: CapabilityCache,
+    capability_cache_ttl: Duration,
+    circuit_breakers: Mutex<HashMap<String, CircuitBreaker>>,
+    circuit_cooldown: Duration,
+    /// Tool calls made through `call_tool`, per server, for `watch`
+    tool_activity: Mutex<HashMap<String, ServerActivity>>,
 }
 
 impl MCPHost {
@@ -213,17 +638,22 @@ impl MCPHost {
             json!({
                 "name": t.name,
                 "description": t.description.as_ref().unwrap_or(&"".to_string()),
-                "inputSchema": t.input_schema
+                "inputSchema": t.input_schema,
+                "outputSchema": t.output_schema
             })
         }).collect();
 
         // Create the tools string first
         let tools_str = tool_info_list.iter().map(|tool| {
+            let output_schema = tool.output_schema.as_ref()
+                .map(|schema| format!("\noutput schema: {:?}", schema))
+                .unwrap_or_default();
             format!(
-                "- {}: {}\ninput schema: {:?}",
+                "- {}: {}\ninput schema: {:?}{}",
                 tool.name,
                 tool.description.as_ref().unwrap_or(&"".to_string()),
-                tool.input_schema
+                tool.input_schema,
+                output_schema
             )
         }).collect::<Vec<_>>().join("");
 
@@ -304,39 +734,88 @@ impl MCPHost {
         )
     }
 
-    pub async fn new() -> Result<MCPHost> {
-        // Try to get the AI provider from environment
-
-        let model_name = "deepseek-chat".to_string();
-        info!("Initializing DeepSeek client with model: {}", model_name);
-
-        // Retrieve the DeepSeek API key from an environment variable
-        let api_key = std::env::var("DEEPSEEK_API_KEY")
-            .expect("DEEPSEEK_API_KEY not set. Please provide it in the environment.");
+    /// The AI client in use right now; `set_ai_model` may replace it between calls
+    pub fn ai_client(&self) -> Option<Arc<dyn AIClient>> {
+        self.ai_client.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
 
-        info!("Got DeepSeek API key: {}", api_key);
-        let client = DeepSeekClient::new(api_key, model_name);
-        let ai_client = Some(Box::new(client) as Box<dyn AIClient>);
+    fn reader_context(&self) -> ReaderContext {
+        ReaderContext {
+            ai_client: Arc::clone(&self.ai_client),
+            capability_cache: Arc::clone(&self.capability_cache),
+        }
+    }
 
+    /// Switch to another provider or model. Turns already in a conversation are kept, so it
+    /// continues with the new model from the next message.
+    pub fn set_ai_model(&self, provider: &str, model: &str) -> Result<()> {
+        let client = create_ai_client(provider, Some(model))?;
+        info!("Switched to {} model {}", provider, client.model_name());
+        *self.ai_client.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(client);
+        Ok(())
+    }
 
+    pub async fn new() -> Result<MCPHost> {
+        // Try to get the AI provider from environment
+        let provider = std::env::var("MCP_AI_PROVIDER").unwrap_or_else(|_| "deepseek".to_string());
+
+        let ai_client = if provider == "deepseek" {
+            let model_name = "deepseek-chat".to_string();
+            info!("Initializing DeepSeek client with model: {}", model_name);
+
+            // Retrieve the DeepSeek API key from an environment variable
+            let api_key = std::env::var("DEEPSEEK_API_KEY")
+                .expect("DEEPSEEK_API_KEY not set. Please provide it in the environment.");
+
+            let client = DeepSeekClient::new(api_key, model_name);
+            Some(Arc::new(client) as Arc<dyn AIClient>)
+        } else {
+            match create_ai_client(&provider, std::env::var("MCP_AI_MODEL").ok().as_deref()) {
+                Ok(client) => {
+                    info!("Initializing {} client with model: {}", provider, client.model_name());
+                    Some(client)
+                }
+                Err(e) => {
+                    warn!("Failed to create the {} client: {}", provider, e);
+                    None
+                }
+            }
+        };
 
         if ai_client.is_none() {
             info!("No AI client configured. Set MCP_AI_PROVIDER and corresponding API key (OPENAI_API_KEY or GEMINI_API_KEY or ANTHROPIC_API_KEY)");
         }
 
+        let servers = Arc::new(Mutex::new(HashMap::new()));
+        tokio::spawn(run_keepalive(Arc::clone(&servers)));
+
         Ok(MCPHost {
-            servers: Arc::new(Mutex::new(HashMap::new())),
+            servers,
+            http_servers: Arc::new(Mutex::new(HashMap::new())),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
             client_info: Implementation {
                 name: "mcp-host".to_string(),
                 version: env!("CARGO_PKG_VERSION").to_string(),
             },
             request_timeout: std::time::Duration::from_secs(120), // Increased timeout for long-running operations
-            ai_client,
+            ai_client: Arc::new(std::sync::RwLock::new(ai_client)),
+            config_path: Mutex::new(None),
+            server_configs: Mutex::new(HashMap::new()),
+            groups: Mutex::new(HashMap::new()),
+            capability_cache: Arc::new(Mutex::new(HashMap::new())),
+            capability_cache_ttl: std::env::var("MCP_CAPABILITY_CACHE_TTL_SECS").ok()
+                .and_then(|secs| secs.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_CAPABILITY_CACHE_TTL),
+            circuit_breakers: Mutex::new(HashMap::new()),
+            circuit_cooldown: circuit_breaker::cooldown_from_env(),
+            tool_activity: Mutex::new(HashMap::new()),
         })
     }
 
     pub async fn load_config(&self, config_path: &str) -> Result<()> {
         info!("Loading configuration from: {}", config_path);
+        *self.config_path.lock().await = Some(config_path.to_string());
         
         // Ensure config directory exists
         if let Some(parent) = std::path::Path::new(config_path).parent() {
@@ -354,6 +833,7 @@ impl MCPHost {
                 info!("Config file not found, creating default");
                 let default_config = Config {
                     servers: HashMap::new(),
+                    groups: HashMap::new(),
                 };
                 let default_str = serde_json::to_string_pretty(&default_config)?;
                 fs::write(config_path, &default_str)?;
@@ -366,42 +846,135 @@ impl MCPHost {
         let config: Config = serde_json::from_str(&config_str)?;
         
         info!("Found {} servers in config", config.servers.len());
+        // Recorded first, so servers that fail to start are still saved back
+        self.server_configs.lock().await.extend(config.servers.clone());
+        self.groups.lock().await.extend(config.groups);
         for (name, server_config) in config.servers {
-            // Start each configured server
-            let mut command = Command::new(&server_config.command);
-            
-            // Set environment variables if specified
-            for (key, value) in server_config.env {
-                command.env(key, value);
+            self.start_configured_server(&name, server_config).await?;
+        }
+        Ok(())
+    }
+
+    async fn start_configured_server(&self, name: &str, server_config: ServerConfig) -> Result<()> {
+        match server_config.transport {
+            TransportKind::Stdio { command: program, args, env } => {
+                let mut command = Command::new(expand_env(&program));
+                command.args(&args);
+
+                // Set environment variables if specified
+                for (key, value) in &env {
+                    command.env(key, expand_env(value));
+                }
+
+                self.start_server_with_command(
+                    name,
+                    command,
+                    server_config.tool_timeouts,
+                    server_config.default_timeout_secs,
+                ).await
+            }
+            TransportKind::Http { url, headers } => {
+                self.connect_http_server(
+                    name,
+                    &url,
+                    &headers,
+                    server_config.tool_timeouts,
+                    server_config.default_timeout_secs,
+                ).await
             }
-            
-            self.start_server_with_command(&name, command).await?;
         }
-        
+    }
+
+    async fn group_members(&self, group_name: &str) -> Option<Vec<String>> {
+        self.groups.lock().await.get(group_name).cloned()
+    }
+
+    /// Start every server of a group that isn't running yet, one result per member.
+    /// Members must be defined in the loaded config.
+    pub async fn start_group(&self, group_name: &str) -> Vec<Result<()>> {
+        let Some(members) = self.group_members(group_name).await else {
+            return vec![Err(anyhow!("Group not found: {}", group_name))];
+        };
+        let running = self.server_names().await;
+
+        let mut results = Vec::with_capacity(members.len());
+        for name in members {
+            if running.contains(&name) {
+                results.push(Ok(()));
+                continue;
+            }
+            let server_config = self.server_configs.lock().await.get(&name).cloned();
+            let result = match server_config {
+                Some(server_config) => self.start_configured_server(&name, server_config).await,
+                None => Err(anyhow!("Server '{}' is not defined in the config", name)),
+            };
+            results.push(result.map_err(|e| anyhow!("{}: {}", name, e)));
+        }
+        results
+    }
+
+    /// Stop every server of a group. They stay in the config file so the group can be
+    /// started again.
+    pub async fn stop_group(&self, group_name: &str) -> Vec<Result<()>> {
+        let Some(members) = self.group_members(group_name).await else {
+            return vec![Err(anyhow!("Group not found: {}", group_name))];
+        };
+
+        let mut results = Vec::with_capacity(members.len());
+        for name in members {
+            let result = self.shutdown_server_gracefully(&name, SHUTDOWN_GRACE_PERIOD).await;
+            results.push(result.map_err(|e| anyhow!("{}: {}", name, e)));
+        }
+        results
+    }
+
+    /// Write the configured servers to `path` in the format `load_config` reads. Servers are
+    /// saved as they were configured, before `expand_env`, so secrets taken from the
+    /// environment stay out of the file, and servers that aren't running are kept.
+    pub async fn save_config(&self, path: &str) -> Result<()> {
+        let servers = self.server_configs.lock().await.clone();
+        let groups = self.groups.lock().await.clone();
+        fs::write(path, serde_json::to_string_pretty(&Config { servers, groups })?)?;
+        info!("Saved configuration to {}", path);
         Ok(())
     }
 
-    async fn start_server_with_command(&self, name: &str, mut command: Command) -> Result<()> {
+    /// Save to the config file loaded at startup, if any, so servers started or stopped
+    /// from the CLI survive a restart
+    async fn autosave_config(&self) {
+        let Some(path) = self.config_path.lock().await.clone() else { return };
+        if let Err(e) = self.save_config(&path).await {
+            warn!("Failed to save configuration to {}: {}", path, e);
+        }
+    }
+
+    async fn start_server_with_command(
+        &self,
+        name: &str,
+        mut command: Command,
+        tool_timeouts: HashMap<String, u64>,
+        default_timeout_secs: Option<u64>,
+    ) -> Result<()> {
         info!("Starting server '{}' with command: {:?}", name, command);
         command.stdin(Stdio::piped())
                .stdout(Stdio::piped())
                .stderr(Stdio::piped());
 
         info!("Spawning server process");
-        let mut child = command.spawn()?;
-        let child_stdin = child.stdin.take().expect("Failed to get stdin");
-        let stdin = Arc::new(Mutex::new(ChildStdin::from_std(child_stdin)?));
-
-        let stdout = child.stdout.take().expect("Failed to get stdout");
-        let stdout = Arc::new(Mutex::new(ChildStdout::from_std(stdout)?));
+        let (child, connection) = spawn_server_process(name, &mut command, self.reader_context())?;
 
         let server = ManagedServer {
             name: name.to_string(),
             process: child,
-            stdin,
-            stdout,
+            connection,
             capabilities: None,
             initialized: false,
+            missed_pings: 0,
+            tool_timeouts,
+            default_timeout_secs,
+            restart_cmd: command,
+            restart_times: Vec::new(),
+            permanently_failed: false,
         };
 
         {
@@ -414,10 +987,113 @@ impl MCPHost {
         Ok(())
     }
 
+    async fn connect_http_server(
+        &self,
+        name: &str,
+        url: &str,
+        headers: &HashMap<String, String>,
+        tool_timeouts: HashMap<String, u64>,
+        default_timeout_secs: Option<u64>,
+    ) -> Result<()> {
+        info!("Connecting to HTTP server '{}' at {}", name, url);
+        let server = HttpManagedServer::new(name, url, headers, tool_timeouts, default_timeout_secs)?;
+
+        {
+            let mut http_servers = self.http_servers.lock().await;
+            http_servers.insert(name.to_string(), server);
+        }
+
+        self.initialize_server(name).await?;
+
+        Ok(())
+    }
+
+    /// Poll every server's process and restart the ones that exited with a failure status.
+    /// Runs until the host is dropped, so callers should spawn it.
+    pub async fn monitor_servers(&self) {
+        let mut interval = tokio::time::interval(MONITOR_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let crashed: Vec<String> = {
+                let mut servers = self.servers.lock().await;
+                servers.iter_mut()
+                    .filter(|(_, server)| !server.permanently_failed)
+                    .filter_map(|(name, server)| match server.process.try_wait() {
+                        Ok(Some(status)) if !status.success() => {
+                            warn!("Server '{}' exited with {}", name, status);
+                            Some(name.clone())
+                        }
+                        Ok(_) => None,
+                        Err(e) => {
+                            error!("Failed to poll server '{}': {}", name, e);
+                            None
+                        }
+                    })
+                    .collect()
+            };
+
+            for name in crashed {
+                match self.restart_server(&name).await {
+                    Ok(true) => info!("Restarted server '{}'", name),
+                    Ok(false) => {}
+                    Err(e) => error!("Failed to restart server '{}': {}", name, e),
+                }
+            }
+        }
+    }
+
+    /// Re-run a crashed server's command and initialize it again. Returns `false` if the
+    /// server has restarted too often and was marked as permanently failed instead.
+    async fn restart_server(&self, name: &str) -> Result<bool> {
+        {
+            let mut servers = self.servers.lock().await;
+            let server = servers.get_mut(name)
+                .ok_or_else(|| anyhow!("Server not found: {}", name))?;
+
+            let now = std::time::Instant::now();
+            server.restart_times.retain(|t| now.duration_since(*t) < RESTART_WINDOW);
+            if server.restart_times.len() >= MAX_RESTARTS {
+                server.permanently_failed = true;
+                server.initialized = false;
+                error!(
+                    "Server '{}' restarted {} times within {:?}, giving up",
+                    name, MAX_RESTARTS, RESTART_WINDOW
+                );
+                return Ok(false);
+            }
+            server.restart_times.push(now);
+
+            info!("Restarting server '{}' with command: {:?}", name, server.restart_cmd);
+            let (child, connection) = spawn_server_process(name, &mut server.restart_cmd, self.reader_context())?;
+            server.process = child;
+            server.connection = connection;
+            server.capabilities = None;
+            server.initialized = false;
+            server.missed_pings = 0;
+        }
+
+        self.capability_cache.lock().await.remove(name);
+        self.circuit_breakers.lock().await.remove(name);
+        self.initialize_server(name).await?;
+        Ok(true)
+    }
+
     pub async fn start_server(&self, name: &str, command: &str, args: &[String]) -> Result<()> {
-        let mut cmd = Command::new(command);
+        let mut cmd = Command::new(expand_env(command));
         cmd.args(args);
-        self.start_server_with_command(name, cmd).await
+        self.start_server_with_command(name, cmd, HashMap::new(), None).await?;
+        self.server_configs.lock().await.insert(name.to_string(), ServerConfig {
+            transport: TransportKind::Stdio {
+                command: command.to_string(),
+                args: args.to_vec(),
+                env: HashMap::new(),
+            },
+            tool_timeouts: HashMap::new(),
+            default_timeout_secs: None,
+        });
+        self.autosave_config().await;
+        Ok(())
     }
 
     async fn initialize_server(&self, name: &str) -> Result<()> {
@@ -436,7 +1112,7 @@ impl MCPHost {
             })),
         };
 
-        let response = self.send_request(name, request).await?;
+        let response = self.send_request_with_retry(name, request, REQUEST_ATTEMPTS).await?;
 
         // Check for error response
         if let Some(error) = response.error {
@@ -450,99 +1126,198 @@ impl MCPHost {
             if let Some(server) = servers.get_mut(name) {
                 server.capabilities = Some(capabilities);
                 server.initialized = true;
+            } else if let Some(server) = self.http_servers.lock().await.get_mut(name) {
+                server.capabilities = Some(capabilities);
+                server.initialized = true;
             }
         }
 
         // Send initialized notification
-        let notification = JsonRpcRequest {
-            jsonrpc: "2.0".to_string(),
-            id: RequestId::String(Uuid::new_v4().to_string()).into(),
-            method: "notifications/initialized".to_string(),
-            params: None,
-        };
+        let notification = create_notification("notifications/initialized", None);
 
         self.send_request(name, notification).await?;
 
         Ok(())
     }
 
+    /// Tell the server to stop working on a request the host no longer waits for
+    async fn cancel_request(&self, server_name: &str, request_id: Value, reason: &str) {
+        let notification = create_notification(
+            "notifications/cancelled",
+            Some(json!({ "requestId": request_id, "reason": reason })),
+        );
+        if let Err(e) = self.send_request(server_name, notification).await {
+            debug!("Failed to cancel request {} on server '{}': {}", request_id, server_name, e);
+        }
+    }
+
     async fn send_request(&self, server_name: &str, request: JsonRpcRequest) -> Result<JsonRpcResponse> {
-        debug!("\n=== Starting send_request ===");
-        debug!("Server: {}", server_name);
-        debug!("Request method: {}", request.method);
-        let request_str = serde_json::to_string(&request)? + "\n";
-        debug!("DEBUG: Sending request: {}", request_str.trim());
-        
-        // Create channels for stdin/stdout communication
-        let (tx, mut rx) = mpsc::channel(1);
-        
-        // Get the server's I/O handles
-        let (stdin, stdout): (Arc<Mutex<ChildStdin>>, Arc<Mutex<ChildStdout>>) = {
-            let servers = self.servers.lock().await;
-            let server = servers.get(server_name)
-                .ok_or_else(|| anyhow::anyhow!("Server not found: {}", server_name))?;
-            
-            (Arc::clone(&server.stdin), Arc::clone(&server.stdout))
+        self.send_request_with_timeout(server_name, request, self.request_timeout).await
+    }
+
+    /// `send_request`, retried with exponential backoff while it fails with a timeout or an
+    /// I/O error. Errors the server answers with are returned right away.
+    pub async fn send_request_with_retry(
+        &self,
+        server_name: &str,
+        request: JsonRpcRequest,
+        max_attempts: u32,
+    ) -> Result<JsonRpcResponse> {
+        retry_with_backoff(max_attempts, || self.send_request(server_name, request.clone())).await
+    }
+
+    async fn send_request_with_timeout(
+        &self,
+        server_name: &str,
+        request: JsonRpcRequest,
+        request_timeout: std::time::Duration,
+    ) -> Result<JsonRpcResponse> {
+        self.check_circuit(server_name).await?;
+        let result = dispatch_request(
+            Arc::clone(&self.servers),
+            Arc::clone(&self.http_servers),
+            server_name.to_string(),
+            request,
+            request_timeout,
+        ).await;
+        self.record_request_outcome(server_name, result.is_ok()).await;
+        result
+    }
+
+    /// Fail fast while the server's circuit is open. After the cooldown the server has to pass
+    /// a health check before the trial request is sent.
+    async fn check_circuit(&self, server_name: &str) -> Result<()> {
+        let trial = {
+            let mut breakers = self.circuit_breakers.lock().await;
+            let breaker = breakers.entry(server_name.to_string())
+                .or_insert_with(|| CircuitBreaker::new(self.circuit_cooldown));
+            if !breaker.allow_request() {
+                return Err(anyhow!("Circuit open: server {} is temporarily unavailable", server_name));
+            }
+            breaker.is_half_open()
         };
 
-        debug!("Spawning async task for request/response handling");
-        // Write request and read response in a separate task
-        tokio::spawn(async move {
-            debug!("Async task started");
-            // Write request
-            {
-                let request_bytes = request_str.as_bytes().to_vec(); // Clone the data
-                debug!("Acquiring stdin lock");
-                let mut stdin_guard = stdin.lock().await;
-                debug!("Acquired stdin lock");
-                if let Err(e) = stdin_guard.write_all(&request_bytes).await {
-                    let _ = tx.send(Err(anyhow::anyhow!("Failed to write to stdin: {}", e))).await;
-                    return;
-                }
-                if let Err(e) = stdin_guard.flush().await {
-                    let _ = tx.send(Err(anyhow::anyhow!("Failed to flush stdin: {}", e))).await;
-                    return;
-                }
-                // stdin_guard is dropped here
-            }
-
-            // Read response
-            debug!("Starting response read");
-            let mut response_line = String::new();
-            {
-                let mut stdout_guard = stdout.lock().await;
-                let mut reader = BufReader::new(&mut *stdout_guard);
-                
-                match reader.read_line(&mut response_line).await {
-                    Ok(0) => {
-                        let _ = tx.send(Err(anyhow::anyhow!("Server closed connection"))).await;
-                    }
-                    Ok(_) => {
-                        debug!("DEBUG: Received response: {}", response_line.trim());
-                        match serde_json::from_str(&response_line) {
-                            Ok(response) => { let _ = tx.send(Ok(response)).await; }
-                            Err(e) => { 
-                                let _ = tx.send(Err(anyhow::anyhow!("Failed to parse response '{}': {}", response_line.trim(), e))).await; 
-                            }
-                        }
+        if trial {
+            if let Err(e) = self.check_health(server_name).await {
+                self.record_request_outcome(server_name, false).await;
+                return Err(anyhow!("Circuit open: server {} failed its health check: {}", server_name, e));
+            }
+        }
+        Ok(())
+    }
+
+    /// Send `health` to a server, bypassing its circuit breaker. Fails if no answer arrives
+    /// within `HEALTH_CHECK_TIMEOUT`. A server answering with an error, or without a `status`,
+    /// is alive but doesn't implement `health`; that gives `None`.
+    pub async fn check_health(&self, server_name: &str) -> Result<Option<Value>> {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: RequestId::String(Uuid::new_v4().to_string()).into(),
+            method: "health".to_string(),
+            params: None,
+        };
+        let response = dispatch_request(
+            Arc::clone(&self.servers),
+            Arc::clone(&self.http_servers),
+            server_name.to_string(),
+            request,
+            HEALTH_CHECK_TIMEOUT,
+        ).await?;
+        Ok(response.result.filter(|health| response.error.is_none() && health.get("status").is_some()))
+    }
+
+    async fn record_request_outcome(&self, server_name: &str, succeeded: bool) {
+        let mut breakers = self.circuit_breakers.lock().await;
+        let Some(breaker) = breakers.get_mut(server_name) else { return };
+        if succeeded {
+            breaker.record_success();
+        } else if breaker.record_failure() {
+            warn!(
+                "Server '{}' keeps failing, rejecting its requests for {:?}",
+                server_name, self.circuit_cooldown
+            );
+        }
+    }
+
+    /// Send `request`, sharing the response with any identical request already in flight
+    /// instead of sending a duplicate. Only meant for side-effect free methods like `tools/list`.
+    async fn send_request_coalesced(&self, server_name: &str, request: JsonRpcRequest) -> Result<JsonRpcResponse> {
+        let key = coalesce_key(server_name, &request);
+        let existing = self.in_flight.lock().await.get(&key).cloned();
+        let (shared, joined) = match existing {
+            Some(shared) => {
+                debug!("Joining in-flight request {}", key);
+                (shared, true)
+            }
+            None => {
+                // A half-open circuit waits for a health check, which must not hold up
+                // requests to other servers, so the lock is taken again afterwards
+                self.check_circuit(server_name).await?;
+                let mut in_flight = self.in_flight.lock().await;
+                match in_flight.get(&key) {
+                    Some(shared) => {
+                        debug!("Joining in-flight request {}", key);
+                        (shared.clone(), true)
                     }
-                    Err(e) => {
-                        let _ = tx.send(Err(anyhow::anyhow!("Failed to read response: {}", e))).await;
+                    None => {
+                        let shared = dispatch_request(
+                            Arc::clone(&self.servers),
+                            Arc::clone(&self.http_servers),
+                            server_name.to_string(),
+                            request,
+                            self.request_timeout,
+                        )
+                        .map(|result| result.map_err(Arc::new))
+                        .boxed()
+                        .shared();
+                        in_flight.insert(key.clone(), shared.clone());
+                        (shared, false)
                     }
                 }
-                // stdout_guard is dropped here
             }
-        });
+        };
+
+        let result = shared.clone().await;
+        // Only the caller that sent the request counts it for the circuit breaker
+        if !joined {
+            self.record_request_outcome(server_name, result.is_ok()).await;
+        }
 
-        // Wait for response with timeout
-        match timeout(self.request_timeout, rx.recv()).await {
-            Ok(Some(result)) => result,
-            Ok(None) => Err(anyhow::anyhow!("Response channel closed")),
-            Err(_) => Err(anyhow::anyhow!("Request timed out")),
+        // A later caller may already have started a fresh request under the same key
+        let mut in_flight = self.in_flight.lock().await;
+        if in_flight.get(&key).is_some_and(|current| current.ptr_eq(&shared)) {
+            in_flight.remove(&key);
         }
+
+        result.map_err(|e| anyhow!("{:#}", e))
     }
 
+    /// Tools of a server, or of every member of a group with names prefixed `server::`
     pub async fn list_server_tools(&self, server_name: &str) -> Result<Vec<ToolInfo>> {
+        let Some(members) = self.group_members(server_name).await else {
+            return self.list_single_server_tools(server_name).await;
+        };
+
+        let mut merged = Vec::new();
+        for member in members {
+            for mut tool in self.list_single_server_tools(&member).await? {
+                tool.name = format!("{}::{}", member, tool.name);
+                merged.push(tool);
+            }
+        }
+        Ok(merged)
+    }
+
+    /// Tools of one server. The list is cached for `capability_cache_ttl`, or until the server
+    /// says its tools changed.
+    async fn list_single_server_tools(&self, server_name: &str) -> Result<Vec<ToolInfo>> {
+        if let Some((fetched, tools)) = self.capability_cache.lock().await.get(server_name) {
+            if fetched.elapsed() < self.capability_cache_ttl {
+                debug!("Using cached tool list of server '{}'", server_name);
+                return Ok(tools.clone());
+            }
+        }
+
         let request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
             id: RequestId::String(Uuid::new_v4().to_string()).into(),
@@ -551,46 +1326,346 @@ impl MCPHost {
         };
 
         info!("Sending tool call request to server");
-        let response = self.send_request(server_name, request).await?;
+        let response = retry_with_backoff(REQUEST_ATTEMPTS, || {
+            self.send_request_coalesced(server_name, request.clone())
+        }).await?;
         info!("Received response from server");
         let tools: ListToolsResult = serde_json::from_value(response.result.unwrap_or_default())?;
+        self.capability_cache.lock().await
+            .insert(server_name.to_string(), (std::time::Instant::now(), tools.tools.clone()));
         Ok(tools.tools)
     }
 
+    /// Call a tool on the first stdio or HTTP server, by name, that offers it
+    pub async fn call_tool_on_any_server(&self, tool_name: &str, args: Value) -> Result<String> {
+        let server_name = self.find_tool_server(tool_name).await?;
+        self.call_tool(&server_name, tool_name, args).await
+    }
+
+    /// Run independent `(server_name, tool_name, args)` calls concurrently. All of them share
+    /// one `request_timeout` deadline; a call still running when it passes fails, the others
+    /// keep their results. Results are in the order of `calls`.
+    pub async fn call_tool_parallel(&self, calls: Vec<(String, String, Value)>) -> Vec<Result<String>> {
+        let deadline = tokio::time::Instant::now() + self.request_timeout;
+        let calls = calls.into_iter().map(|(server_name, tool_name, args)| async move {
+            match tokio::time::timeout_at(deadline, self.call_tool(&server_name, &tool_name, args)).await {
+                Ok(result) => result,
+                Err(_) => Err(anyhow!("Tool '{}' on '{}' timed out", tool_name, server_name)),
+            }
+        });
+        futures::future::join_all(calls).await
+    }
+
+    async fn find_tool_server(&self, tool_name: &str) -> Result<String> {
+        self.find_tool(tool_name).await
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No server offers the tool '{}'", tool_name))
+    }
+
+    /// Tools of every stdio and HTTP server, keyed by server name. A tool a server lists twice
+    /// appears once. Servers whose tools can't be listed are left out.
+    pub async fn list_all_server_tools(&self) -> Result<HashMap<String, Vec<ToolInfo>>> {
+        let mut all_tools = HashMap::new();
+        for server_name in self.server_names().await {
+            match self.list_single_server_tools(&server_name).await {
+                Ok(mut tools) => {
+                    let mut seen = std::collections::HashSet::new();
+                    tools.retain(|tool| seen.insert(tool.name.clone()));
+                    all_tools.insert(server_name, tools);
+                }
+                Err(e) => warn!("Failed to list tools of server '{}': {}", server_name, e),
+            }
+        }
+        Ok(all_tools)
+    }
+
+    /// Names of the servers that offer `tool_name`, sorted
+    pub async fn find_tool(&self, tool_name: &str) -> Vec<String> {
+        let mut server_names: Vec<String> = self.list_all_server_tools().await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, tools)| tools.iter().any(|tool| tool.name == tool_name))
+            .map(|(server_name, _)| server_name)
+            .collect();
+        server_names.sort();
+        server_names
+    }
+
+    /// Write a readable Markdown record of a chat session to `path`
+    pub fn export_conversation_markdown(&self, state: &ConversationState, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, state.to_markdown())?;
+        info!("Exported conversation to {}", path.display());
+        Ok(())
+    }
+
+    /// Ask a server to send only log messages at `level` or above
+    pub async fn set_server_log_level(&self, server_name: &str, level: &str) -> Result<()> {
+        if !server_logging::LOG_LEVELS.contains(&level) {
+            return Err(anyhow!(
+                "Unknown log level '{}', expected one of: {}",
+                level, server_logging::LOG_LEVELS.join(", ")
+            ));
+        }
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: RequestId::String(Uuid::new_v4().to_string()).into(),
+            method: "logging/setLevel".to_string(),
+            params: Some(json!({ "level": level })),
+        };
+
+        let response = self.send_request(server_name, request).await?;
+        if let Some(error) = response.error {
+            return Err(anyhow!("Server returned error: {}", error.message));
+        }
+        Ok(())
+    }
+
+    /// Per-tool call counts reported by the server's `tools/stats` method
+    pub async fn get_server_stats(&self, server_name: &str) -> Result<serde_json::Value> {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: RequestId::String(Uuid::new_v4().to_string()).into(),
+            method: "tools/stats".to_string(),
+            params: None,
+        };
+
+        let response = self.send_request_coalesced(server_name, request).await?;
+        if let Some(error) = response.error {
+            return Err(anyhow!("Server returned error: {}", error.message));
+        }
+        Ok(response.result.unwrap_or_default())
+    }
+
+    /// Names of all stdio and HTTP servers
+    pub async fn server_names(&self) -> Vec<String> {
+        let servers = self.servers.lock().await;
+        let http_servers = self.http_servers.lock().await;
+        servers.keys().chain(http_servers.keys()).cloned().collect()
+    }
+
+    /// Send a notification to every server. Returns one result per server; a server that
+    /// can't be reached doesn't stop the others from being notified.
+    pub async fn broadcast_notification(&self, method: &str, params: Option<Value>) -> Vec<Result<()>> {
+        let server_names = self.server_names().await;
+        let mut results = Vec::with_capacity(server_names.len());
+        for server_name in server_names {
+            let notification = create_notification(method, params.clone());
+            let result = self.send_request(&server_name, notification).await
+                .map(|_| ())
+                .map_err(|e| anyhow!("Failed to notify server '{}': {}", server_name, e));
+            results.push(result);
+        }
+        results
+    }
+
+    /// Change the host's working directory and tell the servers their roots changed
+    pub async fn change_working_directory(&self, dir: &str) -> Result<()> {
+        std::env::set_current_dir(dir)
+            .map_err(|e| anyhow!("Failed to change directory to {}: {}", dir, e))?;
+        info!("Working directory is now {}", std::env::current_dir()?.display());
+
+        for result in self.broadcast_notification("notifications/roots/list_changed", None).await {
+            if let Err(e) = result {
+                warn!("{}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the server exists and is still answering keepalive pings
+    pub async fn is_server_healthy(&self, server_name: &str) -> bool {
+        let servers = self.servers.lock().await;
+        if let Some(server) = servers.get(server_name) {
+            return server.initialized;
+        }
+        let http_servers = self.http_servers.lock().await;
+        http_servers.get(server_name).is_some_and(|server| server.initialized)
+    }
+
+    /// Timeout for a `tools/call` of `tool_name`: the server's per-tool setting, then its
+    /// default, then the host-wide request timeout.
+    async fn tool_timeout(&self, server_name: &str, tool_name: &str) -> std::time::Duration {
+        let servers = self.servers.lock().await;
+        let http_servers = self.http_servers.lock().await;
+        let configured = match servers.get(server_name) {
+            Some(server) => server.tool_timeouts.get(tool_name).copied().or(server.default_timeout_secs),
+            None => http_servers.get(server_name).and_then(|server| {
+                server.tool_timeouts.get(tool_name).copied().or(server.default_timeout_secs)
+            }),
+        };
+        configured
+            .map(Duration::from_secs)
+            .unwrap_or(self.request_timeout)
+    }
+
     pub async fn call_tool(&self, server_name: &str, tool_name: &str, args: Value) -> Result<String> {
+        self.call_tool_with_progress(server_name, tool_name, args, None).await
+    }
+
+    /// `call_tool`, asking the server to report progress with the listener's token as
+    /// `_meta.progressToken`. Its `notifications/progress` go to the listener until the call ends.
+    pub async fn call_tool_with_progress(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        args: Value,
+        progress: Option<ProgressListener>,
+    ) -> Result<String> {
+        let attributes = [
+            ("mcp.server", server_name.to_string()),
+            ("mcp.tool.name", tool_name.to_string()),
+        ];
+        let _subscription = progress.as_ref().map(progress::subscribe);
+        let progress_token = progress.map(|listener| listener.token);
+        let call = self.run_tool_call(server_name, tool_name, args, progress_token);
+        let started = std::time::Instant::now();
+        let result = telemetry::in_span(format!("tool {}", tool_name), &attributes, call).await;
+        self.tool_activity.lock().await
+            .entry(server_name.to_string())
+            .or_default()
+            .record(tool_name, started.elapsed(), result.is_ok());
+        result
+    }
+
+    /// Redraw the tool call counters of `server_name` every second until `q` is pressed. The
+    /// server's own `tools/stats` are shown when it reports them, else the host's counters.
+    /// Without a terminal to read keys from, a `q` line on stdin stops watching.
+    pub async fn watch_server(
+        &self,
+        server_name: &str,
+        lines: &mut tokio::io::Lines<BufReader<tokio::io::Stdin>>,
+    ) -> Result<()> {
+        if !self.server_names().await.iter().any(|name| name == server_name) {
+            return Err(anyhow!("Server '{}' not found", server_name));
+        }
+
+        let term = Term::stdout();
+        let interactive = term.is_term();
+        let (quit_tx, mut quit_rx) = mpsc::channel::<()>(1);
+        if interactive {
+            let term = term.clone();
+            tokio::task::spawn_blocking(move || {
+                loop {
+                    match term.read_key() {
+                        Ok(console::Key::Char('q')) | Err(_) => break,
+                        Ok(_) => {}
+                    }
+                }
+                let _ = quit_tx.blocking_send(());
+            });
+        }
+
+        // Once the server fails to report stats it isn't asked again
+        let mut ask_server = true;
+        let mut ticker = tokio::time::interval(WATCH_REFRESH_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                Some(()) = quit_rx.recv() => break,
+                line = lines.next_line(), if !interactive => match line {
+                    Ok(Some(line)) if line.trim() == "q" => break,
+                    Ok(Some(_)) => continue,
+                    _ => break,
+                },
+            }
+
+            let server_stats = if ask_server {
+                let stats = self.get_server_stats(server_name).await.ok()
+                    .and_then(|stats| serde_json::from_value::<HashMap<String, ToolCounters>>(stats.get("tools")?.clone()).ok());
+                ask_server = stats.is_some();
+                stats
+            } else {
+                None
+            };
+            let health = describe_health(self.check_health(server_name).await);
+            let activity = self.tool_activity.lock().await.get(server_name).cloned().unwrap_or_default();
+            let (source, tools) = match server_stats {
+                Some(tools) => ("server metrics", tools),
+                None => ("host counters", activity.tools),
+            };
+
+            term.clear_screen()?;
+            term.write_str(&tool_activity::render_watch(server_name, &health, source, &tools, activity.last_call.as_ref()))?;
+        }
+        Ok(())
+    }
+
+    async fn run_tool_call(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        args: Value,
+        progress_token: Option<String>,
+    ) -> Result<String> {
+        // Group tools are named `server::tool`
+        let group_target = match self.group_members(server_name).await {
+            Some(members) => {
+                let (member, tool) = tool_name.split_once("::")
+                    .filter(|(member, _)| members.iter().any(|m| m == member))
+                    .ok_or_else(|| anyhow!("Tool '{}' does not name a server of group '{}'", tool_name, server_name))?;
+                Some((member.to_string(), tool.to_string()))
+            }
+            None => None,
+        };
+        let (server_name, tool_name) = match &group_target {
+            Some((member, tool)) => (member.as_str(), tool.as_str()),
+            None => (server_name, tool_name),
+        };
+
         debug!("call_tool started");
         debug!("Server: {}", server_name);
+
+        if !self.is_server_healthy(server_name).await {
+            return Err(anyhow!(
+                "Server '{}' is not responding; restart it with 'stop {}' and 'start'",
+                server_name, server_name
+            ));
+        }
     
             
         debug!("Tool: {}", tool_name);
         debug!("Arguments: {}", serde_json::to_string_pretty(&args).unwrap_or_default());
         
-        let request = JsonRpcRequest {
-            jsonrpc: "2.0".to_string(),
-            id: RequestId::String(Uuid::new_v4().to_string()).into(),
-            method: "tools/call".to_string(),
-            params: Some(json!({
-                "name": tool_name,
-                "arguments": args
-            })),
-        };
-
-        let response = self.send_request(server_name, request).await?;
+        let params = serde_json::to_value(CallToolParams {
+            name: tool_name.to_string(),
+            arguments: args,
+            _meta: progress_token.map(|token| json!({ "progressToken": token })),
+        })?;
+
+        let tool_timeout = self.tool_timeout(server_name, tool_name).await;
+        debug!("Timeout: {:?}", tool_timeout);
+        // Tools can have side effects, so a call that timed out may still be running and is
+        // cancelled rather than sent again. Only calls that never got through are retried,
+        // each under a new id.
+        let response = retry_with_backoff_if(REQUEST_ATTEMPTS, is_connection_error, || async {
+            let request = JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: RequestId::String(Uuid::new_v4().to_string()).into(),
+                method: "tools/call".to_string(),
+                params: Some(params.clone()),
+            };
+            let request_id = request.id.clone();
+            let result = self.send_request_with_timeout(server_name, request, tool_timeout).await;
+            if result.as_ref().is_err_and(|e| e.to_string().starts_with("Request timed out")) {
+                self.cancel_request(server_name, request_id, "Timed out").await;
+            }
+            result
+        }).await?;
         let result: CallToolResult = serde_json::from_value(response.result.unwrap_or_default())?;
 
         let mut output = String::new();
         for content in result.content {
             match content {
-                content => {
-                    match content.type_.as_str() {
-                        "text" => {
-                            output.push_str(&content.text);
-                            output.push('\n');
-                        }
-                        _ => {
-                            output.push_str(&format!("Unknown content type: {}\n", content.type_));
-                        }
-                    }
+                ToolResponseContent::Text { text, .. } => {
+                    output.push_str(&text);
+                    output.push('\n');
+                }
+                ToolResponseContent::Image { data, mime_type, .. } => {
+                    output.push_str(&format!("[{} image, {} bytes base64]\n", mime_type, data.len()));
                 }
             }
         }
@@ -599,18 +1674,77 @@ impl MCPHost {
     }
 
     pub async fn stop_server(&self, name: &str) -> Result<()> {
-        let mut servers = self.servers.lock().await;
-        if let Some(mut server) = servers.remove(name) {
-            server.process.kill()?;
+        self.shutdown_server_gracefully(name, SHUTDOWN_GRACE_PERIOD).await?;
+        self.server_configs.lock().await.remove(name);
+        self.autosave_config().await;
+        Ok(())
+    }
+
+    /// Stop a server without touching the config file. The server is sent a `shutdown`
+    /// request and its stdin is closed; if the process is still running after
+    /// `grace_period` it is killed.
+    pub async fn shutdown_server_gracefully(&self, name: &str, grace_period: Duration) -> Result<()> {
+        self.capability_cache.lock().await.remove(name);
+        self.circuit_breakers.lock().await.remove(name);
+        let shutdown = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: RequestId::String(Uuid::new_v4().to_string()).into(),
+            method: "shutdown".to_string(),
+            params: None,
+        };
+
+        if self.http_servers.lock().await.contains_key(name) {
+            if let Err(e) = self.send_request_with_timeout(name, shutdown, grace_period).await {
+                debug!("Server '{}' did not answer shutdown: {}", name, e);
+            }
+            // Dropping an HTTP server closes its event stream
+            self.http_servers.lock().await.remove(name);
+            return Ok(());
         }
+
+        // Out of the map first, so the crash monitor doesn't restart the exiting process
+        let Some(server) = self.servers.lock().await.remove(name) else {
+            return Ok(());
+        };
+        let ManagedServer { mut process, connection, .. } = server;
+        if let Err(e) = connection.request(&shutdown, grace_period).await {
+            debug!("Server '{}' did not answer shutdown: {}", name, e);
+        }
+        // Servers that don't know `shutdown` still exit once their stdin closes
+        drop(connection);
+
+        let deadline = std::time::Instant::now() + grace_period;
+        while process.try_wait()?.is_none() {
+            if std::time::Instant::now() >= deadline {
+                warn!("Server '{}' did not exit within {:?}, killing it", name, grace_period);
+                process.kill()?;
+                process.wait()?;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        info!("Server '{}' stopped", name);
         Ok(())
     }
 
+    /// Gracefully stop every server at once, without touching the config file
+    pub async fn shutdown_all_servers(&self) {
+        let names = self.server_names().await;
+        let shutdowns = names.iter().map(|name| self.shutdown_server_gracefully(name, SHUTDOWN_GRACE_PERIOD));
+        for (name, result) in names.iter().zip(futures::future::join_all(shutdowns).await) {
+            if let Err(e) = result {
+                warn!("Failed to stop server '{}': {}", name, e);
+            }
+        }
+    }
+
 
     pub async fn run_cli(&self) -> Result<()> {
         info!("MCP Host CLI - Enter 'help' for commands");
 
         let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+        // The most recent chat session, for `export`
+        let mut last_conversation: Option<ConversationState> = None;
 
         while let Ok(Some(line)) = lines.next_line().await {
             let args: Vec<&str> = line.trim().split_whitespace().collect();
@@ -635,15 +1769,34 @@ impl MCPHost {
                     }
                 },
                 "chat" => {
-                    if server_args.len() != 1 {
-                        info!("Usage: chat <server>");
-                        continue;
-                    }
+                    let session_path = match server_args {
+                        [_] => None,
+                        [_, "--session", path] => Some(std::path::PathBuf::from(path)),
+                        _ => {
+                            info!("Usage: chat <server> [--session <path>]");
+                            continue;
+                        }
+                    };
 
                     let server_name = server_args[0];
                     match self.enter_chat_mode(server_name).await {
                         Ok(mut state) => {
-                            println!("\n{}", style("Entering chat mode. Type 'exit' or 'quit' to leave.").cyan().bold());
+                            if let Some(path) = session_path.as_deref().filter(|p| p.exists()) {
+                                match ConversationState::load_from_file(path) {
+                                    Ok(mut saved) => {
+                                        // Keep the tool list from the live server rather than the saved one
+                                        saved.tools = state.tools;
+                                        state = saved;
+                                        println!("{} {}", style("Resumed session from").green().bold(), path.display());
+                                    }
+                                    Err(e) => warn!("Failed to load session {}: {}", path.display(), e),
+                                }
+                            }
+
+                            println!(
+                                "\n{}",
+                                style("Entering chat mode. Type 'exit' or 'quit' to leave, '/export <path>' to save it as Markdown, '/model <provider> <model_name>' to switch models.").cyan().bold()
+                            );
 
                             loop {
                                 println!("\n{}", style("User:").cyan().bold());
@@ -654,15 +1807,45 @@ impl MCPHost {
                                     info!("Exiting chat mode.");
                                     break;
                                 }
+                                // Slash commands, so a message that merely starts with the word isn't taken for one
+                                if let Some(args) = user_input.strip_prefix("/model ") {
+                                    match args.split_whitespace().collect::<Vec<_>>().as_slice() {
+                                        [provider, model] => {
+                                            if let Err(e) = self.set_ai_model(provider, model) {
+                                                info!("Error switching model: {}", e);
+                                            }
+                                        }
+                                        _ => info!("Usage: /model <provider> <model_name>"),
+                                    }
+                                    continue;
+                                }
+                                if let Some(path) = user_input.strip_prefix("/export ") {
+                                    if let Err(e) = self.export_conversation_markdown(&state, Path::new(path.trim())) {
+                                        info!("Error exporting conversation: {}", e);
+                                    }
+                                    continue;
+                                }
+
+                                if state.over_budget() && !confirm_over_budget(&mut state)? {
+                                    info!("Exiting chat mode.");
+                                    break;
+                                }
 
                                 state.add_user_message(user_input);
 
                                 // Check if we have an AI client
-                                if let Some(client) = &self.ai_client {
+                                if let Some(client) = self.ai_client() {
                                     println!("Using AI model: {}", style(client.model_name()).yellow());
+
+                                    if state.needs_compression() {
+                                        let compress = state.summarize_and_compress(client.as_ref());
+                                        if let Err(e) = with_progress("Summarizing earlier conversation...".to_string(), compress).await {
+                                            warn!("Failed to summarize the conversation, older messages will be trimmed instead: {}", e);
+                                        }
+                                    }
                                     
-                                    let mut builder = client.raw_builder();
-                                    
+                                    let mut messages: Vec<(Role, String)> = Vec::new();
+
                                     // Combine all system messages into one
                                     let system_messages: Vec<String> = state.messages.iter()
                                         .filter_map(|msg| {
@@ -675,7 +1858,7 @@ impl MCPHost {
                                         .collect();
                                     
                                     if !system_messages.is_empty() {
-                                        builder = builder.system(system_messages.join("\n\n"));
+                                        messages.push((Role::System, system_messages.join("\n\n")));
                                     }
 
                                     // Add only the most recent user and assistant messages
@@ -685,22 +1868,47 @@ impl MCPHost {
                                         .take(2)
                                         .collect::<Vec<_>>();
                                     recent_messages.reverse();
-
-                                    for msg in recent_messages {
-                                        match msg.role {
-                                            Role::User => builder = builder.user(msg.content.clone()),
-                                            Role::Assistant => builder = builder.assistant(msg.content.clone()),
-                                            _ => {}
+                                    messages.extend(recent_messages.into_iter().map(|msg| (msg.role.clone(), msg.content.clone())));
+
+                                    let sent = messages.clone();
+                                    // Native tool calls come back as one structured response, so
+                                    // these clients aren't streamed even if they could be
+                                    let response_result = if client.supports_native_tools() {
+                                        let call = client.execute_with_tools(messages, &state.tools);
+                                        let response = with_progress("Waiting for response...".to_string(), call).await;
+                                        if let Ok(AIResponse::Text(text)) = &response {
+                                            println!("\n{}: {}", style("Assistant").cyan().bold(), text);
                                         }
-                                    }
+                                        response
+                                    } else if client.supports_streaming() {
+                                        stream_to_stdout(client.as_ref(), messages).await.map(AIResponse::Text)
+                                    } else {
+                                        let mut builder = client.raw_builder();
+                                        for (role, content) in messages {
+                                            builder = match role {
+                                                Role::System => builder.system(content),
+                                                Role::User => builder.user(content),
+                                                Role::Assistant => builder.assistant(content),
+                                            };
+                                        }
+                                        let response = with_progress("Waiting for response...".to_string(), builder.execute()).await;
+                                        if let Ok(response_str) = &response {
+                                            println!("\n{}: {}", style("Assistant").cyan().bold(), response_str);
+                                        }
+                                        response.map(AIResponse::Text)
+                                    };
 
-                                    match builder.execute().await {
-                                        Ok(response_str) => {
-                                            let response = response_str.as_str();
-                                            println!("\n{}: {}", style("Assistant").cyan().bold(), response);
-                                            if let Err(e) = handle_assistant_response(&self, &response, server_name, &mut state, client, None).await {
+                                    match response_result {
+                                        Ok(response) => {
+                                            state.record_ai_call(&client.model_name(), &sent, &response.reply_text());
+                                            if let Err(e) = handle_assistant_response(&self, response, server_name, &mut state, client.as_ref(), None).await {
                                                 info!("Error handling assistant response: {}", e);
                                             }
+                                            if let Some(path) = &session_path {
+                                                if let Err(e) = state.save_to_file(path) {
+                                                    warn!("Failed to save session {}: {}", path.display(), e);
+                                                }
+                                            }
                                         }
                                         Err(e) => info!("Error getting response: {}", e),
                                     }
@@ -709,19 +1917,64 @@ impl MCPHost {
                                     break;
                                 }
                             }
+                            last_conversation = Some(state);
                         }
                         Err(e) => info!("Error entering chat mode: {}", e),
                     }
                 }
+                "model" => {
+                    match server_args {
+                        [] => match self.ai_client() {
+                            Some(client) => println!("Using AI model: {}", style(client.model_name()).yellow()),
+                            None => info!("No AI client configured"),
+                        },
+                        [provider, model] => {
+                            if let Err(e) = self.set_ai_model(provider, model) {
+                                info!("Error switching model: {}", e);
+                            }
+                        }
+                        _ => info!("Usage: model <provider> <model_name>"),
+                    }
+                }
+                "export" => {
+                    if server_args.len() != 1 {
+                        info!("Usage: export <path>");
+                        continue;
+                    }
+
+                    match &last_conversation {
+                        Some(state) => {
+                            if let Err(e) = self.export_conversation_markdown(state, Path::new(server_args[0])) {
+                                info!("Error exporting conversation: {}", e);
+                            }
+                        }
+                        None => info!("No chat session to export yet, start one with 'chat <server>'"),
+                    }
+                }
                 "help" => {
                     println!("\n{}", style("Available commands:").cyan().bold());
                     println!("  {}  - Load servers from config file", style("load_config <file>").yellow());
+                    println!("  {}  - Save configured servers to a config file", style("save_config <file>").yellow());
                     println!("  {}              - List running servers", style("servers").yellow());
                     println!("  {}    - Start a server", style("start <name> <command> [args]").yellow());
                     println!("  {}                  - Stop a server", style("stop <server>").yellow());
+                    println!("  {}           - Start the servers of a config group", style("start_group <name>").yellow());
+                    println!("  {}            - Stop the servers of a config group", style("stop_group <name>").yellow());
                     println!("  {}               - List tools for a server", style("tools <server>").yellow());
-                    println!("  {}             - Call a tool with JSON arguments", style("call <server> <tool>").yellow());
-                    println!("  {}               - Enter interactive chat mode with a server", style("chat <server>").yellow());
+                    println!("  {}                - List the tools of every server", style("tools --all").yellow());
+                    println!("  {}            - List the servers offering a tool", style("find_tool <tool>").yellow());
+                    println!("  {} - Call a tool with JSON arguments", style("call [server] <tool> [--format table|csv]").yellow());
+                    println!("  {}           - Show call counts and timings per tool", style("get_stats <server>").yellow());
+                    println!("  {}              - Check that a server is responding", style("health <server>").yellow());
+                    println!("  {}               - Watch a server's tool calls live", style("watch <server>").yellow());
+                    println!("  {}    - Set the lowest level of log messages a server sends", style("log_level <server> <level>").yellow());
+                    println!("  {} - Enter interactive chat mode with a server", style("chat <server> [--session <path>]").yellow());
+                    println!("  {} - Run a chain from tool_chaining.json", style("chain <name> [--var key=value ...]").yellow());
+                    println!("  {}                - Save the last chat session as Markdown", style("export <path>").yellow());
+                    println!("  {}   - Switch the AI model, also inside chat", style("model <provider> <model_name>").yellow());
+                    println!("  {} - List recent tool calls from the audit log", style("history [server] [--limit N] [--tool name]").yellow());
+                    println!("  {}       - Run a tool call from the history again", style("history replay <index>").yellow());
+                    println!("  {}                     - Change the working directory", style("cd <dir>").yellow());
                     println!("  {}                         - Exit the program", style("quit").yellow());
                 }
                 "servers" => {
@@ -733,6 +1986,14 @@ impl MCPHost {
                             if server.initialized { style("yes").green() } else { style("no").red() }
                         );
                     }
+                    let http_servers = self.http_servers.lock().await;
+                    for (name, server) in http_servers.iter() {
+                        println!("  {} ({}) - initialized: {}",
+                            style(name).yellow(),
+                            server.base_url,
+                            if server.initialized { style("yes").green() } else { style("no").red() }
+                        );
+                    }
                     // info!();
                 }
                 "start" => {
@@ -750,6 +2011,34 @@ impl MCPHost {
                         Err(e) => info!("Error starting server: {}", e),
                     }
                 }
+                "start_group" | "stop_group" => {
+                    if server_args.len() != 1 {
+                        info!("Usage: {} <group>", command);
+                        continue;
+                    }
+
+                    let group_name = server_args[0];
+                    let results = if command == "start_group" {
+                        self.start_group(group_name).await
+                    } else {
+                        self.stop_group(group_name).await
+                    };
+                    let failures: Vec<_> = results.iter().filter_map(|r| r.as_ref().err()).collect();
+                    for e in &failures {
+                        info!("Error: {}", e);
+                    }
+                    info!("{} of {} servers in group '{}' done", results.len() - failures.len(), results.len(), group_name);
+                }
+                "save_config" => {
+                    if server_args.len() != 1 {
+                        println!("{}: save_config <config_file>", style("Usage").cyan().bold());
+                        continue;
+                    }
+
+                    if let Err(e) = self.save_config(server_args[0]).await {
+                        println!("{}: {}", style("Error saving configuration").red().bold(), e);
+                    }
+                }
                 "stop" => {
                     if server_args.len() != 1 {
                         info!("Usage: stop <server>");
@@ -762,9 +2051,50 @@ impl MCPHost {
                         Err(e) => info!("Error stopping server: {}", e),
                     }
                 }
+                "tools" if server_args == ["--all"] => {
+                    let all_tools = self.list_all_server_tools().await?;
+                    let mut offered_by: HashMap<&str, Vec<&str>> = HashMap::new();
+                    for (server_name, tools) in &all_tools {
+                        for tool in tools {
+                            offered_by.entry(tool.name.as_str()).or_default().push(server_name.as_str());
+                        }
+                    }
+
+                    let mut server_names: Vec<&String> = all_tools.keys().collect();
+                    server_names.sort();
+                    for server_name in server_names {
+                        println!("\n{}", style(server_name).cyan().bold());
+                        for tool in &all_tools[server_name] {
+                            let mut others: Vec<&str> = offered_by[tool.name.as_str()].iter()
+                                .copied()
+                                .filter(|other| *other != server_name.as_str())
+                                .collect();
+                            others.sort();
+                            let also = if others.is_empty() {
+                                String::new()
+                            } else {
+                                format!(" {}", style(format!("(also on {})", others.join(", "))).dim())
+                            };
+                            println!("  {} - {}{}", style(&tool.name).yellow(), tool.description.as_deref().unwrap_or_default(), also);
+                        }
+                    }
+                }
+                "find_tool" => {
+                    if server_args.len() != 1 {
+                        info!("Usage: find_tool <tool>");
+                        continue;
+                    }
+
+                    let server_names = self.find_tool(server_args[0]).await;
+                    if server_names.is_empty() {
+                        println!("No server offers the tool '{}'", server_args[0]);
+                    } else {
+                        println!("{} is offered by: {}", style(server_args[0]).yellow(), server_names.join(", "));
+                    }
+                }
                 "tools" => {
                     if server_args.len() != 1 {
-                        info!("Usage: tools <server>");
+                        info!("Usage: tools <server> | tools --all");
                         continue;
                     }
 
@@ -781,20 +2111,142 @@ impl MCPHost {
                                     .map(|line| format!("      {}", line))
                                     .collect::<Vec<_>>()
                                     .join("\n"));
+                                if let Some(output_schema) = tool.output_schema {
+                                    info!("    Result schema:");
+                                    info!("{}", serde_json::to_string_pretty(&output_schema)?
+                                        .split('\n')
+                                        .map(|line| format!("      {}", line))
+                                        .collect::<Vec<_>>()
+                                        .join("\n"));
+                                }
                             }
                             // info!();
                         }
                         Err(e) => info!("Error: {}", e),
                     }
                 }
-                "call" => {
+                "get_stats" => {
+                    if server_args.len() != 1 {
+                        info!("Usage: get_stats <server>");
+                        continue;
+                    }
+
+                    match self.get_server_stats(server_args[0]).await {
+                        Ok(stats) => println!("{}", serde_json::to_string_pretty(&stats)?),
+                        Err(e) => info!("Error: {}", e),
+                    }
+                }
+                "health" => {
+                    if server_args.len() != 1 {
+                        info!("Usage: health <server>");
+                        continue;
+                    }
+
+                    println!("{}: {}", style(server_args[0]).yellow(), describe_health(self.check_health(server_args[0]).await));
+                }
+                "watch" => {
+                    if server_args.len() != 1 {
+                        info!("Usage: watch <server>");
+                        continue;
+                    }
+
+                    if let Err(e) = self.watch_server(server_args[0], &mut lines).await {
+                        info!("Error: {}", e);
+                    }
+                }
+                "log_level" => {
                     if server_args.len() != 2 {
-                        info!("Usage: call <server> <tool>");
+                        info!("Usage: log_level <server> <level>");
                         continue;
                     }
 
-                    let server_name = server_args[0];
-                    let tool_name = server_args[1];
+                    match self.set_server_log_level(server_args[0], server_args[1]).await {
+                        Ok(()) => println!("Server {} now logs at {} and above", server_args[0], server_args[1]),
+                        Err(e) => info!("Error: {}", e),
+                    }
+                }
+                "chain" => {
+                    let library = tool_chain::ToolChainLibrary::builtin()?;
+                    let mut name_words = Vec::new();
+                    let mut variables = HashMap::new();
+                    let mut rest = server_args.iter();
+                    let mut valid = true;
+                    while let Some(arg) = rest.next() {
+                        if *arg == "--var" {
+                            match rest.next().and_then(|var| var.split_once('=')) {
+                                Some((key, value)) => { variables.insert(key.to_string(), value.to_string()); }
+                                None => valid = false,
+                            }
+                        } else {
+                            name_words.push(*arg);
+                        }
+                    }
+
+                    if !valid || name_words.is_empty() {
+                        info!("Usage: chain <name> [--var key=value ...]");
+                        info!("Available chains:");
+                        for chain in library.chains() {
+                            info!("  {}", chain.title);
+                        }
+                        continue;
+                    }
+
+                    let name = name_words.join(" ");
+                    let Some(chain) = library.find(&name) else {
+                        info!("Error: no chain named '{}'", name);
+                        continue;
+                    };
+
+                    let executor = tool_chain::ToolChainExecutor::new(self, variables);
+                    match executor.execute(chain).await {
+                        Ok(result) => {
+                            for (i, step) in result.steps.iter().enumerate() {
+                                let status = if step.is_error { style("failed").red() } else { style("ok").green() };
+                                println!("\n{} {} ({}): {}", style(format!("Step {}", i + 1)).cyan().bold(), step.tool, status, step.args);
+                                println!("{}", step.output.trim_end());
+                            }
+                            if result.cost_usd > 0.0 {
+                                println!("\nInterpreting steps cost about ${:.4}", result.cost_usd);
+                            }
+                            if result.completed {
+                                println!("\n{}", style("Chain completed").green().bold());
+                            } else {
+                                println!("\n{}", style("Chain stopped at a failing step").red().bold());
+                            }
+                        }
+                        Err(e) => info!("Error: {}", e),
+                    }
+                }
+                "cd" => {
+                    if server_args.len() != 1 {
+                        info!("Usage: cd <dir>");
+                        continue;
+                    }
+
+                    if let Err(e) = self.change_working_directory(server_args[0]).await {
+                        info!("Error: {}", e);
+                    }
+                }
+                "call" => {
+                    let (server_args, format) = match server_args {
+                        [rest @ .., "--format", format] => match OutputFormat::parse(format) {
+                            Ok(format) => (rest, format),
+                            Err(e) => {
+                                info!("{}", e);
+                                continue;
+                            }
+                        },
+                        _ => (server_args, OutputFormat::Json),
+                    };
+                    // Without a server the tool goes to whichever server offers it
+                    let (server_name, tool_name) = match server_args {
+                        [tool] => (None, *tool),
+                        [server, tool] => (Some(*server), *tool),
+                        _ => {
+                            info!("Usage: call [server] <tool> [--format json|table|csv]");
+                            continue;
+                        }
+                    };
 
                     info!("Enter arguments (JSON):");
                     let mut json_input = String::new();
@@ -809,25 +2261,75 @@ impl MCPHost {
                         }
                     };
 
-                    match self.call_tool(server_name, tool_name, args_value).await {
-                        Ok(result) => {
-                            if result.trim().is_empty() {
-                                println!("\n{}", style("No results returned").yellow());
-                            } else {
-                                println!("\n{}", style("Result:").cyan().bold());
-                                if result.trim().starts_with('{') || result.trim().starts_with('[') {
-                                    // Pretty print JSON
-                                    if let Ok(json) = serde_json::from_str::<Value>(&result) {
-                                        println!("```json\n{}\n```", serde_json::to_string_pretty(&json)?);
-                                    } else {
-                                        println!("{}", result);
-                                    }
-                                } else {
-                                    println!("{}", result);
-                                }
-                            }
+                    let result = match server_name {
+                        Some(server_name) => self.call_tool(server_name, tool_name, args_value).await,
+                        None => self.call_tool_on_any_server(tool_name, args_value).await,
+                    };
+                    result_format::print_tool_result(result, format)?;
+                }
+                "history" => {
+                    let calls = match audit_log::read_tool_calls() {
+                        Ok(calls) => calls,
+                        Err(e) => {
+                            println!("{}: {}", style("Cannot read history").red().bold(), e);
+                            continue;
+                        }
+                    };
+
+                    if let ["replay", index] = server_args {
+                        // Indices are those `history` prints, counting from the oldest call
+                        let Some(entry) = index.parse::<usize>().ok()
+                            .and_then(|index| index.checked_sub(1))
+                            .and_then(|index| calls.get(index))
+                        else {
+                            info!("No history entry {}", index);
+                            continue;
+                        };
+                        let Some(arguments) = &entry.arguments else {
+                            info!("The arguments of history entry {} weren't recorded; set MCP_AUDIT_LOG_ARGS=1 to record them", index);
+                            continue;
+                        };
+                        println!("Replaying {} on {} with {}", style(&entry.tool).yellow(), style(&entry.server).yellow(), arguments);
+                        let result = self.call_tool(&entry.server, &entry.tool, arguments.clone()).await;
+                        result_format::print_tool_result(result, OutputFormat::Json)?;
+                        continue;
+                    }
+
+                    let mut server = None;
+                    let mut tool = None;
+                    let mut limit = DEFAULT_HISTORY_LIMIT;
+                    let mut args = server_args.iter();
+                    let mut valid = true;
+                    while let Some(arg) = args.next() {
+                        match *arg {
+                            "--limit" => match args.next().and_then(|limit| limit.parse().ok()) {
+                                Some(n) => limit = n,
+                                None => valid = false,
+                            },
+                            "--tool" => match args.next() {
+                                Some(name) => tool = Some(*name),
+                                None => valid = false,
+                            },
+                            name if server.is_none() && !name.starts_with("--") => server = Some(name),
+                            _ => valid = false,
                         }
-                        Err(e) => println!("{}: {}", style("Error calling tool").red().bold(), e),
+                    }
+                    if !valid {
+                        info!("Usage: history [server] [--limit N] [--tool name] | history replay <index>");
+                        continue;
+                    }
+
+                    let matching: Vec<(usize, &audit_log::ToolCallEntry)> = calls.iter()
+                        .enumerate()
+                        .map(|(index, entry)| (index + 1, entry))
+                        .filter(|(_, entry)| server.is_none_or(|server| entry.server == server))
+                        .filter(|(_, entry)| tool.is_none_or(|tool| entry.tool == tool))
+                        .collect();
+                    if matching.is_empty() {
+                        println!("\n{}", style("No tool calls recorded").yellow());
+                    } else {
+                        let shown = &matching[matching.len().saturating_sub(limit)..];
+                        println!("\n{}", result_format::history_table(shown));
                     }
                 }
                 "quit" => break,
@@ -840,21 +2342,75 @@ impl MCPHost {
 }
 
 
+mod session_manager;
 mod web_interface;
 
 use axum::Router;
 use tower_http::trace::TraceLayer;
 use std::net::SocketAddr;
 
+/// How long a server gets to answer `health`
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The outcome of `check_health` as one line
+fn describe_health(health: Result<Option<Value>>) -> String {
+    match health {
+        Ok(Some(health)) => format!(
+            "{}, up {}s, {} tools, version {}",
+            health["status"].as_str().unwrap_or("unknown"),
+            health["uptime_secs"],
+            health["tools_count"],
+            health["version"].as_str().unwrap_or("unknown"),
+        ),
+        Ok(None) => "responding (no health method)".to_string(),
+        Err(e) => format!("not responding: {}", e),
+    }
+}
+
+/// How often `watch` redraws
+const WATCH_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Tool calls the `history` command lists when no `--limit` is given
+const DEFAULT_HISTORY_LIMIT: usize = 20;
+
+/// Resolves when the host is asked to exit with SIGTERM
+async fn terminate_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+                info!("Received SIGTERM, shutting down");
+            }
+            Err(e) => {
+                warn!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    std::future::pending::<()>().await;
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
     info!("Starting mcp_host application");
+    if let Err(e) = telemetry::init() {
+        warn!("Failed to set up OpenTelemetry export: {}", e);
+    }
 
     info!("Initializing MCPHost");
-    let host = MCPHost::new().await?;
+    let host = Arc::new(MCPHost::new().await?);
     info!("MCPHost initialized successfully");
 
+    // Restart crashed servers in the background
+    tokio::spawn({
+        let host = Arc::clone(&host);
+        async move { host.monitor_servers().await }
+    });
+
     let mut args: Vec<String> = std::env::args().collect();
     
     // Handle load_config argument if present
@@ -873,12 +2429,13 @@ async fn main() -> Result<()> {
     }
 
     // Check if we should run in web mode
+    let mut terminated = false;
     if args.len() > 1 && args[1] == "web" {
         info!("Starting web interface");
         
-        let host = Arc::new(host);
         let app_state = web_interface::WebAppState::new(Arc::clone(&host));
-        let app = web_interface::create_router(app_state)
+        let api_key = web_interface::api_key_from_env();
+        let app = web_interface::create_router(app_state, api_key)
             .layer(TraceLayer::new_for_http());
 
         let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
@@ -888,18 +2445,24 @@ async fn main() -> Result<()> {
             tokio::net::TcpListener::bind(&addr).await?,
             app.into_make_service(),
         )
+        .with_graceful_shutdown(terminate_signal())
         .await?;
     } else {
         // Run in CLI mode
         info!("Starting CLI interface");
-        host.run_cli().await?;
-
-        // Stop all servers before exit
-        let servers = host.servers.lock().await;
-        for name in servers.keys() {
-            let _ = host.stop_server(name).await;
+        tokio::select! {
+            result = host.run_cli() => result?,
+            _ = terminate_signal() => terminated = true,
         }
     }
 
+    // Stop all servers before exit. This must not save the config, which would then
+    // list no servers.
+    host.shutdown_all_servers().await;
+    telemetry::shutdown();
+    if terminated {
+        // The runtime would wait for the blocking read of stdin to finish before exiting
+        std::process::exit(0);
+    }
     Ok(())
 }
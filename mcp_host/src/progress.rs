@@ -0,0 +1,66 @@
+use lazy_static::lazy_static;
+use serde_json::Value;
+use shared_protocol_objects::ProgressNotification;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::mpsc;
+
+lazy_static! {
+    static ref LISTENERS: Mutex<HashMap<String, mpsc::Sender<ProgressNotification>>> = Mutex::new(HashMap::new());
+}
+
+/// Where the progress of a tool call goes: the token sent as its `_meta.progressToken` and
+/// the channel notifications carrying it are passed to
+#[derive(Debug, Clone)]
+pub struct ProgressListener {
+    pub token: String,
+    pub tx: mpsc::Sender<ProgressNotification>,
+}
+
+impl ProgressListener {
+    /// A listener with a fresh random token
+    pub fn new(tx: mpsc::Sender<ProgressNotification>) -> Self {
+        Self { token: uuid::Uuid::new_v4().to_string(), tx }
+    }
+}
+
+/// Keeps progress notifications for one token flowing to their listener; dropping it stops them
+pub struct ProgressSubscription {
+    token: String,
+}
+
+impl Drop for ProgressSubscription {
+    fn drop(&mut self) {
+        LISTENERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).remove(&self.token);
+    }
+}
+
+/// Send the `notifications/progress` of any server that carry the listener's token to it
+pub fn subscribe(listener: &ProgressListener) -> ProgressSubscription {
+    LISTENERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(listener.token.clone(), listener.tx.clone());
+    ProgressSubscription { token: listener.token.clone() }
+}
+
+/// Hand the params of a `notifications/progress` notification to the listener of its token.
+/// Updates are dropped while the listener is behind; the next one supersedes them anyway.
+pub fn route(server_name: &str, params: &Value) {
+    let token = match params.get("progressToken") {
+        Some(Value::String(token)) => token.clone(),
+        Some(token) => token.to_string(),
+        None => return,
+    };
+    let notification: ProgressNotification = match serde_json::from_value(params.clone()) {
+        Ok(notification) => notification,
+        Err(e) => {
+            log::debug!("Ignoring malformed progress from server '{}': {}", server_name, e);
+            return;
+        }
+    };
+
+    let listeners = LISTENERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    match listeners.get(&token) {
+        Some(tx) => { let _ = tx.try_send(notification); }
+        None => log::debug!("No listener for progress token {} of server '{}'", token, server_name),
+    }
+}
@@ -0,0 +1,128 @@
+use crate::audit_log::ToolCallEntry;
+use anyhow::{anyhow, Result};
+use comfy_table::{presets::ASCII_FULL, Table};
+use console::style;
+use serde_json::{Map, Value};
+
+/// How the `call` command prints a tool result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Table,
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn parse(format: &str) -> Result<Self> {
+        match format {
+            "json" => Ok(Self::Json),
+            "table" => Ok(Self::Table),
+            "csv" => Ok(Self::Csv),
+            _ => Err(anyhow!("Unknown format '{}': expected json, table or csv", format)),
+        }
+    }
+}
+
+type Row = Map<String, Value>;
+
+/// The columns and rows of `value` if it is an array of objects that all have the same keys
+fn tabular_rows(value: &Value) -> Option<(Vec<String>, Vec<&Row>)> {
+    let items = value.as_array()?;
+    let rows: Vec<&Row> = items.iter().map(Value::as_object).collect::<Option<_>>()?;
+    let first = rows.first()?;
+    let columns: Vec<String> = first.keys().cloned().collect();
+    let uniform = rows.iter()
+        .all(|row| row.len() == columns.len() && columns.iter().all(|column| row.contains_key(column)));
+    uniform.then_some((columns, rows))
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Render `result` as an ASCII table or as CSV. Returns None if the result isn't tabular, or
+/// for the JSON format, so the caller falls back to printing JSON.
+pub fn render(result: &str, format: OutputFormat) -> Result<Option<String>> {
+    if format == OutputFormat::Json {
+        return Ok(None);
+    }
+    let Ok(value) = serde_json::from_str::<Value>(result) else {
+        return Ok(None);
+    };
+    let Some((columns, rows)) = tabular_rows(&value) else {
+        return Ok(None);
+    };
+
+    match format {
+        OutputFormat::Table => {
+            let mut table = Table::new();
+            table.load_preset(ASCII_FULL).set_header(&columns);
+            for row in rows {
+                table.add_row(columns.iter().map(|column| cell(&row[column])));
+            }
+            Ok(Some(table.to_string()))
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            writer.write_record(&columns)?;
+            for row in rows {
+                writer.write_record(columns.iter().map(|column| cell(&row[column])))?;
+            }
+            Ok(Some(String::from_utf8(writer.into_inner()?)?))
+        }
+        OutputFormat::Json => Ok(None),
+    }
+}
+
+/// Print the outcome of a tool call: a table or CSV if asked for and the result is tabular,
+/// pretty JSON if it parses, else the text as is
+pub fn print_tool_result(result: Result<String>, format: OutputFormat) -> Result<()> {
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            println!("{}: {}", style("Error calling tool").red().bold(), e);
+            return Ok(());
+        }
+    };
+    if result.trim().is_empty() {
+        println!("\n{}", style("No results returned").yellow());
+        return Ok(());
+    }
+
+    println!("\n{}", style("Result:").cyan().bold());
+    if let Some(rendered) = render(result.trim(), format)? {
+        println!("{}", rendered);
+    } else if result.trim().starts_with('{') || result.trim().starts_with('[') {
+        // Pretty print JSON
+        if let Ok(json) = serde_json::from_str::<Value>(&result) {
+            println!("```json\n{}\n```", serde_json::to_string_pretty(&json)?);
+        } else {
+            println!("{}", result);
+        }
+    } else {
+        println!("{}", result);
+    }
+    Ok(())
+}
+
+/// The `history` listing: one row per tool call, numbered for `history replay`
+pub fn history_table(entries: &[(usize, &ToolCallEntry)]) -> String {
+    let mut table = Table::new();
+    table.load_preset(ASCII_FULL)
+        .set_header(["#", "timestamp", "server", "tool", "duration_ms", "status"]);
+    for (index, entry) in entries {
+        table.add_row([
+            index.to_string(),
+            entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+            entry.server.clone(),
+            entry.tool.clone(),
+            entry.duration_ms.to_string(),
+            entry.response_status.clone(),
+        ]);
+    }
+    table.to_string()
+}
@@ -0,0 +1,69 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use shared_protocol_objects::{error_response, success_response, JsonRpcErrorCode, JsonRpcResponse};
+use std::sync::Arc;
+
+use crate::ai_client::{AIClient, GenerationConfig};
+
+/// Answer a request a server sent to the host. Only `sampling/createMessage` is supported.
+pub async fn handle_server_request(ai_client: Option<Arc<dyn AIClient>>, request: &Value) -> JsonRpcResponse {
+    let id = request.get("id").cloned();
+    let method = request.get("method").and_then(Value::as_str).unwrap_or_default();
+    if method != "sampling/createMessage" {
+        return error_response(id, JsonRpcErrorCode::MethodNotFound, "Method not found");
+    }
+
+    let Some(client) = ai_client else {
+        return error_response(id, JsonRpcErrorCode::InternalError, "No AI client configured");
+    };
+
+    let params = request.get("params").cloned().unwrap_or_default();
+    match create_message(client.as_ref(), &params).await {
+        Ok(result) => success_response(id, result),
+        Err(e) => {
+            log::error!("Sampling request failed: {}", e);
+            error_response(id, JsonRpcErrorCode::InternalError, &e.to_string())
+        }
+    }
+}
+
+/// Run `sampling/createMessage` params through the AI client and build the MCP result
+async fn create_message(client: &dyn AIClient, params: &Value) -> Result<Value> {
+    let messages = params.get("messages")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow!("Missing messages"))?;
+
+    let mut builder = client.raw_builder();
+    if let Some(system_prompt) = params.get("systemPrompt").and_then(Value::as_str) {
+        builder = builder.system(system_prompt.to_string());
+    }
+    for message in messages {
+        let content = &message["content"];
+        let text = match content["type"].as_str() {
+            Some("text") => content["text"].as_str().unwrap_or_default().to_string(),
+            Some(other) => return Err(anyhow!("Unsupported sampling content type: {}", other)),
+            None => return Err(anyhow!("Message content has no type")),
+        };
+        builder = match message["role"].as_str() {
+            Some("user") => builder.user(text),
+            Some("assistant") => builder.assistant(text),
+            other => return Err(anyhow!("Unsupported message role: {:?}", other)),
+        };
+    }
+
+    builder = builder.config(GenerationConfig {
+        temperature: params.get("temperature").and_then(Value::as_f64).map(|t| t as f32),
+        max_tokens: params.get("maxTokens").and_then(Value::as_u64).map(|t| t as u32),
+        ..Default::default()
+    });
+
+    log::info!("Running sampling request with {} message(s)", messages.len());
+    let text = builder.execute().await?;
+
+    Ok(json!({
+        "role": "assistant",
+        "content": { "type": "text", "text": text },
+        "model": client.model_name(),
+        "stopReason": "endTurn"
+    }))
+}
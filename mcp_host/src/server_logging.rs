@@ -0,0 +1,49 @@
+use log::Level;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Logging levels of the MCP spec, least severe first
+pub const LOG_LEVELS: [&str; 8] = [
+    "debug", "info", "notice", "warning", "error", "critical", "alert", "emergency",
+];
+
+/// Params of a `notifications/message` notification
+#[derive(Debug, Deserialize)]
+struct LogMessage {
+    level: String,
+    #[serde(default)]
+    logger: Option<String>,
+    #[serde(default)]
+    data: Value,
+}
+
+fn host_level(level: &str) -> Level {
+    match level {
+        "debug" => Level::Debug,
+        "info" | "notice" => Level::Info,
+        "warning" => Level::Warn,
+        _ => Level::Error,
+    }
+}
+
+/// Write a log line a server sent with `notifications/message` to the host log. The target is
+/// `mcp_server::<server>`, so `RUST_LOG` can filter each server on its own.
+pub fn log_server_message(server_name: &str, params: &Value) {
+    let message: LogMessage = match serde_json::from_value(params.clone()) {
+        Ok(message) => message,
+        Err(e) => {
+            log::warn!("Ignoring malformed log message from server '{}': {}", server_name, e);
+            return;
+        }
+    };
+
+    let target = format!("mcp_server::{}", server_name);
+    let text = match &message.data {
+        Value::String(text) => text.clone(),
+        data => data.to_string(),
+    };
+    match &message.logger {
+        Some(logger) => log::log!(target: &target, host_level(&message.level), "[{}] {}", logger, text),
+        None => log::log!(target: &target, host_level(&message.level), "{}", text),
+    }
+}
@@ -0,0 +1,87 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::conversation_state::ConversationState;
+use crate::MCPHost;
+
+/// One conversation of the web interface and the server whose tools it uses
+#[derive(Clone)]
+pub struct Session {
+    pub server_name: String,
+    pub created_at: DateTime<Utc>,
+    pub conversation: Arc<Mutex<ConversationState>>,
+}
+
+/// What `GET /sessions` reports about a session
+#[derive(Debug, Serialize)]
+pub struct SessionInfo {
+    pub session_id: Uuid,
+    pub server_name: String,
+    pub created_at: DateTime<Utc>,
+    /// `None` while the session is busy answering a message
+    pub message_count: Option<usize>,
+}
+
+/// The web interface's chat sessions. Each conversation has its own lock, so sessions
+/// don't wait on each other.
+#[derive(Clone, Default)]
+pub struct SessionManager {
+    sessions: Arc<Mutex<HashMap<Uuid, Session>>>,
+}
+
+impl SessionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a conversation with `server_name` and return its session ID
+    pub async fn create(&self, host: &MCPHost, server_name: &str) -> Result<Uuid> {
+        let conversation = host.enter_chat_mode(server_name).await?;
+        let session_id = Uuid::new_v4();
+        self.insert(session_id, server_name, conversation).await;
+        Ok(session_id)
+    }
+
+    pub async fn insert(&self, session_id: Uuid, server_name: &str, conversation: ConversationState) {
+        log::info!("[Sessions] New session {} for server {}", session_id, server_name);
+        let session = Session {
+            server_name: server_name.to_string(),
+            created_at: Utc::now(),
+            conversation: Arc::new(Mutex::new(conversation)),
+        };
+        self.sessions.lock().await.insert(session_id, session);
+    }
+
+    pub async fn get(&self, session_id: &Uuid) -> Option<Session> {
+        self.sessions.lock().await.get(session_id).cloned()
+    }
+
+    /// Remove a session. Returns whether it existed.
+    pub async fn remove(&self, session_id: &Uuid) -> bool {
+        let removed = self.sessions.lock().await.remove(session_id).is_some();
+        if removed {
+            log::info!("[Sessions] Removed session {}", session_id);
+        }
+        removed
+    }
+
+    /// Metadata of every session, oldest first
+    pub async fn list(&self) -> Vec<SessionInfo> {
+        let mut infos: Vec<SessionInfo> = self.sessions.lock().await
+            .iter()
+            .map(|(session_id, session)| SessionInfo {
+                session_id: *session_id,
+                server_name: session.server_name.clone(),
+                created_at: session.created_at,
+                message_count: session.conversation.try_lock().ok().map(|c| c.messages.len()),
+            })
+            .collect();
+        infos.sort_by_key(|info| info.created_at);
+        infos
+    }
+}
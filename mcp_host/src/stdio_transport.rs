@@ -0,0 +1,197 @@
+use anyhow::{anyhow, Result};
+use log::{debug, error, info};
+use serde_json::Value;
+use shared_protocol_objects::{JsonRpcRequest, JsonRpcResponse};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{ChildStdin, ChildStdout};
+use tokio::sync::{oneshot, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::ai_client::SharedAIClient;
+use crate::{notification_method, notification_params, progress, sampling, server_logging, CapabilityCache};
+
+type PendingResponses = Arc<std::sync::Mutex<HashMap<String, oneshot::Sender<Result<JsonRpcResponse>>>>>;
+
+/// What the reader of a server's stdout needs besides the pipe: the AI client for the
+/// server's own `sampling/createMessage` requests, and the tool lists to drop when the
+/// server says they changed
+#[derive(Clone)]
+pub struct ReaderContext {
+    pub ai_client: SharedAIClient,
+    pub capability_cache: CapabilityCache,
+}
+
+/// JSON-RPC over a server process's stdin and stdout. A single task reads stdout and hands
+/// each response to the request with its id, so any number of requests, pings included, can
+/// wait at once and answers may come back in any order.
+#[derive(Debug)]
+pub struct StdioConnection {
+    stdin: Arc<Mutex<ChildStdin>>,
+    pending: PendingResponses,
+    closed: Arc<AtomicBool>,
+    reader: JoinHandle<()>,
+}
+
+impl Drop for StdioConnection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Removes a request from the pending ones when its caller stops waiting, whether it got an
+/// answer, timed out or was cancelled
+struct PendingGuard<'a> {
+    pending: &'a PendingResponses,
+    key: String,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        lock(self.pending).remove(&self.key);
+    }
+}
+
+fn lock(pending: &PendingResponses) -> std::sync::MutexGuard<'_, HashMap<String, oneshot::Sender<Result<JsonRpcResponse>>>> {
+    pending.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Ids are matched by their JSON form, so the string `"1"` and the number `1` differ
+fn id_key(id: &Value) -> String {
+    id.to_string()
+}
+
+impl StdioConnection {
+    pub fn start(server_name: &str, stdin: ChildStdin, stdout: ChildStdout, context: ReaderContext) -> Self {
+        let stdin = Arc::new(Mutex::new(stdin));
+        let pending = PendingResponses::default();
+        let closed = Arc::new(AtomicBool::new(false));
+        let reader = tokio::spawn(read_messages(
+            server_name.to_string(),
+            stdout,
+            Arc::clone(&stdin),
+            Arc::clone(&pending),
+            Arc::clone(&closed),
+            context,
+        ));
+        Self { stdin, pending, closed, reader }
+    }
+
+    /// Send `request` and wait up to `request_timeout` for the response with its id.
+    /// Notifications, having no id, return an empty response once written.
+    pub async fn request(&self, request: &JsonRpcRequest, request_timeout: Duration) -> Result<JsonRpcResponse> {
+        let line = serde_json::to_string(request)? + "\n";
+        debug!("DEBUG: Sending request: {}", line.trim());
+        if request.id.is_null() {
+            write_line(&self.stdin, &line).await?;
+            return Ok(JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id: Value::Null,
+                result: None,
+                error: None,
+            });
+        }
+
+        // Registered before writing, so an answer arriving right away isn't missed
+        let (tx, rx) = oneshot::channel();
+        let key = id_key(&request.id);
+        lock(&self.pending).insert(key.clone(), tx);
+        let _guard = PendingGuard { pending: &self.pending, key };
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(anyhow!("Server closed connection"));
+        }
+        write_line(&self.stdin, &line).await?;
+
+        match tokio::time::timeout(request_timeout, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(anyhow!("Response channel closed")),
+            Err(_) => Err(anyhow!("Request timed out")),
+        }
+    }
+}
+
+async fn write_line(stdin: &Mutex<ChildStdin>, line: &str) -> Result<()> {
+    let mut stdin = stdin.lock().await;
+    stdin.write_all(line.as_bytes()).await
+        .map_err(|e| anyhow!("Failed to write to stdin: {}", e))?;
+    stdin.flush().await
+        .map_err(|e| anyhow!("Failed to flush stdin: {}", e))
+}
+
+/// Read the server's stdout until it closes. Responses go to the request waiting for their
+/// id, requests from the server are answered, and notifications are handled in place.
+async fn read_messages(
+    server_name: String,
+    stdout: ChildStdout,
+    stdin: Arc<Mutex<ChildStdin>>,
+    pending: PendingResponses,
+    closed: Arc<AtomicBool>,
+    context: ReaderContext,
+) {
+    let mut lines = BufReader::new(stdout).lines();
+    let reason = loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break "Server closed connection".to_string(),
+            Err(e) => break format!("Failed to read response: {}", e),
+        };
+        debug!("DEBUG: Received response: {}", line.trim());
+        let message: Value = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                debug!("Ignoring unparseable line from server '{}': {}: {}", server_name, e, line.trim());
+                continue;
+            }
+        };
+
+        if message.get("method").is_some() && message.get("id").is_some_and(|id| !id.is_null()) {
+            // Sampling waits on the AI, so it mustn't hold up the responses behind it
+            let stdin = Arc::clone(&stdin);
+            let ai_client = context.ai_client.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+            tokio::spawn(async move {
+                let reply = sampling::handle_server_request(ai_client, &message).await;
+                let reply_line = serde_json::to_string(&reply).unwrap_or_default() + "\n";
+                if let Err(e) = write_line(&stdin, &reply_line).await {
+                    error!("Failed to answer server request: {}", e);
+                }
+            });
+            continue;
+        }
+
+        if let Some(method) = notification_method(&message) {
+            if method == "notifications/tools/list_changed" {
+                info!("Tools of server '{}' changed", server_name);
+                context.capability_cache.lock().await.remove(&server_name);
+            } else if method == "notifications/message" {
+                server_logging::log_server_message(&server_name, notification_params(&message));
+            } else if method == "notifications/progress" {
+                progress::route(&server_name, notification_params(&message));
+            } else {
+                debug!("Ignoring server notification: {}", line.trim());
+            }
+            continue;
+        }
+
+        let Some(id) = message.get("id") else {
+            debug!("Ignoring message without an id from server '{}': {}", server_name, line.trim());
+            continue;
+        };
+        let Some(tx) = lock(&pending).remove(&id_key(id)) else {
+            // A late answer to a request that timed out or was cancelled
+            debug!("Skipping stale response: {}", line.trim());
+            continue;
+        };
+        let response = serde_json::from_value(message)
+            .map_err(|e| anyhow!("Failed to parse response '{}': {}", line.trim(), e));
+        let _ = tx.send(response);
+    };
+
+    debug!("Stopped reading from server '{}': {}", server_name, reason);
+    closed.store(true, Ordering::SeqCst);
+    for (_, tx) in lock(&pending).drain() {
+        let _ = tx.send(Err(anyhow!("{}", reason)));
+    }
+}
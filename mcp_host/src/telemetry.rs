@@ -0,0 +1,67 @@
+use anyhow::Result;
+use std::future::Future;
+
+/// Set up the OTLP span exporter when the `opentelemetry` feature is enabled. The collector
+/// address is read from `OTEL_EXPORTER_OTLP_ENDPOINT`.
+#[cfg(feature = "opentelemetry")]
+pub fn init() -> Result<()> {
+    use opentelemetry::KeyValue;
+    use opentelemetry_sdk::{runtime, trace::TracerProvider, Resource};
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .build()?;
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new([KeyValue::new("service.name", "mcp_host")]))
+        .build();
+    opentelemetry::global::set_tracer_provider(provider);
+    log::info!("Exporting OpenTelemetry spans over OTLP");
+    Ok(())
+}
+
+#[cfg(not(feature = "opentelemetry"))]
+pub fn init() -> Result<()> {
+    Ok(())
+}
+
+/// Flush the spans that haven't been exported yet
+pub fn shutdown() {
+    #[cfg(feature = "opentelemetry")]
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// Run `future` in a span named `name`, as a child of the span it's awaited in. A failed
+/// result marks the span as an error.
+#[cfg(feature = "opentelemetry")]
+pub async fn in_span<F, T>(name: String, attributes: &[(&'static str, String)], future: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    use opentelemetry::trace::{FutureExt, SpanKind, Status, TraceContextExt, Tracer};
+    use opentelemetry::{global, Context, KeyValue};
+
+    let tracer = global::tracer("mcp_host");
+    let span = tracer
+        .span_builder(name)
+        .with_kind(SpanKind::Client)
+        .with_attributes(attributes.iter().map(|(key, value)| KeyValue::new(*key, value.clone())))
+        .start_with_context(&tracer, &Context::current());
+    let cx = Context::current_with_span(span);
+
+    let result = future.with_context(cx.clone()).await;
+    let span = cx.span();
+    if let Err(e) = &result {
+        span.set_status(Status::error(e.to_string()));
+    }
+    span.end();
+    result
+}
+
+#[cfg(not(feature = "opentelemetry"))]
+pub async fn in_span<F, T>(_name: String, _attributes: &[(&'static str, String)], future: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    future.await
+}
@@ -0,0 +1,90 @@
+use chrono::{DateTime, Local};
+use comfy_table::{presets::ASCII_FULL, Table};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Calls of one tool made through this host
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ToolCounters {
+    pub calls: u64,
+    pub errors: u64,
+    pub total_duration_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LastCall {
+    pub tool: String,
+    pub at: DateTime<Local>,
+    pub duration_ms: u64,
+    pub succeeded: bool,
+}
+
+/// Tool calls the host made to one server, for `watch` when the server doesn't report
+/// `tools/stats` itself
+#[derive(Debug, Clone, Default)]
+pub struct ServerActivity {
+    pub tools: HashMap<String, ToolCounters>,
+    pub last_call: Option<LastCall>,
+}
+
+impl ServerActivity {
+    pub fn record(&mut self, tool: &str, duration: Duration, succeeded: bool) {
+        let duration_ms = duration.as_millis() as u64;
+        let counters = self.tools.entry(tool.to_string()).or_default();
+        counters.calls += 1;
+        counters.total_duration_ms += duration_ms;
+        if !succeeded {
+            counters.errors += 1;
+        }
+        self.last_call = Some(LastCall {
+            tool: tool.to_string(),
+            at: Local::now(),
+            duration_ms,
+            succeeded,
+        });
+    }
+}
+
+/// One frame of the `watch` display. `source` names where the counters came from.
+pub fn render_watch(
+    server: &str,
+    health: &str,
+    source: &str,
+    tools: &HashMap<String, ToolCounters>,
+    last_call: Option<&LastCall>,
+) -> String {
+    let mut table = Table::new();
+    table.load_preset(ASCII_FULL)
+        .set_header(["tool", "calls", "avg_ms", "error_rate"]);
+    let mut names: Vec<&String> = tools.keys().collect();
+    names.sort();
+    for name in names {
+        let counters = &tools[name];
+        let (avg_ms, error_rate) = match counters.calls {
+            0 => (0, 0.0),
+            calls => (counters.total_duration_ms / calls, counters.errors as f64 * 100.0 / calls as f64),
+        };
+        table.add_row([
+            name.clone(),
+            counters.calls.to_string(),
+            avg_ms.to_string(),
+            format!("{:.1}%", error_rate),
+        ]);
+    }
+
+    let last_call = match last_call {
+        Some(call) => format!(
+            "{} at {} ({} ms, {})",
+            call.tool,
+            call.at.format("%H:%M:%S"),
+            call.duration_ms,
+            if call.succeeded { "ok" } else { "error" }
+        ),
+        None => "none yet".to_string(),
+    };
+    format!(
+        "Watching {} ({}) - press q to stop\nHealth: {}\n\n{}\n\nLast call: {}\n",
+        server, source, health, table, last_call
+    )
+}
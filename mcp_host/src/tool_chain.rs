@@ -0,0 +1,209 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::conversation_state::{estimate_tokens, max_cost_from_env};
+use crate::cost::CostEstimator;
+use crate::MCPHost;
+
+/// A named sequence of tool calls, as found in `tool_chaining.json`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ToolChain {
+    pub title: String,
+    /// Each step is either `<tool> with <json args>`, a `{ "tool": ..., "args": ... }` object
+    /// or a plain instruction the AI turns into a tool call
+    pub steps: Vec<String>,
+}
+
+/// The chains shipped in `tool_chaining.json`
+#[derive(Debug, Clone)]
+pub struct ToolChainLibrary {
+    chains: Vec<ToolChain>,
+}
+
+impl ToolChainLibrary {
+    pub fn builtin() -> Result<Self> {
+        let chains = serde_json::from_str(include_str!("tool_chaining.json"))?;
+        Ok(Self { chains })
+    }
+
+    pub fn chains(&self) -> &[ToolChain] {
+        &self.chains
+    }
+
+    /// The chain whose title matches `name`, ignoring case
+    pub fn find(&self, name: &str) -> Option<&ToolChain> {
+        self.chains.iter().find(|chain| chain.title.eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// Output of one executed step
+#[derive(Debug, Clone, Serialize)]
+pub struct StepResult {
+    pub step: String,
+    pub tool: String,
+    pub args: Value,
+    pub output: String,
+    pub is_error: bool,
+}
+
+/// Outcome of running a chain. Execution stops at the first failing step, so `steps` may be
+/// shorter than the chain.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainResult {
+    pub title: String,
+    pub steps: Vec<StepResult>,
+    pub completed: bool,
+    /// Estimated cost of asking the AI to interpret plain-language steps
+    pub cost_usd: f64,
+}
+
+/// Runs the steps of a chain one after another on whichever server offers each tool
+pub struct ToolChainExecutor<'a> {
+    host: &'a MCPHost,
+    variables: HashMap<String, String>,
+    /// Spent on `interpret_step` so far, stopped at `MCP_MAX_COST_USD` like a chat
+    cost_usd: Mutex<f64>,
+    max_cost_usd: Option<f64>,
+}
+
+impl<'a> ToolChainExecutor<'a> {
+    pub fn new(host: &'a MCPHost, variables: HashMap<String, String>) -> Self {
+        Self { host, variables, cost_usd: Mutex::new(0.0), max_cost_usd: max_cost_from_env() }
+    }
+
+    fn spent(&self) -> f64 {
+        *self.cost_usd.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub async fn execute(&self, chain: &ToolChain) -> Result<ChainResult> {
+        // Fill in every step first so a missing variable fails before anything has run
+        let steps = chain.steps.iter()
+            .map(|step| self.substitute(step))
+            .collect::<Result<Vec<_>>>()?;
+        let tool_servers = self.tool_servers().await;
+        let mut result = ChainResult {
+            title: chain.title.clone(),
+            steps: Vec::new(),
+            completed: false,
+            cost_usd: 0.0,
+        };
+
+        for step in steps {
+            let step_result = self.run_step(step, &tool_servers, &chain.title).await;
+            let failed = step_result.is_error;
+            result.steps.push(step_result);
+            if failed {
+                result.cost_usd = self.spent();
+                return Ok(result);
+            }
+        }
+
+        result.completed = true;
+        result.cost_usd = self.spent();
+        Ok(result)
+    }
+
+    async fn run_step(&self, step: String, tool_servers: &HashMap<String, String>, title: &str) -> StepResult {
+        let call = match parse_step(&step) {
+            Some(call) => Ok(call),
+            None => self.interpret_step(&step).await,
+        };
+        let (tool, args) = match call {
+            Ok(call) => call,
+            Err(e) => return StepResult { step, tool: String::new(), args: Value::Null, output: e.to_string(), is_error: true },
+        };
+
+        let Some(server) = tool_servers.get(&tool) else {
+            let output = format!("No running server offers the tool '{}'", tool);
+            return StepResult { step, tool, args, output, is_error: true };
+        };
+
+        log::info!("Chain '{}': running {} on {}", title, tool, server);
+        let (output, is_error) = match self.host.call_tool(server, &tool, args.clone()).await {
+            Ok(output) => (output, false),
+            Err(e) => (e.to_string(), true),
+        };
+        StepResult { step, tool, args, output, is_error }
+    }
+
+    /// Replace `{{name}}` placeholders with the variable values. Values are JSON string
+    /// escaped so they can sit inside the quoted arguments of a step.
+    fn substitute(&self, step: &str) -> Result<String> {
+        let mut text = step.to_string();
+        for (name, value) in &self.variables {
+            let escaped = serde_json::to_string(value)?;
+            text = text.replace(&format!("{{{{{}}}}}", name), &escaped[1..escaped.len() - 1]);
+        }
+
+        if let Some(start) = text.find("{{") {
+            let end = text[start..].find("}}").map_or(text.len(), |end| start + end + 2);
+            return Err(anyhow!("Missing value for placeholder {}, pass it with --var", &text[start..end]));
+        }
+        Ok(text)
+    }
+
+    /// Ask the AI to turn a plain-language step into a tool call
+    async fn interpret_step(&self, step: &str) -> Result<(String, Value)> {
+        let client = self.host.ai_client()
+            .ok_or_else(|| anyhow!("Step '{}' needs an AI client to interpret it", step))?;
+        if let Some(max) = self.max_cost_usd.filter(|&max| self.spent() > max) {
+            return Err(anyhow!("Cost budget of ${:.4} reached, not asking the AI to interpret step '{}'", max, step));
+        }
+        let system = "Turn the instruction into a single tool call. Reply with only a JSON object of the form {\"tool\": \"<tool name>\", \"args\": {...}}.";
+        let response = client.raw_builder()
+            .system(system.to_string())
+            .user(step.to_string())
+            .execute()
+            .await?;
+        let cost = CostEstimator::default().estimate_cost(
+            &client.model_name(),
+            estimate_tokens(system) + estimate_tokens(step),
+            estimate_tokens(&response),
+        );
+        *self.cost_usd.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) += cost;
+
+        crate::find_any_json(&response)
+            .and_then(|json| tool_call_object(&json))
+            .ok_or_else(|| anyhow!("Could not understand step '{}' as a tool call", step))
+    }
+
+    /// Tool name to the server that offers it. The first server listing a tool wins.
+    async fn tool_servers(&self) -> HashMap<String, String> {
+        let mut tool_servers = HashMap::new();
+        for server in self.host.server_names().await {
+            match self.host.list_server_tools(&server).await {
+                Ok(tools) => {
+                    for tool in tools {
+                        tool_servers.entry(tool.name).or_insert_with(|| server.clone());
+                    }
+                }
+                Err(e) => log::warn!("Skipping server '{}' for chain tools: {}", server, e),
+            }
+        }
+        tool_servers
+    }
+}
+
+/// Parse a step written as `<tool> with <json>` or as `{ "tool": ..., "args": ... }`
+fn parse_step(step: &str) -> Option<(String, Value)> {
+    let step = step.trim();
+    if step.starts_with('{') {
+        return tool_call_object(&serde_json::from_str(step).ok()?);
+    }
+
+    let (tool, args) = step.split_once(" with ")?;
+    let tool = tool.trim();
+    if tool.is_empty() || tool.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((tool.to_string(), serde_json::from_str(args.trim()).ok()?))
+}
+
+fn tool_call_object(json: &Value) -> Option<(String, Value)> {
+    let tool = json.get("tool")?.as_str()?.to_string();
+    let args = json.get("args").cloned().unwrap_or_else(|| json!({}));
+    Some((tool, args))
+}
@@ -1,8 +1,9 @@
 use axum::{
-    extract::State,
-    response::{Html, IntoResponse},
-    routing::{get, post, Router},
-    http::StatusCode,
+    extract::{Path, Query, Request, State},
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Response},
+    routing::{delete, get, post, Router},
+    http::{header::{AUTHORIZATION, CONTENT_TYPE}, StatusCode},
     Json,
     extract::ws::{Message, WebSocket, WebSocketUpgrade},
 };
@@ -11,11 +12,11 @@ use crate::{
     conversation_state::ConversationState,
     MCPHost,
     conversation_service::{self, parse_tool_call},
+    session_manager::{Session, SessionManager},
 };
 use serde_json::json;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::Mutex;
 use serde_json::Value;
 use uuid::Uuid;
 use anyhow::Result;
@@ -30,14 +31,14 @@ use shared_protocol_objects::Role;
 // ---------------------------------------------------------------------------
 #[derive(Clone)]
 pub struct WebAppState {
-    pub sessions: Arc<Mutex<HashMap<Uuid, ConversationState>>>,
+    pub sessions: SessionManager,
     pub host: Arc<MCPHost>,
 }
 
 impl WebAppState {
     pub fn new(host: Arc<MCPHost>) -> Self {
         WebAppState {
-            sessions: Arc::new(Mutex::new(HashMap::new())),
+            sessions: SessionManager::new(),
             host,
         }
     }
@@ -49,18 +50,266 @@ struct WsRequest {
     user_input: String,
 }
 
+/// `?session_id=` on the chat endpoints
+#[derive(Debug, serde::Deserialize)]
+pub struct SessionQuery {
+    session_id: Option<Uuid>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct NewSessionRequest {
+    server_name: String,
+}
+
+/// The key clients must present, from `MCP_WEB_API_KEY`. Without it a random key is
+/// generated and written to `~/.mcp/web_api_key`, readable only by the current user, and
+/// the log says where to find it.
+pub fn api_key_from_env() -> String {
+    match std::env::var("MCP_WEB_API_KEY") {
+        Ok(key) if !key.is_empty() => key,
+        _ => {
+            let key = Uuid::new_v4().simple().to_string();
+            match write_generated_key(&key) {
+                Ok(path) => log::warn!(
+                    "MCP_WEB_API_KEY is not set; generated an API key for this run in {}",
+                    path.display()
+                ),
+                Err(e) => log::warn!(
+                    "MCP_WEB_API_KEY is not set and the generated API key could not be saved ({}); key for this run: {}",
+                    e, key
+                ),
+            }
+            key
+        }
+    }
+}
+
+fn write_generated_key(key: &str) -> anyhow::Result<std::path::PathBuf> {
+    use std::io::Write;
+
+    let home = std::env::var_os("HOME").ok_or_else(|| anyhow::anyhow!("HOME is not set"))?;
+    let dir = std::path::Path::new(&home).join(".mcp");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("web_api_key");
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // An existing file keeps its mode when opened, so tighten it first
+        if path.exists() {
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    let mut file = options.open(&path)?;
+    writeln!(file, "{}", key)?;
+    Ok(path)
+}
+
+/// The key a request carries: `Authorization: Bearer <key>`, `X-Api-Key: <key>`, or an
+/// `api_key` query parameter for browsers, which can't set headers on a WebSocket
+fn provided_api_key(request: &Request) -> Option<String> {
+    let headers = request.headers();
+    let from_header = headers.get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| headers.get("x-api-key").and_then(|v| v.to_str().ok()));
+    if let Some(key) = from_header {
+        return Some(key.trim().to_string());
+    }
+
+    Query::<HashMap<String, String>>::try_from_uri(request.uri())
+        .ok()
+        .and_then(|Query(mut params)| params.remove("api_key"))
+}
+
+/// Compare without stopping at the first differing byte, so timing doesn't leak the key
+fn keys_match(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided.bytes().zip(expected.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+async fn require_api_key(State(api_key): State<Arc<String>>, request: Request, next: Next) -> Response {
+    match provided_api_key(&request) {
+        Some(key) if keys_match(&key, &api_key) => next.run(request).await,
+        _ => {
+            log::warn!("Rejected unauthenticated request to {}", request.uri().path());
+            (StatusCode::UNAUTHORIZED, "Missing or invalid API key").into_response()
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // CHANGED: Create router without SSE-based endpoints
 // ---------------------------------------------------------------------------
-pub fn create_router(app_state: WebAppState) -> Router {
+pub fn create_router(app_state: WebAppState, api_key: String) -> Router {
     // Removed any .route("/ask") or SSE routes
     Router::new()
         .route("/", get(root))
         .route("/ws", get(ws_handler))
+        .route("/ws/chat/:server_name", get(chat_ws_handler))
+        .route("/session/new", post(new_session))
+        .route("/session/:id", delete(delete_session))
+        .route("/sessions", get(list_sessions))
+        .route("/tools/:server_name", get(tool_explorer))
+        .route("/call/:server_name/:tool_name", post(call_tool))
         .route("/frontend-log", post(receive_frontend_log))
+        .layer(middleware::from_fn_with_state(Arc::new(api_key), require_api_key))
         .with_state(app_state)
 }
 
+/// Start a conversation with the server named in the body and return its session ID
+async fn new_session(
+    State(app_state): State<WebAppState>,
+    Json(request): Json<NewSessionRequest>,
+) -> impl IntoResponse {
+    match app_state.sessions.create(&app_state.host, &request.server_name).await {
+        Ok(session_id) => (StatusCode::OK, Json(json!({ "session_id": session_id }))),
+        Err(e) => {
+            log::error!("Failed to create a session for {}: {}", request.server_name, e);
+            (StatusCode::BAD_GATEWAY, Json(json!({ "error": e.to_string() })))
+        }
+    }
+}
+
+async fn delete_session(State(app_state): State<WebAppState>, Path(session_id): Path<Uuid>) -> StatusCode {
+    if app_state.sessions.remove(&session_id).await {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+async fn list_sessions(State(app_state): State<WebAppState>) -> impl IntoResponse {
+    Json(app_state.sessions.list().await)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Arguments to prefill a tool's form with: its required properties with empty values
+fn argument_skeleton(schema: &Value) -> Value {
+    let mut skeleton = serde_json::Map::new();
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let required = schema.get("required").and_then(Value::as_array);
+    if let (Some(properties), Some(required)) = (properties, required) {
+        for name in required.iter().filter_map(Value::as_str) {
+            let empty = match properties.get(name).and_then(|p| p.get("type")).and_then(Value::as_str) {
+                Some("integer") | Some("number") => json!(0),
+                Some("boolean") => json!(false),
+                Some("array") => json!([]),
+                Some("object") => json!({}),
+                _ => json!(""),
+            };
+            skeleton.insert(name.to_string(), empty);
+        }
+    }
+    Value::Object(skeleton)
+}
+
+/// A page listing the server's tools with their schemas and a form to call each one
+async fn tool_explorer(
+    State(app_state): State<WebAppState>,
+    Path(server_name): Path<String>,
+) -> Response {
+    let tools = match app_state.host.list_server_tools(&server_name).await {
+        Ok(tools) => tools,
+        Err(e) => {
+            let page = format!("<p>Cannot list tools of '{}': {}</p>", escape_html(&server_name), escape_html(&e.to_string()));
+            return (StatusCode::BAD_GATEWAY, Html(page)).into_response();
+        }
+    };
+
+    let mut sections = String::new();
+    for tool in &tools {
+        let pretty = |v: &Value| escape_html(&serde_json::to_string_pretty(v).unwrap_or_default());
+        let output_schema = tool.output_schema.as_ref()
+            .map(|schema| format!("<h4>Result schema</h4><pre>{}</pre>", pretty(schema)))
+            .unwrap_or_default();
+        sections.push_str(&format!(
+            r#"<article>
+  <h3>{name}</h3>
+  <p style="white-space: pre-line">{description}</p>
+  <h4>Input schema</h4>
+  <pre>{input_schema}</pre>
+  {output_schema}
+  <form data-tool="{name}">
+    <textarea name="args" rows="6">{skeleton}</textarea>
+    <button type="submit">Call {name}</button>
+  </form>
+  <pre class="result"></pre>
+</article>
+"#,
+            name = escape_html(&tool.name),
+            description = escape_html(tool.description.as_deref().unwrap_or("")),
+            input_schema = pretty(&tool.input_schema),
+            output_schema = output_schema,
+            skeleton = pretty(&argument_skeleton(&tool.input_schema)),
+        ));
+    }
+
+    let page = format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset="UTF-8">
+  <title>Tools of {server}</title>
+  <link rel="stylesheet" href="https://cdnjs.cloudflare.com/ajax/libs/picocss/2.0.6/pico.classless.min.css">
+</head>
+<body>
+  <main class="container">
+    <h1>Tools of {server}</h1>
+    {sections}
+  </main>
+  <script>
+    const apiKey = new URLSearchParams(location.search).get('api_key');
+    for (const form of document.querySelectorAll('form[data-tool]')) {{
+      form.addEventListener('submit', async (e) => {{
+        e.preventDefault();
+        const result = form.nextElementSibling;
+        result.textContent = 'Calling...';
+        const headers = {{ 'Content-Type': 'application/json' }};
+        if (apiKey) headers['X-Api-Key'] = apiKey;
+        const resp = await fetch(`/call/${{encodeURIComponent({server_js})}}/${{encodeURIComponent(form.dataset.tool)}}`, {{
+          method: 'POST',
+          headers,
+          body: form.elements.args.value,
+        }});
+        result.textContent = `${{resp.status}}\n${{await resp.text()}}`;
+      }});
+    }}
+  </script>
+</body>
+</html>"#,
+        server = escape_html(&server_name),
+        sections = sections,
+        // A JS string literal; `<` is escaped so the name can't close the script element
+        server_js = json!(server_name).to_string().replace('<', "\\u003c"),
+    );
+    Html(page).into_response()
+}
+
+/// Call a tool with the JSON body as its arguments and return the tool's text output
+async fn call_tool(
+    State(app_state): State<WebAppState>,
+    Path((server_name, tool_name)): Path<(String, String)>,
+    Json(args): Json<Value>,
+) -> impl IntoResponse {
+    match app_state.host.call_tool(&server_name, &tool_name, args).await {
+        Ok(output) => (StatusCode::OK, [(CONTENT_TYPE, "text/plain")], output),
+        Err(e) => {
+            log::error!("Calling {} on {} failed: {}", tool_name, server_name, e);
+            (StatusCode::BAD_GATEWAY, [(CONTENT_TYPE, "text/plain")], e.to_string())
+        }
+    }
+}
+
 async fn receive_frontend_log(Json(payload): Json<Value>) -> impl IntoResponse {
     if let Some(level) = payload.get("level").and_then(|v| v.as_str()) {
         if let Some(msg) = payload.get("message").and_then(|v| v.as_str()) {
@@ -126,8 +375,10 @@ pub async fn root() -> impl IntoResponse {
   <script>
     console.log('[INFO] Starting WebSocket demo');
 
-    // Connect to WebSocket
-    const ws = new WebSocket(`ws://${location.host}/ws`);
+    // Connect to WebSocket, passing on the API key this page was opened with
+    const apiKey = new URLSearchParams(location.search).get('api_key');
+    const keyQuery = apiKey ? `?api_key=${encodeURIComponent(apiKey)}` : '';
+    const ws = new WebSocket(`ws://${location.host}/ws${keyQuery}`);
 
     // Logging
     ws.onopen = () => {
@@ -278,16 +529,17 @@ pub async fn root() -> impl IntoResponse {
 // ---------------------------------------------------------------------------
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
+    Query(query): Query<SessionQuery>,
     State(app_state): State<WebAppState>,
 ) -> impl IntoResponse {
-    ws.on_upgrade(|socket| async move {
-        if let Err(e) = handle_ws(socket, app_state).await {
+    ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_ws(socket, app_state, query.session_id).await {
             log::error!("[ws_handler] WebSocket error: {:?}", e);
         }
     })
 }
 
-async fn handle_ws(mut socket: WebSocket, app_state: WebAppState) -> Result<()> {
+async fn handle_ws(mut socket: WebSocket, app_state: WebAppState, query_session: Option<Uuid>) -> Result<()> {
     log::info!("[WS] New WebSocket connection");
 
     let mut accumulated_message = String::new();
@@ -306,22 +558,21 @@ async fn handle_ws(mut socket: WebSocket, app_state: WebAppState) -> Result<()>
                 }
             };
 
-            let session_id = resolve_session_id(parsed.session_id, &app_state).await;
+            // The query parameter wins over the ID in the message
+            let session_id = match query_session {
+                Some(id) => id,
+                None => resolve_session_id(parsed.session_id, &app_state).await,
+            };
             let user_input = parsed.user_input.trim().to_string();
 
             // Possibly init conversation
-            init_convo_if_needed(&app_state, &session_id).await;
+            let session = session_or_init(&app_state, &session_id).await;
 
             // Record user message
-            {
-                let mut sessions = app_state.sessions.lock().await;
-                if let Some(convo) = sessions.get_mut(&session_id) {
-                    convo.add_user_message(&user_input);
-                }
-            }
+            session.conversation.lock().await.add_user_message(&user_input);
 
             // Try to get a streaming response from the AI
-            let client = match app_state.host.ai_client.as_ref() {
+            let client = match app_state.host.ai_client() {
                 Some(c) => c,
                 None => {
                     let err = "No AI client configured";
@@ -332,11 +583,14 @@ async fn handle_ws(mut socket: WebSocket, app_state: WebAppState) -> Result<()>
                 }
             };
 
-            let stream_result = {
-                let sessions = app_state.sessions.lock().await;
-                let convo = sessions.get(&session_id).ok_or_else(|| {
-                    anyhow::anyhow!("Conversation state not found")
-                })?;
+            let (stream_result, sent) = {
+                let convo = session.conversation.lock().await;
+                if convo.over_budget() {
+                    socket.send(Message::Text(
+                        serde_json::json!({ "type": "error", "data": over_budget_message(&convo) }).to_string()
+                    )).await?;
+                    continue;
+                }
 
                 let mut builder = client.raw_builder().streaming(true);
                 for m in &convo.messages {
@@ -346,7 +600,7 @@ async fn handle_ws(mut socket: WebSocket, app_state: WebAppState) -> Result<()>
                         Role::Assistant => builder = builder.assistant(m.content.clone()),
                     }
                 }
-                builder.execute_streaming().await
+                (builder.execute_streaming().await, sent_messages(&convo))
             };
 
             match stream_result {
@@ -373,11 +627,13 @@ async fn handle_ws(mut socket: WebSocket, app_state: WebAppState) -> Result<()>
                                         session_id,
                                         accumulated_message
                                     );
+                                    session.conversation.lock().await
+                                        .record_ai_call(&client.model_name(), &sent, &accumulated_message);
 
                                     // Pass the complete message to `do_multi_tool_loop`
                                     if let Err(e) = do_multi_tool_loop(
                                         &app_state,
-                                        session_id,
+                                        &session,
                                         &mut accumulated_message,
                                         &mut socket
                                     ).await
@@ -423,25 +679,204 @@ async fn handle_ws(mut socket: WebSocket, app_state: WebAppState) -> Result<()>
     Ok(())
 }
 
-/// Initialize conversation state if needed
-async fn init_convo_if_needed(app_state: &WebAppState, session_id: &Uuid) {
-    let mut sessions = app_state.sessions.lock().await;
-    if sessions.contains_key(session_id) {
-        return;
+/// Messages a client sends on `/ws/chat/:server_name`
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ChatClientMessage {
+    UserMessage { content: String },
+}
+
+/// Events pushed to the client on `/ws/chat/:server_name` as the conversation progresses
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ChatEvent {
+    Session { session_id: Uuid },
+    Token { content: String },
+    ToolCall { name: String, args: Value },
+    ToolResult { name: String, output: String },
+    Error { message: String },
+    Done,
+}
+
+/// How many tool calls the model may chain in answer to one user message
+const MAX_TOOL_ROUNDS: usize = 5;
+
+/// The conversation as it is sent to the model, for `record_ai_call`
+fn sent_messages(convo: &ConversationState) -> Vec<(Role, String)> {
+    convo.messages.iter().map(|m| (m.role.clone(), m.content.clone())).collect()
+}
+
+/// Why no more requests go to the model. The CLI asks whether to go on; web clients get
+/// this as an error and can start a new session.
+fn over_budget_message(convo: &ConversationState) -> String {
+    format!(
+        "Cost budget reached: this conversation has cost about ${:.4}, over the budget of ${:.4}",
+        convo.total_cost_usd,
+        convo.max_cost_usd.unwrap_or_default()
+    )
+}
+
+async fn send_event(socket: &mut WebSocket, event: &ChatEvent) -> Result<()> {
+    socket.send(Message::Text(serde_json::to_string(event)?)).await?;
+    Ok(())
+}
+
+/// Chat with the tools of `server_name`, streaming tokens, tool calls and tool results.
+/// Continues the session given as `?session_id=`, or starts a new one and reports its ID
+/// in a `session` event.
+pub async fn chat_ws_handler(
+    ws: WebSocketUpgrade,
+    Path(server_name): Path<String>,
+    Query(query): Query<SessionQuery>,
+    State(app_state): State<WebAppState>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_chat_ws(socket, app_state, server_name, query.session_id).await {
+            log::error!("[chat_ws_handler] WebSocket error: {:?}", e);
+        }
+    })
+}
+
+async fn handle_chat_ws(
+    mut socket: WebSocket,
+    app_state: WebAppState,
+    server_name: String,
+    session_id: Option<Uuid>,
+) -> Result<()> {
+    log::info!("[WS] New chat connection for server {}", server_name);
+
+    let session = match session_id {
+        Some(session_id) => match app_state.sessions.get(&session_id).await {
+            Some(session) if session.server_name == server_name => session,
+            Some(session) => {
+                let message = format!("Session {} belongs to server '{}'", session_id, session.server_name);
+                send_event(&mut socket, &ChatEvent::Error { message }).await?;
+                return Ok(());
+            }
+            None => {
+                let message = format!("Session not found: {}", session_id);
+                send_event(&mut socket, &ChatEvent::Error { message }).await?;
+                return Ok(());
+            }
+        },
+        None => match app_state.sessions.create(&app_state.host, &server_name).await {
+            Ok(session_id) => {
+                send_event(&mut socket, &ChatEvent::Session { session_id }).await?;
+                // Deleted again before we got here
+                let Some(session) = app_state.sessions.get(&session_id).await else { return Ok(()) };
+                session
+            }
+            Err(e) => {
+                let message = format!("Cannot chat with server '{}': {}", server_name, e);
+                send_event(&mut socket, &ChatEvent::Error { message }).await?;
+                return Ok(());
+            }
+        },
+    };
+
+    while let Some(Ok(msg)) = socket.recv().await {
+        let Message::Text(text) = msg else { continue };
+        let content = match serde_json::from_str::<ChatClientMessage>(&text) {
+            Ok(ChatClientMessage::UserMessage { content }) => content,
+            Err(e) => {
+                send_event(&mut socket, &ChatEvent::Error { message: format!("Invalid message: {}", e) }).await?;
+                continue;
+            }
+        };
+
+        // Held for the whole turn so two connections to one session take turns
+        let mut convo = session.conversation.lock().await;
+        convo.add_user_message(content.trim());
+        let turn = run_chat_turn(&app_state.host, &server_name, &mut convo, &mut socket).await;
+        drop(convo);
+        if let Err(e) = turn {
+            log::error!("[WS] Chat turn failed: {}", e);
+            send_event(&mut socket, &ChatEvent::Error { message: e.to_string() }).await?;
+        }
+        send_event(&mut socket, &ChatEvent::Done).await?;
+    }
+
+    log::info!("[WS] Chat connection for server {} closed", server_name);
+    Ok(())
+}
+
+/// Answer the last user message: stream the model's reply and run the tool calls it makes,
+/// asking the model again after each one
+async fn run_chat_turn(
+    host: &MCPHost,
+    server_name: &str,
+    convo: &mut ConversationState,
+    socket: &mut WebSocket,
+) -> Result<()> {
+    let client = host.ai_client()
+        .ok_or_else(|| anyhow::anyhow!("No AI client configured"))?;
+    let tool_names: Vec<String> = convo.tools.iter().map(|t| t.name.clone()).collect();
+
+    if convo.needs_compression() {
+        if let Err(e) = convo.summarize_and_compress(client.as_ref()).await {
+            log::warn!("Failed to summarize the conversation, older messages will be trimmed instead: {}", e);
+        }
     }
-    drop(sessions);
 
-    match app_state.host.enter_chat_mode("api").await {
-        Ok(new_state) => {
-            let mut sessions = app_state.sessions.lock().await;
-            sessions.insert(*session_id, new_state);
+    for _ in 0..MAX_TOOL_ROUNDS {
+        if convo.over_budget() {
+            return Err(anyhow::anyhow!(over_budget_message(convo)));
+        }
+        let messages = sent_messages(convo);
+        let mut chunks = client.stream(messages.clone()).await?;
+        let mut answer = String::new();
+        while let Some(chunk) = chunks.next().await {
+            let chunk = chunk?;
+            answer.push_str(&chunk);
+            send_event(socket, &ChatEvent::Token { content: chunk }).await?;
         }
+        convo.record_ai_call(&client.model_name(), &messages, &answer);
+        convo.add_model_message(&answer, client.model_name());
+
+        let (name, args) = match parse_tool_call(&answer, &tool_names) {
+            conversation_service::ToolCallResult::Success(name, args) => (name, args),
+            conversation_service::ToolCallResult::NearMiss(feedback) => {
+                convo.add_assistant_message(&feedback.join("\n"));
+                return Ok(());
+            }
+            conversation_service::ToolCallResult::NoMatch => return Ok(()),
+        };
+
+        send_event(socket, &ChatEvent::ToolCall { name: name.clone(), args: args.clone() }).await?;
+        let output = match host.call_tool(server_name, &name, args).await {
+            Ok(output) => {
+                convo.add_assistant_message(&format!("Tool '{}' returned: {}", name, output.trim()));
+                output
+            }
+            Err(e) => {
+                let output = format!("Tool '{}' failed: {}", name, e);
+                convo.add_assistant_message(&output);
+                output
+            }
+        };
+        send_event(socket, &ChatEvent::ToolResult { name, output }).await?;
+    }
+
+    log::warn!("[WS] Stopped after {} tool calls in one turn", MAX_TOOL_ROUNDS);
+    Ok(())
+}
+
+/// The session with `session_id`, starting a conversation with the `api` server if needed
+async fn session_or_init(app_state: &WebAppState, session_id: &Uuid) -> Session {
+    if let Some(session) = app_state.sessions.get(session_id).await {
+        return session;
+    }
+
+    let new_state = match app_state.host.enter_chat_mode("api").await {
+        Ok(new_state) => new_state,
         Err(e) => {
             log::warn!("Error calling enter_chat_mode: {}", e);
-            let mut sessions = app_state.sessions.lock().await;
-            sessions.insert(*session_id, ConversationState::new("Welcome!".to_string(), vec![]));
+            ConversationState::new("Welcome!".to_string(), vec![])
         }
-    }
+    };
+    app_state.sessions.insert(*session_id, "api", new_state).await;
+    app_state.sessions.get(session_id).await
+        .expect("session was just inserted")
 }
 
 async fn resolve_session_id(
@@ -462,11 +897,11 @@ async fn run_single_stream_pass(
     app_state: &WebAppState,
     session_id: Uuid,
 ) -> Result<String> {
-    let sessions = app_state.sessions.lock().await;
-    let convo = sessions.get(&session_id)
+    let session = app_state.sessions.get(&session_id).await
         .ok_or_else(|| anyhow::anyhow!("Session not found"))?;
+    let convo = session.conversation.lock().await;
 
-    let client = app_state.host.ai_client.as_ref()
+    let client = app_state.host.ai_client()
         .ok_or_else(|| anyhow::anyhow!("No AI client configured"))?;
 
     let mut builder = client.raw_builder();
@@ -483,17 +918,15 @@ async fn run_single_stream_pass(
 
 async fn do_multi_tool_loop(
     app_state: &WebAppState,
-    session_id: Uuid,
+    session: &Session,
     partial_response: &mut String,
     socket: &mut WebSocket,
 ) -> Result<()> {
     // Acquire the conversation state
-    let mut sessions = app_state.sessions.lock().await;
-    let convo = sessions.get_mut(&session_id)
-        .ok_or_else(|| anyhow::anyhow!("Session not found"))?;
+    let mut convo = session.conversation.lock().await;
 
     // Access the AI client
-    let client = match app_state.host.ai_client.as_ref() {
+    let client = match app_state.host.ai_client() {
         Some(c) => c,
         None => {
             let err = "No AI client configured";
@@ -508,7 +941,7 @@ async fn do_multi_tool_loop(
     };
 
     // Insert the newly received partial_response from the AI as an assistant message
-    convo.add_assistant_message(partial_response);
+    convo.add_model_message(partial_response, client.model_name());
 
     // Now we do a simple loop: parse for tool calls, handle them, and re-ask the model if needed.
     let mut iteration_count = 0;
@@ -530,7 +963,7 @@ async fn do_multi_tool_loop(
                 
 
                 // 2) Actually call the tool
-                match app_state.host.call_tool("api", &tool_name, args).await {
+                match app_state.host.call_tool(&session.server_name, &tool_name, args).await {
                     Ok(tool_output) => {
                         
                         let end_msg = serde_json::json!({
@@ -554,6 +987,12 @@ async fn do_multi_tool_loop(
                 }
 
                 // 3) Now re-run the model with the updated conversation
+                if convo.over_budget() {
+                    let err_msg = serde_json::json!({ "type": "error", "data": over_budget_message(&convo) });
+                    let _ = socket.send(Message::Text(err_msg.to_string())).await;
+                    break;
+                }
+                let sent = sent_messages(&convo);
                 let new_ai_answer = {
                     let mut builder = client.raw_builder();
                     for msg in &convo.messages {
@@ -579,9 +1018,11 @@ async fn do_multi_tool_loop(
                     }
                 };
 
+                convo.record_ai_call(&client.model_name(), &sent, &new_ai_answer);
+
                 // Update partial_response with the new model content
                 *partial_response = new_ai_answer.clone();
-                convo.add_assistant_message(&new_ai_answer);
+                convo.add_model_message(&new_ai_answer, client.model_name());
 
             }
             crate::conversation_service::ToolCallResult::NearMiss(fb) => {
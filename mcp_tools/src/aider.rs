@@ -174,6 +174,7 @@ pub fn aider_tool_info() -> ToolInfo {
             "required": ["directory", "message"],
             "additionalProperties": false
         }),
+        output_schema: None,
     }
 }
 
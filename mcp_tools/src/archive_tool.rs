@@ -0,0 +1,389 @@
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Timelike};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use shared_protocol_objects::{CallToolParams, JsonRpcErrorCode, ToolInfo};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use xz2::read::XzDecoder;
+use xz2::write::XzEncoder;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::filesystem_tool::{allowed_dirs_from_env, resolve_allowed_path};
+use crate::tool_trait::{
+    standard_error_response, standard_success_response, standard_tool_result, ExecuteFuture, Tool,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ArchiveAction {
+    Create,
+    List,
+    Extract,
+    Add,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArchiveParams {
+    action: ArchiveAction,
+    archive_path: String,
+    #[serde(default)]
+    paths: Vec<String>,
+    #[serde(default)]
+    destination: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ArchiveFormat {
+    Zip,
+    TarGz,
+    TarXz,
+}
+
+impl ArchiveFormat {
+    fn from_path(path: &str) -> Result<Self> {
+        let lower = path.to_lowercase();
+        if lower.ends_with(".zip") {
+            Ok(Self::Zip)
+        } else if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+            Ok(Self::TarGz)
+        } else if lower.ends_with(".tar.xz") || lower.ends_with(".txz") {
+            Ok(Self::TarXz)
+        } else {
+            Err(anyhow!("Unsupported archive format for '{}'; use .zip, .tar.gz or .tar.xz", path))
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ArchiveEntry {
+    name: String,
+    size: u64,
+    is_dir: bool,
+    modified: Option<String>,
+}
+
+pub fn archive_tool_info() -> ToolInfo {
+    ToolInfo {
+        name: "archive".to_string(),
+        description: Some(
+            "Create, inspect and extract zip and tar archives. The format is picked from the
+            extension of 'archive_path': .zip, .tar.gz (.tgz) or .tar.xz (.txz).
+
+            Actions:
+            - create: pack the files and directories in 'paths' into a new archive
+            - list: entries of the archive with their size and modification date
+            - extract: unpack the archive into the 'destination' directory
+            - add: add the files in 'paths' to an existing archive
+
+            Entries are stored under the file or directory name of each path. All paths must be
+            inside the allowed directories of the filesystem tool.".to_string()
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["create", "list", "extract", "add"],
+                    "description": "The operation to perform."
+                },
+                "archive_path": {
+                    "type": "string",
+                    "description": "The archive to create, read or modify."
+                },
+                "paths": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Files or directories to pack (for 'create' and 'add')."
+                },
+                "destination": {
+                    "type": "string",
+                    "description": "Directory to extract into (for 'extract')."
+                }
+            },
+            "required": ["action", "archive_path"],
+            "additionalProperties": false
+        }),
+        output_schema: None,
+    }
+}
+
+/// Name an added path gets inside the archive
+fn entry_name(path: &Path) -> Result<String> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| anyhow!("Cannot archive '{}'", path.display()))
+}
+
+/// Modification time of `source` in the zip format, which has no time zone and starts at 1980
+fn zip_modified_time(source: &Path) -> Option<zip::DateTime> {
+    let modified: chrono::DateTime<chrono::Local> = fs::metadata(source).ok()?.modified().ok()?.into();
+    zip::DateTime::from_date_and_time(
+        u16::try_from(modified.year()).ok()?,
+        modified.month() as u8,
+        modified.day() as u8,
+        modified.hour() as u8,
+        modified.minute() as u8,
+        modified.second() as u8,
+    ).ok()
+}
+
+fn add_to_zip<W: Write + Seek>(writer: &mut ZipWriter<W>, source: &Path, name: &str) -> Result<()> {
+    let mut options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    if let Some(modified) = zip_modified_time(source) {
+        options = options.last_modified_time(modified);
+    }
+    if source.is_dir() {
+        writer.add_directory(name, options)?;
+        let mut children: Vec<_> = fs::read_dir(source)?.collect::<io::Result<_>>()?;
+        children.sort_by_key(|entry| entry.file_name());
+        for child in children {
+            let child_name = format!("{}/{}", name, child.file_name().to_string_lossy());
+            add_to_zip(writer, &child.path(), &child_name)?;
+        }
+    } else {
+        writer.start_file(name, options)?;
+        io::copy(&mut File::open(source)?, writer)?;
+    }
+    Ok(())
+}
+
+fn add_to_tar<W: Write>(builder: &mut tar::Builder<W>, source: &Path) -> Result<()> {
+    let name = entry_name(source)?;
+    if source.is_dir() {
+        builder.append_dir_all(&name, source)?;
+    } else {
+        builder.append_path_with_name(source, &name)?;
+    }
+    Ok(())
+}
+
+/// Write a compressed tar to `output`, with `fill` adding the entries
+fn write_tar(
+    format: ArchiveFormat,
+    output: &Path,
+    fill: impl FnOnce(&mut tar::Builder<&mut dyn Write>) -> Result<()>,
+) -> Result<()> {
+    let file = File::create(output)?;
+    match format {
+        ArchiveFormat::TarGz => {
+            let mut encoder = GzEncoder::new(file, Compression::default());
+            let mut builder = tar::Builder::new(&mut encoder as &mut dyn Write);
+            fill(&mut builder)?;
+            builder.into_inner()?;
+            encoder.finish()?;
+        }
+        ArchiveFormat::TarXz => {
+            let mut encoder = XzEncoder::new(file, 6);
+            let mut builder = tar::Builder::new(&mut encoder as &mut dyn Write);
+            fill(&mut builder)?;
+            builder.into_inner()?;
+            encoder.finish()?;
+        }
+        ArchiveFormat::Zip => unreachable!("zip archives are not tars"),
+    }
+    Ok(())
+}
+
+fn read_tar(format: ArchiveFormat, path: &Path) -> Result<tar::Archive<Box<dyn Read>>> {
+    let file = File::open(path).map_err(|e| anyhow!("Failed to open '{}': {}", path.display(), e))?;
+    let reader: Box<dyn Read> = match format {
+        ArchiveFormat::TarGz => Box::new(GzDecoder::new(file)),
+        ArchiveFormat::TarXz => Box::new(XzDecoder::new(file)),
+        ArchiveFormat::Zip => unreachable!("zip archives are not tars"),
+    };
+    Ok(tar::Archive::new(reader))
+}
+
+fn open_zip(path: &Path) -> Result<ZipArchive<File>> {
+    let file = File::open(path).map_err(|e| anyhow!("Failed to open '{}': {}", path.display(), e))?;
+    Ok(ZipArchive::new(file)?)
+}
+
+fn create(format: ArchiveFormat, archive_path: &Path, paths: &[PathBuf]) -> Result<()> {
+    match format {
+        ArchiveFormat::Zip => {
+            let mut writer = ZipWriter::new(File::create(archive_path)?);
+            for path in paths {
+                add_to_zip(&mut writer, path, &entry_name(path)?)?;
+            }
+            writer.finish()?;
+            Ok(())
+        }
+        _ => write_tar(format, archive_path, |builder| {
+            paths.iter().try_for_each(|path| add_to_tar(builder, path))
+        }),
+    }
+}
+
+fn list(format: ArchiveFormat, archive_path: &Path) -> Result<Vec<ArchiveEntry>> {
+    let mut entries = Vec::new();
+    match format {
+        ArchiveFormat::Zip => {
+            let mut archive = open_zip(archive_path)?;
+            for i in 0..archive.len() {
+                let file = archive.by_index(i)?;
+                entries.push(ArchiveEntry {
+                    name: file.name().to_string(),
+                    size: file.size(),
+                    is_dir: file.is_dir(),
+                    modified: file.last_modified().map(|time| time.to_string()),
+                });
+            }
+        }
+        _ => {
+            let mut archive = read_tar(format, archive_path)?;
+            for entry in archive.entries()? {
+                let entry = entry?;
+                let header = entry.header();
+                entries.push(ArchiveEntry {
+                    name: entry.path()?.to_string_lossy().into_owned(),
+                    size: header.size()?,
+                    is_dir: header.entry_type().is_dir(),
+                    modified: header.mtime().ok()
+                        .and_then(|secs| chrono::DateTime::from_timestamp(secs as i64, 0))
+                        .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string()),
+                });
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Both `zip` and `tar` refuse entries that would land outside `destination`
+fn extract(format: ArchiveFormat, archive_path: &Path, destination: &Path) -> Result<()> {
+    fs::create_dir_all(destination)?;
+    match format {
+        ArchiveFormat::Zip => open_zip(archive_path)?.extract(destination)?,
+        _ => read_tar(format, archive_path)?.unpack(destination)?,
+    }
+    Ok(())
+}
+
+/// Compressed tars can't be appended to in place, so they are rewritten with the new files
+fn add(format: ArchiveFormat, archive_path: &Path, paths: &[PathBuf]) -> Result<()> {
+    match format {
+        ArchiveFormat::Zip => {
+            let file = OpenOptions::new().read(true).write(true).open(archive_path)
+                .map_err(|e| anyhow!("Failed to open '{}': {}", archive_path.display(), e))?;
+            let mut writer = ZipWriter::new_append(file)?;
+            for path in paths {
+                add_to_zip(&mut writer, path, &entry_name(path)?)?;
+            }
+            writer.finish()?;
+            Ok(())
+        }
+        _ => {
+            let mut existing = read_tar(format, archive_path)?;
+            let mut tmp_path = archive_path.as_os_str().to_owned();
+            tmp_path.push(".tmp");
+            let tmp_path = PathBuf::from(tmp_path);
+            write_tar(format, &tmp_path, |builder| {
+                for entry in existing.entries()? {
+                    let mut entry = entry?;
+                    let mut header = entry.header().clone();
+                    let path = entry.path()?.into_owned();
+                    builder.append_data(&mut header, path, &mut entry)?;
+                }
+                paths.iter().try_for_each(|path| add_to_tar(builder, path))
+            })
+            .inspect_err(|_| {
+                let _ = fs::remove_file(&tmp_path);
+            })?;
+            fs::rename(&tmp_path, archive_path)?;
+            Ok(())
+        }
+    }
+}
+
+/// The archive, the packed paths and the destination must all lie inside `allowed_dirs`
+fn run(allowed_dirs: &[PathBuf], params: ArchiveParams) -> Result<Value> {
+    let format = ArchiveFormat::from_path(&params.archive_path)?;
+    let archive_path = resolve_allowed_path(allowed_dirs, &params.archive_path)?;
+    let paths = || {
+        if params.paths.is_empty() {
+            return Err(anyhow!("Missing 'paths' parameter"));
+        }
+        params.paths.iter()
+            .map(|path| resolve_allowed_path(allowed_dirs, path))
+            .collect::<Result<Vec<_>>>()
+    };
+
+    match params.action {
+        ArchiveAction::Create => {
+            let paths = paths()?;
+            create(format, &archive_path, &paths)?;
+            Ok(Value::String(format!("Created {} with {} paths", archive_path.display(), paths.len())))
+        }
+        ArchiveAction::List => Ok(json!(list(format, &archive_path)?)),
+        ArchiveAction::Extract => {
+            let destination = params.destination.as_deref()
+                .ok_or_else(|| anyhow!("Missing 'destination' parameter"))?;
+            let destination = resolve_allowed_path(allowed_dirs, destination)?;
+            extract(format, &archive_path, &destination)?;
+            Ok(Value::String(format!("Extracted {} to {}", archive_path.display(), destination.display())))
+        }
+        ArchiveAction::Add => {
+            let paths = paths()?;
+            add(format, &archive_path, &paths)?;
+            Ok(Value::String(format!("Added {} paths to {}", paths.len(), archive_path.display())))
+        }
+    }
+}
+
+// Archive Tool Implementation
+#[derive(Debug)]
+pub struct ArchiveTool {
+    allowed_dirs: Vec<PathBuf>,
+}
+
+impl ArchiveTool {
+    pub fn new(allowed_dirs: Vec<PathBuf>) -> Self {
+        Self { allowed_dirs }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(allowed_dirs_from_env())
+    }
+}
+
+impl Tool for ArchiveTool {
+    fn name(&self) -> &str {
+        "archive"
+    }
+
+    fn info(&self) -> ToolInfo {
+        archive_tool_info()
+    }
+
+    fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture {
+        let allowed_dirs = self.allowed_dirs.clone();
+
+        Box::pin(async move {
+            let archive_params: ArchiveParams = match serde_json::from_value(params.arguments) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Ok(standard_error_response(
+                        id,
+                        JsonRpcErrorCode::InvalidParams,
+                        &format!("Invalid parameters: {}", e),
+                    ));
+                }
+            };
+
+            // Compression is CPU bound and the archive crates are synchronous
+            let result = tokio::task::spawn_blocking(move || run(&allowed_dirs, archive_params)).await?;
+            let tool_res = match result {
+                Ok(Value::String(text)) => standard_tool_result(text, None),
+                Ok(value) => standard_tool_result(serde_json::to_string_pretty(&value)?, None),
+                Err(e) => standard_tool_result(format!("Archive error: {}", e), Some(true)),
+            };
+            Ok(standard_success_response(id, json!(tool_res)))
+        })
+    }
+}
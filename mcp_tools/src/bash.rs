@@ -1,18 +1,24 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::process::Command;
-use serde_json::json;
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use serde_json::{json, Value};
 
-use shared_protocol_objects::ToolInfo;
+use shared_protocol_objects::{notification_response, JsonRpcResponse, ToolInfo};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BashParams {
     pub command: String,
     #[serde(default = "default_cwd")]
     pub cwd: String,
+    /// Stream stdout lines as `notifications/progress` while the command runs
+    #[serde(default)]
+    pub stream: bool,
 }
 
-fn default_cwd() -> String {
+pub(crate) fn default_cwd() -> String {
     std::env::current_dir()
         .unwrap_or_else(|_| std::path::PathBuf::from("/"))
         .to_string_lossy()
@@ -52,30 +58,24 @@ impl BashExecutor {
                 - Output is limited to stdout/stderr (no interactive prompts)
                 - Commands run in a non-interactive shell (sh)".to_string()
             ),
-            input_schema: json!({})
+            input_schema: json!({}),
+            output_schema: None,
         }
     }
 
     pub async fn execute(&self, params: BashParams) -> Result<BashResult> {
-        // Create working directory if it doesn't exist
-        let cwd = std::path::PathBuf::from(&params.cwd);
-        if !cwd.exists() {
-            std::fs::create_dir_all(&cwd)?;
-        }
+        let cwd = ensure_cwd(&params.cwd)?;
 
+        // Killing the child on drop lets a cancelled request stop the command
         let output = Command::new("sh")
             .arg("-c")
             .arg(&params.command)
             .current_dir(&cwd)
-            .output()?;
-
-        // Check if there were permission issues
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if stderr.contains("permission denied") {
-                return Err(anyhow::anyhow!("Permission denied. Try running with appropriate permissions or in a different directory."));
-            }
-        }
+            .kill_on_drop(true)
+            .output()
+            .await?;
+
+        check_permission_denied(output.status, &String::from_utf8_lossy(&output.stderr))?;
 
         Ok(BashResult {
             success: output.status.success(),
@@ -84,6 +84,83 @@ impl BashExecutor {
             stderr: String::from_utf8_lossy(&output.stderr).to_string(),
         })
     }
+
+    /// Like `execute`, but sends every stdout line as a `notifications/progress`
+    /// notification tagged with `progress_token` as soon as it is printed.
+    pub async fn execute_streaming(
+        &self,
+        params: BashParams,
+        tx_out: &mpsc::UnboundedSender<JsonRpcResponse>,
+        progress_token: Value,
+    ) -> Result<BashResult> {
+        let cwd = ensure_cwd(&params.cwd)?;
+
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&params.command)
+            .current_dir(&cwd)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let child_stdout = child.stdout.take()
+            .ok_or_else(|| anyhow::anyhow!("Failed to capture stdout"))?;
+        let mut child_stderr = child.stderr.take()
+            .ok_or_else(|| anyhow::anyhow!("Failed to capture stderr"))?;
+
+        // Drain stderr concurrently so a chatty command can't block on a full pipe
+        let stderr_task = tokio::spawn(async move {
+            let mut buf = Vec::new();
+            let _ = child_stderr.read_to_end(&mut buf).await;
+            buf
+        });
+
+        let mut stdout = String::new();
+        let mut lines = BufReader::new(child_stdout).lines();
+        let mut line_count: u32 = 0;
+        while let Some(line) = lines.next_line().await? {
+            line_count += 1;
+            let _ = tx_out.send(notification_response(
+                "notifications/progress",
+                Some(json!({
+                    "progressToken": progress_token,
+                    "progress": line_count,
+                    "message": line
+                })),
+            ));
+            stdout.push_str(&line);
+            stdout.push('\n');
+        }
+
+        let status = child.wait().await?;
+        let stderr = String::from_utf8_lossy(&stderr_task.await.unwrap_or_default()).to_string();
+        check_permission_denied(status, &stderr)?;
+
+        Ok(BashResult {
+            success: status.success(),
+            status: status.code().unwrap_or(-1),
+            stdout,
+            stderr,
+        })
+    }
+}
+
+// Create working directory if it doesn't exist
+fn ensure_cwd(cwd: &str) -> Result<std::path::PathBuf> {
+    let cwd = std::path::PathBuf::from(cwd);
+    if !cwd.exists() {
+        std::fs::create_dir_all(&cwd)?;
+    }
+    Ok(cwd)
+}
+
+// Check if there were permission issues
+fn check_permission_denied(status: std::process::ExitStatus, stderr: &str) -> Result<()> {
+    if !status.success() && stderr.contains("permission denied") {
+        return Err(anyhow::anyhow!("Permission denied. Try running with appropriate permissions or in a different directory."));
+    }
+    Ok(())
 }
 
 pub fn bash_tool_info() -> ToolInfo {
@@ -100,11 +177,16 @@ pub fn bash_tool_info() -> ToolInfo {
                 "cwd": {
                     "type": "string",
                     "description": "The working directory for the command"
+                },
+                "stream": {
+                    "type": "boolean",
+                    "description": "Stream stdout lines as progress notifications while the command runs"
                 }
             },
             "required": ["command"],
             "additionalProperties": false
         }),
+        output_schema: None,
     }
 }
 
@@ -150,6 +232,7 @@ pub fn quick_bash_tool_info() -> ToolInfo {
             "required": ["cmd"],
             "additionalProperties": false
         }),
+        output_schema: None,
     }
 }
 
@@ -160,6 +243,7 @@ pub async fn handle_quick_bash(params: QuickBashParams) -> Result<BashResult> {
     let bash_params = BashParams {
         command: params.cmd,
         cwd: default_cwd(),  // Always use the current working directory
+        stream: false,
     };
     
     // Execute the command using the existing executor
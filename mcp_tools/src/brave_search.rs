@@ -2,10 +2,13 @@ use serde::{Deserialize, Serialize};
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT};
 use anyhow::Result;
 use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use shared_protocol_objects::ToolInfo;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct SearchResponse {
     #[serde(rename = "type")]
     pub type_: String,
@@ -13,7 +16,7 @@ pub struct SearchResponse {
     pub query: Option<Query>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Search {
     #[serde(rename = "type")]
     pub type_: String,
@@ -21,7 +24,7 @@ pub struct Search {
     pub family_friendly: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct SearchResult {
     pub title: String,
     pub url: String,
@@ -35,7 +38,7 @@ pub struct SearchResult {
     pub extra_snippets: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Query {
     pub original: String,
     pub show_strict_warning: Option<bool>,
@@ -74,10 +77,19 @@ pub struct SearchParams {
     pub safesearch: Option<String>,
 }
 
+/// How long cached results are reused by clients built with `BraveSearchClient::with_cache`
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+type SearchCache = Arc<Mutex<HashMap<String, (Instant, SearchResponse)>>>;
+
+#[derive(Debug)]
 pub struct BraveSearchClient {
     client: reqwest::Client,
     api_key: String,
     base_url: String,
+    /// Responses keyed by normalized query, only set when caching is enabled
+    cache: Option<SearchCache>,
+    cache_ttl: Duration,
 }
 
 pub fn search_tool_info() -> ToolInfo {
@@ -104,6 +116,7 @@ pub fn search_tool_info() -> ToolInfo {
             - Age indicators showing content freshness
             
             The search defaults to returning 10 results but can provide up to 20 with the count parameter.
+            Results are cached for a few minutes, so repeating a query returns the same results.
             ".into()
         ),
         input_schema: json!({
@@ -125,6 +138,7 @@ pub fn search_tool_info() -> ToolInfo {
             "required": ["query"],
             "additionalProperties": false
         }),
+        output_schema: None,
     }
 }
 
@@ -137,10 +151,35 @@ impl BraveSearchClient {
             client,
             api_key,
             base_url,
+            cache: None,
+            cache_ttl: DEFAULT_CACHE_TTL,
+        }
+    }
+
+    /// Create a client that reuses responses for identical queries made within `ttl`
+    pub fn with_cache(api_key: String, ttl: Duration) -> Self {
+        Self {
+            cache: Some(Arc::new(Mutex::new(HashMap::new()))),
+            cache_ttl: ttl,
+            ..Self::new(api_key)
         }
     }
 
+    /// Queries that differ only in case or whitespace share a cache entry
+    fn cache_key(query: &str) -> String {
+        query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+    }
+
     pub async fn search(&self, query: &str) -> Result<SearchResponse> {
+        let key = Self::cache_key(query);
+        if let Some(cache) = &self.cache {
+            let mut cache = cache.lock().unwrap();
+            cache.retain(|_, (fetched, _)| fetched.elapsed() < self.cache_ttl);
+            if let Some((_, response)) = cache.get(&key) {
+                return Ok(response.clone());
+            }
+        }
+
         let params = SearchParams {
             q: query.to_string(),
             count: Some(20),  // maximum results
@@ -173,6 +212,10 @@ impl BraveSearchClient {
         let search_response = response.json::<SearchResponse>().await
             .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
 
+        if let Some(cache) = &self.cache {
+            cache.lock().unwrap().insert(key, (Instant::now(), search_response.clone()));
+        }
+
         Ok(search_response)
     }
 }
@@ -0,0 +1,263 @@
+use anyhow::{anyhow, Result};
+use grep_matcher::Matcher;
+use grep_regex::{RegexMatcher, RegexMatcherBuilder};
+use grep_searcher::{Searcher, SearcherBuilder, Sink, SinkContext, SinkContextKind, SinkMatch};
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use shared_protocol_objects::{CallToolParams, JsonRpcErrorCode, ToolInfo};
+use std::path::{Path, PathBuf};
+
+use crate::filesystem_tool::{allowed_dirs_from_env, resolve_allowed_path};
+use crate::tool_trait::{
+    standard_error_response, standard_success_response, standard_tool_result, ExecuteFuture, Tool,
+};
+
+const DEFAULT_MAX_RESULTS: usize = 100;
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_results() -> usize {
+    DEFAULT_MAX_RESULTS
+}
+
+#[derive(Debug, Deserialize)]
+struct CodeSearchParams {
+    root: String,
+    pattern: String,
+    #[serde(default)]
+    file_glob: Option<String>,
+    #[serde(default = "default_true")]
+    case_sensitive: bool,
+    #[serde(default)]
+    context_lines: usize,
+    #[serde(default = "default_max_results")]
+    max_results: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchMatch {
+    file: String,
+    line: u64,
+    /// 1-based byte offset of the first match in the line
+    column: usize,
+    content: String,
+    context_before: Vec<String>,
+    context_after: Vec<String>,
+}
+
+pub fn code_search_tool_info() -> ToolInfo {
+    ToolInfo {
+        name: "code_search".to_string(),
+        description: Some(
+            "Search source code for a regular expression and get structured results.
+            Prefer this over grep or rg through bash.
+
+            Walks 'root' recursively, skipping hidden files and anything ignored by .gitignore.
+            Each match has the file, line, column, the matching line and 'context_lines' lines
+            of context on either side. 'file_glob' (e.g. '*.rs') limits the files searched.
+
+            Stops after 'max_results' matches (default 100).".to_string()
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "root": {
+                    "type": "string",
+                    "description": "Directory (or file) to search."
+                },
+                "pattern": {
+                    "type": "string",
+                    "description": "Regular expression to search for."
+                },
+                "file_glob": {
+                    "type": "string",
+                    "description": "Only search files matching this glob."
+                },
+                "case_sensitive": {
+                    "type": "boolean",
+                    "description": "Match case exactly (default true)."
+                },
+                "context_lines": {
+                    "type": "integer",
+                    "description": "Lines of context before and after each match (default 0)."
+                },
+                "max_results": {
+                    "type": "integer",
+                    "description": "Maximum number of matches to return (default 100)."
+                }
+            },
+            "required": ["root", "pattern"],
+            "additionalProperties": false
+        }),
+        output_schema: Some(json!({
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "file": { "type": "string" },
+                    "line": { "type": "integer" },
+                    "column": { "type": "integer" },
+                    "content": { "type": "string" },
+                    "context_before": { "type": "array", "items": { "type": "string" } },
+                    "context_after": { "type": "array", "items": { "type": "string" } }
+                }
+            }
+        })),
+    }
+}
+
+/// Collects the matches of one file, attaching context lines to the match they belong to
+struct MatchSink<'a> {
+    file: String,
+    matcher: &'a RegexMatcher,
+    matches: &'a mut Vec<SearchMatch>,
+    max_results: usize,
+    pending_before: Vec<String>,
+}
+
+fn line_text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).trim_end_matches(['\r', '\n']).to_string()
+}
+
+impl Sink for MatchSink<'_> {
+    type Error = std::io::Error;
+
+    fn matched(&mut self, _searcher: &Searcher, mat: &SinkMatch<'_>) -> Result<bool, Self::Error> {
+        // Stop only when the next match arrives, so the last one still gets its trailing context
+        if self.matches.len() >= self.max_results {
+            return Ok(false);
+        }
+        let column = self.matcher.find(mat.bytes())
+            .ok()
+            .flatten()
+            .map_or(1, |m| m.start() + 1);
+        self.matches.push(SearchMatch {
+            file: self.file.clone(),
+            line: mat.line_number().unwrap_or(0),
+            column,
+            content: line_text(mat.bytes()),
+            context_before: std::mem::take(&mut self.pending_before),
+            context_after: Vec::new(),
+        });
+        Ok(true)
+    }
+
+    fn context(&mut self, _searcher: &Searcher, context: &SinkContext<'_>) -> Result<bool, Self::Error> {
+        let line = line_text(context.bytes());
+        match context.kind() {
+            SinkContextKind::Before => self.pending_before.push(line),
+            SinkContextKind::After => {
+                if let Some(last) = self.matches.last_mut() {
+                    last.context_after.push(line);
+                }
+            }
+            SinkContextKind::Other => {}
+        }
+        Ok(true)
+    }
+}
+
+fn search(root: &Path, params: &CodeSearchParams) -> Result<Vec<SearchMatch>> {
+    let matcher = RegexMatcherBuilder::new()
+        .case_insensitive(!params.case_sensitive)
+        .build(&params.pattern)
+        .map_err(|e| anyhow!("Invalid pattern: {}", e))?;
+    let mut searcher = SearcherBuilder::new()
+        .line_number(true)
+        .before_context(params.context_lines)
+        .after_context(params.context_lines)
+        .build();
+
+    let mut walker = WalkBuilder::new(root);
+    if let Some(glob) = &params.file_glob {
+        let overrides = OverrideBuilder::new(root).add(glob)?.build()?;
+        walker.overrides(overrides);
+    }
+
+    let mut matches = Vec::new();
+    for entry in walker.build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                tracing::warn!("Skipping unreadable entry: {}", e);
+                continue;
+            }
+        };
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+
+        let mut sink = MatchSink {
+            file: entry.path().display().to_string(),
+            matcher: &matcher,
+            matches: &mut matches,
+            max_results: params.max_results,
+            pending_before: Vec::new(),
+        };
+        if let Err(e) = searcher.search_path(&matcher, entry.path(), &mut sink) {
+            tracing::warn!("Failed to search {}: {}", entry.path().display(), e);
+        }
+        if matches.len() >= params.max_results {
+            break;
+        }
+    }
+    Ok(matches)
+}
+
+// Code Search Tool Implementation
+#[derive(Debug)]
+pub struct CodeSearchTool {
+    allowed_dirs: Vec<PathBuf>,
+}
+
+impl CodeSearchTool {
+    pub fn new(allowed_dirs: Vec<PathBuf>) -> Self {
+        Self { allowed_dirs }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(allowed_dirs_from_env())
+    }
+}
+
+impl Tool for CodeSearchTool {
+    fn name(&self) -> &str {
+        "code_search"
+    }
+
+    fn info(&self) -> ToolInfo {
+        code_search_tool_info()
+    }
+
+    fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture {
+        let allowed_dirs = self.allowed_dirs.clone();
+
+        Box::pin(async move {
+            let search_params: CodeSearchParams = match serde_json::from_value(params.arguments) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Ok(standard_error_response(
+                        id,
+                        JsonRpcErrorCode::InvalidParams,
+                        &format!("Invalid parameters: {}", e),
+                    ));
+                }
+            };
+
+            // Walking and searching the tree is blocking IO
+            let result = tokio::task::spawn_blocking(move || {
+                let root = resolve_allowed_path(&allowed_dirs, &search_params.root)?;
+                search(&root, &search_params)
+            }).await?;
+            let tool_res = match result {
+                Ok(matches) => standard_tool_result(serde_json::to_string_pretty(&matches)?, None),
+                Err(e) => standard_tool_result(format!("Code search error: {}", e), Some(true)),
+            };
+            Ok(standard_success_response(id, json!(tool_res)))
+        })
+    }
+}
@@ -0,0 +1,389 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use shared_protocol_objects::{CallToolParams, JsonRpcErrorCode, ToolInfo};
+
+use crate::tool_trait::{
+    standard_error_response, standard_success_response, standard_tool_result, ExecuteFuture, Tool,
+};
+
+/// Rows returned by `read` and `filter` unless the caller passes `limit`
+const DEFAULT_ROW_LIMIT: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CsvAction {
+    Read,
+    Schema,
+    Filter,
+    Stats,
+    Write,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FilterOperator {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Contains,
+}
+
+#[derive(Debug, Deserialize)]
+struct FilterPredicate {
+    column: String,
+    operator: FilterOperator,
+    value: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct CsvParams {
+    action: CsvAction,
+    path: String,
+    #[serde(default)]
+    limit: Option<usize>,
+    #[serde(default)]
+    filter: Option<FilterPredicate>,
+    #[serde(default)]
+    data: Option<Vec<Map<String, Value>>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ColumnType {
+    Integer,
+    Float,
+    Date,
+    Boolean,
+    String,
+}
+
+#[derive(Debug, Serialize)]
+struct ColumnSchema {
+    name: String,
+    #[serde(rename = "type")]
+    column_type: ColumnType,
+    /// Whether any row leaves the column empty
+    nullable: bool,
+}
+
+pub fn csv_tool_info() -> ToolInfo {
+    ToolInfo {
+        name: "csv".to_string(),
+        description: Some(
+            "Read, filter and summarize CSV files without shell scripting.
+
+            Actions:
+            - read: first 'limit' rows (default 1000) as JSON objects keyed by header
+            - schema: column names with inferred types (integer, float, date, boolean, string)
+            - filter: rows matching 'filter' = { column, operator, value }; operators are
+              eq, ne, gt, gte, lt, lte and contains
+            - stats: count, min, max, mean and stddev of every numeric column
+            - write: write 'data', an array of JSON objects, to 'path' as CSV
+
+            The first line of the file must be a header row.".to_string()
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["read", "schema", "filter", "stats", "write"],
+                    "description": "The operation to perform."
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Path to the CSV file."
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of rows to return (for 'read' and 'filter', default 1000)."
+                },
+                "filter": {
+                    "type": "object",
+                    "properties": {
+                        "column": { "type": "string" },
+                        "operator": {
+                            "type": "string",
+                            "enum": ["eq", "ne", "gt", "gte", "lt", "lte", "contains"]
+                        },
+                        "value": {}
+                    },
+                    "required": ["column", "operator", "value"],
+                    "description": "Predicate rows must satisfy (for 'filter')."
+                },
+                "data": {
+                    "type": "array",
+                    "items": { "type": "object" },
+                    "description": "Rows to write (for 'write')."
+                }
+            },
+            "required": ["action", "path"],
+            "additionalProperties": false
+        }),
+        output_schema: None,
+    }
+}
+
+fn read_csv(path: &str) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+    let mut reader = csv::Reader::from_path(path)
+        .map_err(|e| anyhow!("Failed to open '{}': {}", path, e))?;
+    let headers = reader.headers()?.iter().map(String::from).collect();
+    let rows = reader.records()
+        .map(|record| record.map(|r| r.iter().map(String::from).collect()))
+        .collect::<csv::Result<Vec<Vec<String>>>>()?;
+    Ok((headers, rows))
+}
+
+fn row_to_object(headers: &[String], row: &[String]) -> Value {
+    let object: Map<String, Value> = headers.iter()
+        .zip(row)
+        .map(|(header, value)| (header.clone(), Value::String(value.clone())))
+        .collect();
+    Value::Object(object)
+}
+
+fn is_date(value: &str) -> bool {
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+        || chrono::DateTime::parse_from_rfc3339(value).is_ok()
+        || chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S").is_ok()
+}
+
+fn infer_type<'a>(values: impl Iterator<Item = &'a str>) -> ColumnType {
+    let mut values = values.filter(|v| !v.is_empty()).peekable();
+    if values.peek().is_none() {
+        return ColumnType::String;
+    }
+    let values: Vec<&str> = values.collect();
+
+    if values.iter().all(|v| v.parse::<i64>().is_ok()) {
+        ColumnType::Integer
+    } else if values.iter().all(|v| v.parse::<f64>().is_ok()) {
+        ColumnType::Float
+    } else if values.iter().all(|v| v.eq_ignore_ascii_case("true") || v.eq_ignore_ascii_case("false")) {
+        ColumnType::Boolean
+    } else if values.iter().all(|v| is_date(v)) {
+        ColumnType::Date
+    } else {
+        ColumnType::String
+    }
+}
+
+fn schema(headers: &[String], rows: &[Vec<String>]) -> Vec<ColumnSchema> {
+    headers.iter().enumerate().map(|(i, name)| {
+        let column = rows.iter().map(move |row| row.get(i).map(String::as_str).unwrap_or(""));
+        ColumnSchema {
+            name: name.clone(),
+            column_type: infer_type(column.clone()),
+            nullable: column.clone().any(str::is_empty),
+        }
+    }).collect()
+}
+
+fn cell_matches(cell: &str, operator: &FilterOperator, value: &Value) -> bool {
+    let expected = match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if let FilterOperator::Contains = operator {
+        return cell.contains(&expected);
+    }
+
+    // Compare numerically when both sides are numbers, otherwise as strings
+    let ordering = match (cell.parse::<f64>(), expected.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b),
+        _ => Some(cell.cmp(expected.as_str())),
+    };
+    let Some(ordering) = ordering else { return false };
+    match operator {
+        FilterOperator::Eq => ordering.is_eq(),
+        FilterOperator::Ne => ordering.is_ne(),
+        FilterOperator::Gt => ordering.is_gt(),
+        FilterOperator::Gte => ordering.is_ge(),
+        FilterOperator::Lt => ordering.is_lt(),
+        FilterOperator::Lte => ordering.is_le(),
+        FilterOperator::Contains => unreachable!(),
+    }
+}
+
+fn stats(headers: &[String], rows: &[Vec<String>]) -> Value {
+    let columns = schema(headers, rows);
+    let mut result = Map::new();
+    for (i, column) in columns.iter().enumerate() {
+        if !matches!(column.column_type, ColumnType::Integer | ColumnType::Float) {
+            continue;
+        }
+        let values: Vec<f64> = rows.iter()
+            .filter_map(|row| row.get(i).and_then(|v| v.parse().ok()))
+            .collect();
+        if values.is_empty() {
+            continue;
+        }
+
+        let count = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / count;
+        // Sample standard deviation; a single value has none
+        let stddev = if values.len() > 1 {
+            (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (count - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+        result.insert(column.name.clone(), json!({
+            "count": values.len(),
+            "min": values.iter().copied().fold(f64::INFINITY, f64::min),
+            "max": values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            "mean": mean,
+            "stddev": stddev,
+        }));
+    }
+    Value::Object(result)
+}
+
+fn write_csv(path: &str, data: &[Map<String, Value>]) -> Result<usize> {
+    // Header is the union of all keys. JSON objects keep their keys sorted, so this is the
+    // first row's keys alphabetically, followed by keys that only later rows have
+    let mut headers: Vec<&String> = Vec::new();
+    for row in data {
+        for key in row.keys() {
+            if !headers.contains(&key) {
+                headers.push(key);
+            }
+        }
+    }
+
+    let mut writer = csv::Writer::from_path(path)
+        .map_err(|e| anyhow!("Failed to create '{}': {}", path, e))?;
+    writer.write_record(&headers)?;
+    for row in data {
+        writer.write_record(headers.iter().map(|header| match row.get(*header) {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+        }))?;
+    }
+    writer.flush()?;
+    Ok(data.len())
+}
+
+fn run(params: CsvParams) -> Result<Value> {
+    let limit = params.limit.unwrap_or(DEFAULT_ROW_LIMIT);
+    match params.action {
+        CsvAction::Read => {
+            let (headers, rows) = read_csv(&params.path)?;
+            Ok(rows.iter().take(limit).map(|row| row_to_object(&headers, row)).collect())
+        }
+        CsvAction::Schema => {
+            let (headers, rows) = read_csv(&params.path)?;
+            Ok(json!(schema(&headers, &rows)))
+        }
+        CsvAction::Filter => {
+            let filter = params.filter.ok_or_else(|| anyhow!("Missing 'filter' parameter"))?;
+            let (headers, rows) = read_csv(&params.path)?;
+            let column = headers.iter().position(|h| *h == filter.column)
+                .ok_or_else(|| anyhow!("Unknown column '{}'", filter.column))?;
+            Ok(rows.iter()
+                .filter(|row| row.get(column).is_some_and(|cell| cell_matches(cell, &filter.operator, &filter.value)))
+                .take(limit)
+                .map(|row| row_to_object(&headers, row))
+                .collect())
+        }
+        CsvAction::Stats => {
+            let (headers, rows) = read_csv(&params.path)?;
+            Ok(stats(&headers, &rows))
+        }
+        CsvAction::Write => {
+            let data = params.data.ok_or_else(|| anyhow!("Missing 'data' parameter"))?;
+            let written = write_csv(&params.path, &data)?;
+            Ok(Value::String(format!("Wrote {} rows to {}", written, params.path)))
+        }
+    }
+}
+
+// CSV Tool Implementation
+#[derive(Debug)]
+pub struct CsvTool;
+
+impl Tool for CsvTool {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    fn info(&self) -> ToolInfo {
+        csv_tool_info()
+    }
+
+    fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture {
+        Box::pin(async move {
+            let csv_params: CsvParams = match serde_json::from_value(params.arguments) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Ok(standard_error_response(
+                        id,
+                        JsonRpcErrorCode::InvalidParams,
+                        &format!("Invalid parameters: {}", e),
+                    ));
+                }
+            };
+
+            // The csv crate reads synchronously
+            let result = tokio::task::spawn_blocking(move || run(csv_params)).await?;
+            let tool_res = match result {
+                Ok(Value::String(text)) => standard_tool_result(text, None),
+                Ok(value) => standard_tool_result(serde_json::to_string_pretty(&value)?, None),
+                Err(e) => standard_tool_result(format!("CSV error: {}", e), Some(true)),
+            };
+            Ok(standard_success_response(id, json!(tool_res)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column_type(values: &[&str]) -> ColumnType {
+        infer_type(values.iter().copied())
+    }
+
+    #[test]
+    fn infers_the_narrowest_type() {
+        assert_eq!(column_type(&["1", "-2", ""]), ColumnType::Integer);
+        assert_eq!(column_type(&["1", "2.5"]), ColumnType::Float);
+        assert_eq!(column_type(&["true", "FALSE"]), ColumnType::Boolean);
+        assert_eq!(column_type(&["2024-01-31", "2024-02-01T10:00:00Z"]), ColumnType::Date);
+        assert_eq!(column_type(&["1", "one"]), ColumnType::String);
+        assert_eq!(column_type(&["", ""]), ColumnType::String);
+    }
+
+    #[test]
+    fn schema_marks_columns_with_empty_cells_nullable() {
+        let headers = vec!["id".to_string(), "note".to_string()];
+        let rows = vec![
+            vec!["1".to_string(), "a".to_string()],
+            vec!["2".to_string()],
+        ];
+        let columns = schema(&headers, &rows);
+        assert!(!columns[0].nullable);
+        assert!(columns[1].nullable);
+    }
+
+    #[test]
+    fn filters_compare_numbers_numerically() {
+        assert!(cell_matches("10", &FilterOperator::Gt, &json!(9)));
+        assert!(cell_matches("10", &FilterOperator::Eq, &json!("10.0")));
+        assert!(cell_matches("9", &FilterOperator::Lte, &json!(9)));
+        // As strings "10" sorts before "9"
+        assert!(cell_matches("10", &FilterOperator::Lt, &json!("9x")));
+    }
+
+    #[test]
+    fn filters_on_strings() {
+        assert!(cell_matches("apple", &FilterOperator::Eq, &json!("apple")));
+        assert!(cell_matches("apple", &FilterOperator::Ne, &json!("pear")));
+        assert!(cell_matches("pineapple", &FilterOperator::Contains, &json!("apple")));
+        assert!(!cell_matches("pear", &FilterOperator::Contains, &json!("apple")));
+    }
+}
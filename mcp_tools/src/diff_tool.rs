@@ -0,0 +1,389 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use shared_protocol_objects::{CallToolParams, JsonRpcErrorCode, ToolInfo};
+use similar::{DiffTag, TextDiff};
+use std::fs;
+
+use crate::tool_trait::{
+    standard_error_response, standard_success_response, standard_tool_result, ExecuteFuture, Tool,
+};
+
+const DEFAULT_CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum DiffAction {
+    Diff,
+    Apply,
+    Stats,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiffParams {
+    action: DiffAction,
+    #[serde(default)]
+    old_path: Option<String>,
+    #[serde(default)]
+    new_path: Option<String>,
+    #[serde(default)]
+    old_text: Option<String>,
+    #[serde(default)]
+    new_text: Option<String>,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    patch: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+    #[serde(default)]
+    context_lines: Option<usize>,
+}
+
+pub fn diff_tool_info() -> ToolInfo {
+    ToolInfo {
+        name: "diff".to_string(),
+        description: Some(
+            "Compute and apply unified diffs.
+
+            Actions:
+            - diff: unified diff between 'old_path' and 'new_path', or between 'old_text' and
+              'new_text' (a path and a text can be mixed)
+            - stats: number of lines added, removed and changed between the same inputs as 'diff'
+            - apply: apply the unified diff 'patch' to the file at 'path'. A copy of the original is
+              saved as '<path>.bak'; with 'dry_run' the file is left untouched. Returns the patched
+              content and a summary of the changes.
+
+            Hunks must match the file exactly; a hunk that doesn't apply aborts the whole patch.".to_string()
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["diff", "apply", "stats"],
+                    "description": "The operation to perform."
+                },
+                "old_path": {
+                    "type": "string",
+                    "description": "Original file (for 'diff' and 'stats')."
+                },
+                "new_path": {
+                    "type": "string",
+                    "description": "Modified file (for 'diff' and 'stats')."
+                },
+                "old_text": {
+                    "type": "string",
+                    "description": "Original text, instead of 'old_path'."
+                },
+                "new_text": {
+                    "type": "string",
+                    "description": "Modified text, instead of 'new_path'."
+                },
+                "path": {
+                    "type": "string",
+                    "description": "File to patch (for 'apply')."
+                },
+                "patch": {
+                    "type": "string",
+                    "description": "Unified diff to apply (for 'apply')."
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Return the patched content without writing it (for 'apply', default false)."
+                },
+                "context_lines": {
+                    "type": "integer",
+                    "description": "Lines of context around each change (for 'diff', default 3)."
+                }
+            },
+            "required": ["action"],
+            "additionalProperties": false
+        }),
+        output_schema: None,
+    }
+}
+
+/// Content of one side of the diff, from the given path or the inline text
+fn side(path: &Option<String>, text: &Option<String>, name: &str) -> Result<(String, String)> {
+    match (path, text) {
+        (Some(path), _) => {
+            let content = fs::read_to_string(path)
+                .map_err(|e| anyhow!("Failed to read '{}': {}", path, e))?;
+            Ok((path.clone(), content))
+        }
+        (None, Some(text)) => Ok((name.to_string(), text.clone())),
+        (None, None) => Err(anyhow!("Either '{0}_path' or '{0}_text' is required", name)),
+    }
+}
+
+fn stats(old: &str, new: &str) -> Value {
+    let diff = TextDiff::from_lines(old, new);
+    let (mut added, mut removed, mut changed) = (0, 0, 0);
+    for op in diff.ops() {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        match tag {
+            DiffTag::Equal => {}
+            DiffTag::Insert => added += new_range.len(),
+            DiffTag::Delete => removed += old_range.len(),
+            // Lines replaced one for one count as changed, the rest as added or removed
+            DiffTag::Replace => {
+                let common = old_range.len().min(new_range.len());
+                changed += common;
+                added += new_range.len() - common;
+                removed += old_range.len() - common;
+            }
+        }
+    }
+    json!({
+        "added": added,
+        "removed": removed,
+        "changed": changed,
+        "hunks": diff.grouped_ops(DEFAULT_CONTEXT_LINES).len(),
+    })
+}
+
+#[derive(Debug)]
+struct Hunk {
+    old_start: usize,
+    /// Lines the hunk expects in the file: context and removals
+    old_lines: Vec<String>,
+    /// Lines that replace them: context and additions
+    new_lines: Vec<String>,
+}
+
+/// The `-a,b +c,d` ranges of a `@@ -a,b +c,d @@` header: where the hunk starts in the old
+/// file and how many old and new lines it has. A count left out means one line.
+fn parse_hunk_header(line: &str) -> Result<(usize, usize, usize)> {
+    let malformed = || anyhow!("Malformed hunk header: {}", line);
+    let mut ranges = line.trim_start_matches("@@").split_whitespace();
+    let mut range = |sign: char| -> Result<(usize, usize)> {
+        let range = ranges.next().and_then(|range| range.strip_prefix(sign)).ok_or_else(malformed)?;
+        let (start, count) = range.split_once(',').unwrap_or((range, "1"));
+        Ok((start.parse().map_err(|_| malformed())?, count.parse().map_err(|_| malformed())?))
+    };
+    let (old_start, old_count) = range('-')?;
+    let (_, new_count) = range('+')?;
+    Ok((old_start, old_count, new_count))
+}
+
+/// Split a unified diff into hunks. The line counts in each hunk header say where the hunk
+/// ends, so removed or added lines that look like `---`/`+++` file headers are kept.
+fn parse_patch(patch: &str) -> Result<Vec<Hunk>> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    // Old and new lines the current hunk still has to come
+    let (mut old_left, mut new_left) = (0, 0);
+    for line in patch.lines() {
+        // "\ No newline at end of file"
+        if line.starts_with('\\') {
+            continue;
+        }
+        if old_left == 0 && new_left == 0 {
+            // File headers, and anything else between hunks
+            if line.starts_with("@@") {
+                let (old_start, old_count, new_count) = parse_hunk_header(line)?;
+                (old_left, new_left) = (old_count, new_count);
+                hunks.push(Hunk { old_start, old_lines: Vec::new(), new_lines: Vec::new() });
+            }
+            continue;
+        }
+
+        let hunk = hunks.last_mut().expect("lines are only left inside a hunk");
+        let too_long = || anyhow!("Hunk at line {} has more lines than its header says", hunk.old_start);
+        match line.chars().next() {
+            Some('-') => {
+                old_left = old_left.checked_sub(1).ok_or_else(too_long)?;
+                hunk.old_lines.push(line[1..].to_string());
+            }
+            Some('+') => {
+                new_left = new_left.checked_sub(1).ok_or_else(too_long)?;
+                hunk.new_lines.push(line[1..].to_string());
+            }
+            // Some tools strip the space from empty context lines
+            Some(' ') | None => {
+                let text = line.get(1..).unwrap_or("").to_string();
+                old_left = old_left.checked_sub(1).ok_or_else(too_long)?;
+                new_left = new_left.checked_sub(1).ok_or_else(too_long)?;
+                hunk.old_lines.push(text.clone());
+                hunk.new_lines.push(text);
+            }
+            Some(_) => return Err(anyhow!("Unexpected line in patch: {}", line)),
+        }
+    }
+    if old_left > 0 || new_left > 0 {
+        return Err(anyhow!("Patch ends in the middle of a hunk"));
+    }
+    if hunks.is_empty() {
+        return Err(anyhow!("Patch contains no hunks"));
+    }
+    Ok(hunks)
+}
+
+/// Apply `hunks` to `content`. Each hunk is looked up at its stated position first and then
+/// anywhere after the previous hunk, so patches made against a slightly shifted file still apply.
+fn apply_hunks(content: &str, hunks: &[Hunk]) -> Result<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut result: Vec<String> = Vec::new();
+    let mut cursor = 0;
+
+    for (i, hunk) in hunks.iter().enumerate() {
+        let matches_at = |start: usize| {
+            start + hunk.old_lines.len() <= lines.len()
+                && hunk.old_lines.iter().zip(&lines[start..]).all(|(a, b)| a == b)
+        };
+        let expected = hunk.old_start.saturating_sub(1).max(cursor);
+        let start = if matches_at(expected) {
+            expected
+        } else {
+            (cursor..=lines.len()).find(|&start| matches_at(start))
+                .ok_or_else(|| anyhow!("Hunk {} (line {}) does not match the file", i + 1, hunk.old_start))?
+        };
+
+        result.extend(lines[cursor..start].iter().map(|l| l.to_string()));
+        result.extend(hunk.new_lines.iter().cloned());
+        cursor = start + hunk.old_lines.len();
+    }
+    result.extend(lines[cursor..].iter().map(|l| l.to_string()));
+
+    let mut patched = result.join("\n");
+    if content.ends_with('\n') && !patched.is_empty() {
+        patched.push('\n');
+    }
+    Ok(patched)
+}
+
+fn apply(path: &str, patch: &str, dry_run: bool) -> Result<Value> {
+    let original = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read '{}': {}", path, e))?;
+    let hunks = parse_patch(patch)?;
+    let patched = apply_hunks(&original, &hunks)?;
+
+    let backup = if dry_run {
+        None
+    } else {
+        let backup_path = format!("{}.bak", path);
+        fs::copy(path, &backup_path)
+            .map_err(|e| anyhow!("Failed to create backup file: {}", e))?;
+        fs::write(path, &patched)?;
+        Some(backup_path)
+    };
+
+    Ok(json!({
+        "path": path,
+        "dry_run": dry_run,
+        "backup": backup,
+        "hunks_applied": hunks.len(),
+        "summary": stats(&original, &patched),
+        "patched_content": patched,
+    }))
+}
+
+fn run(params: DiffParams) -> Result<Value> {
+    match params.action {
+        DiffAction::Diff => {
+            let (old_name, old) = side(&params.old_path, &params.old_text, "old")?;
+            let (new_name, new) = side(&params.new_path, &params.new_text, "new")?;
+            let diff = TextDiff::from_lines(&old, &new)
+                .unified_diff()
+                .context_radius(params.context_lines.unwrap_or(DEFAULT_CONTEXT_LINES))
+                .header(&old_name, &new_name)
+                .to_string();
+            if diff.is_empty() {
+                Ok(Value::String("No differences".to_string()))
+            } else {
+                Ok(Value::String(diff))
+            }
+        }
+        DiffAction::Stats => {
+            let (_, old) = side(&params.old_path, &params.old_text, "old")?;
+            let (_, new) = side(&params.new_path, &params.new_text, "new")?;
+            Ok(stats(&old, &new))
+        }
+        DiffAction::Apply => {
+            let path = params.path.as_deref().ok_or_else(|| anyhow!("Missing 'path' parameter"))?;
+            let patch = params.patch.as_deref().ok_or_else(|| anyhow!("Missing 'patch' parameter"))?;
+            apply(path, patch, params.dry_run)
+        }
+    }
+}
+
+// Diff Tool Implementation
+#[derive(Debug)]
+pub struct DiffTool;
+
+impl Tool for DiffTool {
+    fn name(&self) -> &str {
+        "diff"
+    }
+
+    fn info(&self) -> ToolInfo {
+        diff_tool_info()
+    }
+
+    fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture {
+        Box::pin(async move {
+            let diff_params: DiffParams = match serde_json::from_value(params.arguments) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Ok(standard_error_response(
+                        id,
+                        JsonRpcErrorCode::InvalidParams,
+                        &format!("Invalid parameters: {}", e),
+                    ));
+                }
+            };
+
+            let result = tokio::task::spawn_blocking(move || run(diff_params)).await?;
+            let tool_res = match result {
+                Ok(Value::String(text)) => standard_tool_result(text, None),
+                Ok(value) => standard_tool_result(serde_json::to_string_pretty(&value)?, None),
+                Err(e) => standard_tool_result(format!("Diff error: {}", e), Some(true)),
+            };
+            Ok(standard_success_response(id, json!(tool_res)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_a_patch_made_by_diff() {
+        let old = "one\ntwo\nthree\n";
+        let new = "one\n2\nthree\nfour\n";
+        let patch = TextDiff::from_lines(old, new).unified_diff().header("a", "b").to_string();
+        assert_eq!(apply_hunks(old, &parse_patch(&patch).unwrap()).unwrap(), new);
+    }
+
+    #[test]
+    fn lines_that_look_like_file_headers_stay_in_the_hunk() {
+        let old = "a\n-- x\nb\n";
+        let new = "a\n++ y\nb\n";
+        let patch = "--- old\n+++ new\n@@ -1,3 +1,3 @@\n a\n--- x\n+++ y\n b\n";
+        let hunks = parse_patch(patch).unwrap();
+        assert_eq!(hunks[0].old_lines, ["a", "-- x", "b"]);
+        assert_eq!(apply_hunks(old, &hunks).unwrap(), new);
+    }
+
+    #[test]
+    fn hunk_counts_default_to_one() {
+        let hunks = parse_patch("@@ -2 +2 @@\n-b\n+B\n").unwrap();
+        assert_eq!(hunks[0].old_start, 2);
+        assert_eq!(apply_hunks("a\nb\nc\n", &hunks).unwrap(), "a\nB\nc\n");
+    }
+
+    #[test]
+    fn shifted_hunks_still_apply() {
+        let hunks = parse_patch("@@ -1,2 +1,2 @@\n x\n-y\n+Y\n").unwrap();
+        assert_eq!(apply_hunks("new\nx\ny\n", &hunks).unwrap(), "new\nx\nY\n");
+    }
+
+    #[test]
+    fn rejects_malformed_patches() {
+        assert!(parse_patch("no hunks here").is_err());
+        assert!(parse_patch("@@ -1,2 +1,2 @@\n a\n").is_err());
+        assert!(parse_patch("@@ -1 +1 @@\n-a\n-b\n+c\n").is_err());
+        assert!(apply_hunks("a\n", &parse_patch("@@ -1 +1 @@\n-z\n+y\n").unwrap()).is_err());
+    }
+}
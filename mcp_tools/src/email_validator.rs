@@ -14,6 +14,7 @@ pub fn neverbounce_tool_info() -> ToolInfo {
             },
             "required": ["email"]
         }),
+        output_schema: None,
     }
 }
 
@@ -98,8 +99,7 @@ pub async fn handle_neverbounce_tool_call(
 
     // Return success with the tool’s text content
     let tool_res = CallToolResult {
-        content: vec![ToolResponseContent {
-            type_: "text".into(),
+        content: vec![ToolResponseContent::Text {
             text: tool_result_str,
             annotations: None,
         }],
@@ -0,0 +1,322 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use shared_protocol_objects::{CallToolParams, JsonRpcErrorCode, Root, ToolInfo};
+use std::env;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+use crate::tool_trait::{
+    standard_error_response, standard_success_response, standard_tool_result, ExecuteFuture, Tool,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FilesystemAction {
+    Read,
+    Write,
+    Append,
+    ListDir,
+    Stat,
+    Delete,
+    Move,
+}
+
+#[derive(Debug, Deserialize)]
+struct FilesystemParams {
+    action: FilesystemAction,
+    path: String,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    destination: Option<String>,
+    #[serde(default)]
+    glob: Option<String>,
+}
+
+/// Directories from the colon-separated `FILESYSTEM_ALLOWED_DIRS` variable, else the roots in
+/// `MCP_ROOTS`, falling back to the working directory. Entries that don't exist are dropped.
+pub fn allowed_dirs_from_env() -> Vec<PathBuf> {
+    let dirs: Vec<PathBuf> = match env::var("FILESYSTEM_ALLOWED_DIRS").or_else(|_| env::var("MCP_ROOTS")) {
+        Ok(value) => split_paths(&value),
+        Err(_) => env::current_dir().into_iter().collect(),
+    };
+    canonical_dirs(dirs)
+}
+
+/// The roots `roots/list` reports: exactly the directories `allowed_dirs_from_env` lets the
+/// file tools access
+pub fn roots_from_env() -> Vec<Root> {
+    allowed_dirs_from_env()
+        .into_iter()
+        .filter_map(|dir| {
+            let uri = url::Url::from_file_path(&dir).ok()?.to_string();
+            let name = dir.file_name().map(|name| name.to_string_lossy().into_owned());
+            Some(Root { uri, name })
+        })
+        .collect()
+}
+
+fn split_paths(value: &str) -> Vec<PathBuf> {
+    value.split(':').filter(|dir| !dir.is_empty()).map(PathBuf::from).collect()
+}
+
+fn canonical_dirs(dirs: Vec<PathBuf>) -> Vec<PathBuf> {
+    dirs.into_iter()
+        .filter_map(|dir| match dir.canonicalize() {
+            Ok(dir) => Some(dir),
+            Err(e) => {
+                warn!("Ignoring allowed directory {}: {}", dir.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Resolve `path` to an absolute path and make sure it lies inside one of `allowed_dirs`.
+/// Symlinks and `..` are resolved first, so they can't be used to escape; the path itself
+/// doesn't have to exist yet. A dangling symlink is rejected rather than treated as missing,
+/// since writing through it would create its target wherever that is.
+pub fn resolve_allowed_path(allowed_dirs: &[PathBuf], path: &str) -> Result<PathBuf> {
+    let path = Path::new(path);
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir()?.join(path)
+    };
+
+    // Canonicalize the deepest ancestor that exists and re-append the missing components.
+    // `symlink_metadata` doesn't follow links, so a dangling one counts as existing and
+    // fails to canonicalize below.
+    let mut existing = absolute.as_path();
+    let mut missing = Vec::new();
+    while std::fs::symlink_metadata(existing).is_err() {
+        let name = existing.file_name()
+            .ok_or_else(|| anyhow!("Invalid path: {}", absolute.display()))?;
+        missing.push(name);
+        existing = existing.parent()
+            .ok_or_else(|| anyhow!("Invalid path: {}", absolute.display()))?;
+    }
+    let mut resolved = existing.canonicalize()
+        .map_err(|e| anyhow!("Cannot resolve {}: {}", existing.display(), e))?;
+    for name in missing.into_iter().rev() {
+        resolved.push(name);
+    }
+
+    if allowed_dirs.iter().any(|dir| resolved.starts_with(dir)) {
+        Ok(resolved)
+    } else {
+        Err(anyhow!("Permission denied: {} is outside the allowed directories", resolved.display()))
+    }
+}
+
+pub fn filesystem_tool_info() -> ToolInfo {
+    ToolInfo {
+        name: "filesystem".to_string(),
+        description: Some(
+            "Read, write and manage files inside the allowed directories. Prefer this over bash for file access.
+
+            Actions:
+            - read: return the content of the file at 'path'
+            - write: replace the file at 'path' with 'content', creating it if needed
+            - append: add 'content' to the end of the file at 'path'
+            - list_dir: list the directory at 'path', optionally only names matching 'glob' (e.g. '*.rs')
+            - stat: size, type and modification time of 'path'
+            - delete: remove a file or an empty directory
+            - move: move or rename 'path' to 'destination'
+
+            Paths outside the allowed directories are rejected.".to_string()
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["read", "write", "append", "list_dir", "stat", "delete", "move"],
+                    "description": "The operation to perform."
+                },
+                "path": {
+                    "type": "string",
+                    "description": "File or directory to operate on."
+                },
+                "content": {
+                    "type": "string",
+                    "description": "Text to write (for 'write' and 'append')."
+                },
+                "destination": {
+                    "type": "string",
+                    "description": "Target path (for 'move')."
+                },
+                "glob": {
+                    "type": "string",
+                    "description": "Pattern that entry names must match (for 'list_dir')."
+                }
+            },
+            "required": ["action", "path"],
+            "additionalProperties": false
+        }),
+        output_schema: None,
+    }
+}
+
+fn modified_time(metadata: &std::fs::Metadata) -> Option<String> {
+    metadata.modified().ok().map(|time| DateTime::<Utc>::from(time).to_rfc3339())
+}
+
+// Filesystem Tool Implementation
+#[derive(Debug)]
+pub struct FilesystemTool {
+    allowed_dirs: Vec<PathBuf>,
+}
+
+impl FilesystemTool {
+    pub fn new(allowed_dirs: Vec<PathBuf>) -> Self {
+        Self { allowed_dirs }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(allowed_dirs_from_env())
+    }
+}
+
+async fn run(allowed_dirs: &[PathBuf], params: FilesystemParams) -> Result<String> {
+    let path = resolve_allowed_path(allowed_dirs, &params.path)?;
+    let content = || params.content.as_deref().ok_or_else(|| anyhow!("Missing 'content' parameter"));
+
+    match params.action {
+        FilesystemAction::Read => Ok(fs::read_to_string(&path).await?),
+        FilesystemAction::Write => {
+            let content = content()?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::write(&path, content).await?;
+            Ok(format!("Wrote {} bytes to {}", content.len(), path.display()))
+        }
+        FilesystemAction::Append => {
+            let content = content()?;
+            let mut file = fs::OpenOptions::new().create(true).append(true).open(&path).await?;
+            file.write_all(content.as_bytes()).await?;
+            Ok(format!("Appended {} bytes to {}", content.len(), path.display()))
+        }
+        FilesystemAction::ListDir => {
+            let pattern = params.glob.as_deref().map(glob::Pattern::new).transpose()?;
+            let mut entries = Vec::new();
+            let mut dir = fs::read_dir(&path).await?;
+            while let Some(entry) = dir.next_entry().await? {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if pattern.as_ref().is_some_and(|pattern| !pattern.matches(&name)) {
+                    continue;
+                }
+                let metadata = entry.metadata().await?;
+                entries.push(json!({
+                    "name": name,
+                    "is_dir": metadata.is_dir(),
+                    "size": metadata.len(),
+                }));
+            }
+            entries.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+            Ok(serde_json::to_string_pretty(&entries)?)
+        }
+        FilesystemAction::Stat => {
+            let metadata = fs::metadata(&path).await?;
+            Ok(serde_json::to_string_pretty(&json!({
+                "path": path,
+                "is_file": metadata.is_file(),
+                "is_dir": metadata.is_dir(),
+                "size": metadata.len(),
+                "readonly": metadata.permissions().readonly(),
+                "modified": modified_time(&metadata),
+            }))?)
+        }
+        FilesystemAction::Delete => {
+            if fs::metadata(&path).await?.is_dir() {
+                fs::remove_dir(&path).await?;
+            } else {
+                fs::remove_file(&path).await?;
+            }
+            Ok(format!("Deleted {}", path.display()))
+        }
+        FilesystemAction::Move => {
+            let destination = params.destination.as_deref()
+                .ok_or_else(|| anyhow!("Missing 'destination' parameter"))?;
+            let destination = resolve_allowed_path(allowed_dirs, destination)?;
+            fs::rename(&path, &destination).await?;
+            Ok(format!("Moved {} to {}", path.display(), destination.display()))
+        }
+    }
+}
+
+impl Tool for FilesystemTool {
+    fn name(&self) -> &str {
+        "filesystem"
+    }
+
+    fn info(&self) -> ToolInfo {
+        filesystem_tool_info()
+    }
+
+    fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture {
+        let allowed_dirs = self.allowed_dirs.clone();
+
+        Box::pin(async move {
+            let fs_params: FilesystemParams = match serde_json::from_value(params.arguments) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Ok(standard_error_response(
+                        id,
+                        JsonRpcErrorCode::InvalidParams,
+                        &format!("Invalid parameters: {}", e),
+                    ));
+                }
+            };
+
+            let tool_res = match run(&allowed_dirs, fs_params).await {
+                Ok(text) => standard_tool_result(text, None),
+                Err(e) => standard_tool_result(format!("Error: {}", e), Some(true)),
+            };
+            Ok(standard_success_response(id, json!(tool_res)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowed_dir() -> (tempfile::TempDir, Vec<PathBuf>) {
+        let dir = tempfile::tempdir().unwrap();
+        let allowed = vec![dir.path().canonicalize().unwrap()];
+        (dir, allowed)
+    }
+
+    #[test]
+    fn missing_paths_inside_the_dir_are_allowed() {
+        let (dir, allowed) = allowed_dir();
+        let path = dir.path().join("new/file.txt");
+        let resolved = resolve_allowed_path(&allowed, path.to_str().unwrap()).unwrap();
+        assert_eq!(resolved, allowed[0].join("new/file.txt"));
+    }
+
+    #[test]
+    fn dotdot_cannot_leave_the_dir() {
+        let (dir, allowed) = allowed_dir();
+        let path = dir.path().join("../outside.txt");
+        assert!(resolve_allowed_path(&allowed, path.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn dangling_symlink_to_outside_is_rejected() {
+        let (dir, allowed) = allowed_dir();
+        let outside = tempfile::tempdir().unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(outside.path().join("target.txt"), &link).unwrap();
+
+        assert!(resolve_allowed_path(&allowed, link.to_str().unwrap()).is_err());
+        let child = link.join("child.txt");
+        assert!(resolve_allowed_path(&allowed, child.to_str().unwrap()).is_err());
+    }
+}
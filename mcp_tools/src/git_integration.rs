@@ -1,7 +1,8 @@
 use anyhow::{anyhow, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use shared_protocol_objects::{error_response, success_response, CallToolParams, CallToolResult, JsonRpcResponse, ToolResponseContent, INTERNAL_ERROR};
+use shared_protocol_objects::{error_response, success_response, CallToolParams, CallToolResult, JsonRpcResponse, ToolResponseContent, JsonRpcErrorCode};
+use std::collections::HashMap;
 use std::process::{Command, Stdio};
 
 #[derive(Debug, Deserialize)]
@@ -21,6 +22,301 @@ struct GitParams {
     max_count: Option<usize>,
 }
 
+fn default_repo_path() -> String {
+    "./repo".to_string()
+}
+
+/// Parameters for the `log` action
+#[derive(Debug, Deserialize)]
+struct GitLogParams {
+    #[serde(default = "default_repo_path")]
+    repo_path: String,
+    #[serde(default)]
+    max_count: Option<usize>,
+    #[serde(default)]
+    since: Option<String>,
+    #[serde(default)]
+    until: Option<String>,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    grep: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CommitInfo {
+    hash: String,
+    author_name: String,
+    author_email: String,
+    date: String,
+    subject: String,
+    body: String,
+}
+
+// Unit and record separators can't appear in commit metadata, so they delimit fields and commits
+const FIELD_SEP: char = '\x1f';
+const RECORD_SEP: char = '\x1e';
+
+/// Run `git log` with the given filters and parse each commit into a `CommitInfo`.
+fn git_log(params: &GitLogParams) -> Result<Vec<CommitInfo>> {
+    let mut args = vec![
+        "log".to_string(),
+        format!("--max-count={}", params.max_count.unwrap_or(20)),
+        "--format=%H%x1f%an%x1f%ae%x1f%aI%x1f%s%x1f%b%x1e".to_string(),
+    ];
+    if let Some(since) = &params.since {
+        args.push(format!("--since={}", since));
+    }
+    if let Some(until) = &params.until {
+        args.push(format!("--until={}", until));
+    }
+    if let Some(author) = &params.author {
+        args.push(format!("--author={}", author));
+    }
+    if let Some(grep) = &params.grep {
+        args.push(format!("--grep={}", grep));
+    }
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let (stdout, _) = run_git_command(&params.repo_path, &args)?;
+
+    Ok(stdout
+        .split(RECORD_SEP)
+        .map(str::trim)
+        .filter(|record| !record.is_empty())
+        .filter_map(|record| {
+            let mut fields = record.splitn(6, FIELD_SEP);
+            Some(CommitInfo {
+                hash: fields.next()?.to_string(),
+                author_name: fields.next()?.to_string(),
+                author_email: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+                body: fields.next().unwrap_or_default().trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum StashAction {
+    Push,
+    Pop,
+    List,
+    Drop,
+}
+
+/// Parameters for the `stash` action; the stash operation is passed as `stash_action`
+#[derive(Debug, Deserialize)]
+struct GitStashParams {
+    #[serde(rename = "stash_action")]
+    action: StashAction,
+    #[serde(default = "default_repo_path")]
+    repo_path: String,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    stash_index: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct StashEntry {
+    index: usize,
+    message: String,
+    timestamp: String,
+}
+
+fn git_stash(params: &GitStashParams) -> Result<String> {
+    let stash_ref = format!("stash@{{{}}}", params.stash_index.unwrap_or(0));
+    let (stdout, _) = match params.action {
+        StashAction::Push => match &params.message {
+            Some(message) => run_git_command(&params.repo_path, &["stash", "push", "-m", message])?,
+            None => run_git_command(&params.repo_path, &["stash", "push"])?,
+        },
+        StashAction::Pop => run_git_command(&params.repo_path, &["stash", "pop", &stash_ref])?,
+        StashAction::Drop => run_git_command(&params.repo_path, &["stash", "drop", &stash_ref])?,
+        StashAction::List => {
+            let (stdout, _) = run_git_command(&params.repo_path, &["stash", "list", "--format=%gd%x1f%gs%x1f%cI"])?;
+            let entries: Vec<StashEntry> = stdout
+                .lines()
+                .filter_map(|line| {
+                    let mut fields = line.splitn(3, FIELD_SEP);
+                    // Refs look like `stash@{2}`
+                    let index = fields.next()?
+                        .strip_prefix("stash@{")?
+                        .strip_suffix('}')?
+                        .parse()
+                        .ok()?;
+                    Some(StashEntry {
+                        index,
+                        message: fields.next()?.to_string(),
+                        timestamp: fields.next()?.to_string(),
+                    })
+                })
+                .collect();
+            return Ok(serde_json::to_string_pretty(&entries)?);
+        }
+    };
+
+    Ok(if stdout.is_empty() { "Done.".to_string() } else { stdout })
+}
+
+/// Parameters for the `blame` action
+#[derive(Debug, Deserialize)]
+struct GitBlameParams {
+    #[serde(default = "default_repo_path")]
+    repo_path: String,
+    file_path: String,
+    #[serde(default)]
+    start_line: Option<usize>,
+    #[serde(default)]
+    end_line: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct BlameLine {
+    line_number: usize,
+    commit_hash: String,
+    author: String,
+    timestamp: i64,
+    content: String,
+}
+
+/// Blame output is capped to this many lines unless the caller narrows the range
+const MAX_BLAME_LINES: usize = 500;
+
+/// Run `git blame --porcelain` on the file and parse it into one `BlameLine` per line.
+fn git_blame(params: &GitBlameParams) -> Result<Vec<BlameLine>> {
+    let range = match (params.start_line, params.end_line) {
+        (Some(start), Some(end)) => Some(format!("{},{}", start, end)),
+        (Some(start), None) => Some(format!("{},", start)),
+        (None, Some(end)) => Some(format!("1,{}", end)),
+        (None, None) => None,
+    };
+    let mut args = vec!["blame", "--porcelain"];
+    if let Some(range) = &range {
+        args.extend(["-L", range.as_str()]);
+    }
+    args.extend(["--", params.file_path.as_str()]);
+    let (stdout, _) = run_git_command(&params.repo_path, &args)?;
+
+    // Porcelain output only lists a commit's author details the first time the commit appears
+    let mut authors: HashMap<String, (String, i64)> = HashMap::new();
+    let mut lines = Vec::new();
+    let mut current: Option<(String, usize)> = None;
+    for line in stdout.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            if let Some((hash, line_number)) = current.take() {
+                let (author, timestamp) = authors.get(&hash).cloned().unwrap_or_default();
+                lines.push(BlameLine { line_number, commit_hash: hash, author, timestamp, content: content.to_string() });
+                if lines.len() >= MAX_BLAME_LINES {
+                    break;
+                }
+            }
+        } else if let Some(author) = line.strip_prefix("author ") {
+            if let Some((hash, _)) = &current {
+                authors.entry(hash.clone()).or_default().0 = author.to_string();
+            }
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            if let Some((hash, _)) = &current {
+                authors.entry(hash.clone()).or_default().1 = time.parse().unwrap_or_default();
+            }
+        } else {
+            // Header: <hash> <original line> <final line> [<lines in group>]
+            let mut fields = line.split(' ');
+            if let (Some(hash), Some(_), Some(final_line)) = (fields.next(), fields.next(), fields.next()) {
+                if hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                    current = final_line.parse().ok().map(|n| (hash.to_string(), n));
+                }
+            }
+        }
+    }
+
+    Ok(lines)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum WorktreeAction {
+    List,
+    Add,
+    Remove,
+}
+
+/// Parameters for the `worktree` action; the operation is passed as `worktree_action`
+#[derive(Debug, Deserialize)]
+struct GitWorktreeParams {
+    #[serde(rename = "worktree_action")]
+    action: WorktreeAction,
+    #[serde(default = "default_repo_path")]
+    repo_path: String,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    branch: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct WorktreeInfo {
+    path: String,
+    head: Option<String>,
+    branch: Option<String>,
+    detached: bool,
+    bare: bool,
+    locked: bool,
+}
+
+fn git_worktree(params: &GitWorktreeParams) -> Result<String> {
+    match params.action {
+        WorktreeAction::List => {
+            let (stdout, _) = run_git_command(&params.repo_path, &["worktree", "list", "--porcelain"])?;
+            // Each worktree is a block of `key value` lines separated by a blank line
+            let worktrees: Vec<WorktreeInfo> = stdout
+                .split("\n\n")
+                .filter_map(|block| {
+                    let mut info = WorktreeInfo::default();
+                    for line in block.lines() {
+                        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+                        match key {
+                            "worktree" => info.path = value.to_string(),
+                            "HEAD" => info.head = Some(value.to_string()),
+                            "branch" => info.branch = Some(value.trim_start_matches("refs/heads/").to_string()),
+                            "detached" => info.detached = true,
+                            "bare" => info.bare = true,
+                            "locked" => info.locked = true,
+                            _ => {}
+                        }
+                    }
+                    (!info.path.is_empty()).then_some(info)
+                })
+                .collect();
+            Ok(serde_json::to_string_pretty(&worktrees)?)
+        }
+        WorktreeAction::Add => {
+            let path = params.path.as_deref().ok_or_else(|| anyhow!("Missing 'path' parameter"))?;
+            let branch = params.branch.as_deref().ok_or_else(|| anyhow!("Missing 'branch' parameter"))?;
+
+            let branch_ref = format!("refs/heads/{}", branch);
+            if run_git_command(&params.repo_path, &["rev-parse", "--verify", "--quiet", &branch_ref]).is_err() {
+                return Err(anyhow!("Branch '{}' does not exist", branch));
+            }
+            let target = std::path::Path::new(&params.repo_path).join(path);
+            if target.read_dir().is_ok_and(|mut entries| entries.next().is_some()) {
+                return Err(anyhow!("Path '{}' is already in use", path));
+            }
+
+            run_git_command(&params.repo_path, &["worktree", "add", path, branch])?;
+            Ok(format!("Worktree created at '{}' on branch '{}'.", path, branch))
+        }
+        WorktreeAction::Remove => {
+            let path = params.path.as_deref().ok_or_else(|| anyhow!("Missing 'path' parameter"))?;
+            run_git_command(&params.repo_path, &["worktree", "remove", path])?;
+            Ok(format!("Worktree at '{}' removed.", path))
+        }
+    }
+}
+
 /// Execute a command in `repo_path` directory, capturing stdout and stderr.
 fn run_git_command(repo_path: &str, args: &[&str]) -> Result<(String, String)> {
     let output = Command::new("git")
@@ -50,7 +346,8 @@ fn ensure_repo_initialized(repo_path: &str) -> Result<()> {
 
 /// Handle calls to the `git` tool.
 pub async fn handle_git_tool_call(params: CallToolParams, id: Option<Value>) -> Result<JsonRpcResponse> {
-    let git_params: GitParams = serde_json::from_value(params.arguments).map_err(|e| anyhow!(e))?;
+    let arguments = params.arguments;
+    let git_params: GitParams = serde_json::from_value(arguments.clone()).map_err(|e| anyhow!(e))?;
     let action = git_params.action.as_str();
 
     // Default repo path if none provided
@@ -101,6 +398,22 @@ pub async fn handle_git_tool_call(params: CallToolParams, id: Option<Value>) ->
                 stdout
             }
         }
+        "log" => {
+            let log_params: GitLogParams = serde_json::from_value(arguments).map_err(|e| anyhow!(e))?;
+            serde_json::to_string_pretty(&git_log(&log_params)?)?
+        }
+        "stash" => {
+            let stash_params: GitStashParams = serde_json::from_value(arguments).map_err(|e| anyhow!(e))?;
+            git_stash(&stash_params)?
+        }
+        "blame" => {
+            let blame_params: GitBlameParams = serde_json::from_value(arguments).map_err(|e| anyhow!(e))?;
+            serde_json::to_string_pretty(&git_blame(&blame_params)?)?
+        }
+        "worktree" => {
+            let worktree_params: GitWorktreeParams = serde_json::from_value(arguments).map_err(|e| anyhow!(e))?;
+            git_worktree(&worktree_params)?
+        }
         "push_changes" => {
             let remote = git_params.remote.as_deref().unwrap_or("origin");
             let branch = git_params.branch.as_deref().unwrap_or("main");
@@ -108,13 +421,12 @@ pub async fn handle_git_tool_call(params: CallToolParams, id: Option<Value>) ->
             "Changes pushed successfully.".to_string()
         }
         _ => {
-            return Ok(error_response(id, INTERNAL_ERROR, &format!("Unknown action '{}'", action)));
+            return Ok(error_response(id, JsonRpcErrorCode::InternalError, &format!("Unknown action '{}'", action)));
         }
     };
 
     let tool_res = CallToolResult {
-        content: vec![ToolResponseContent {
-            type_: "text".into(),
+        content: vec![ToolResponseContent::Text {
             text: result,
             annotations: None,
         }],
@@ -133,14 +445,15 @@ pub fn git_tool_info() -> shared_protocol_objects::ToolInfo {
         description: Some(
             "Git version control tool.
             
-            Use this to manage code changes.".to_string()
+            Use this to manage code changes. The 'log' action returns commits as JSON with \
+            hash, author, date, subject and body.".to_string()
         ),
         input_schema: json!({
             "type": "object",
             "properties": {
                 "action": {
                     "type": "string",
-                    "enum": ["init_repo","add_files","commit_changes","undo_last_commit","get_status","get_log","push_changes"],
+                    "enum": ["init_repo","add_files","commit_changes","undo_last_commit","get_status","get_log","log","stash","blame","worktree","push_changes"],
                     "description": "The git action to perform."
                 },
                 "repo_path": {
@@ -162,15 +475,62 @@ pub fn git_tool_info() -> shared_protocol_objects::ToolInfo {
                 },
                 "branch": {
                     "type": "string",
-                    "description": "Branch name for push (default 'main')."
+                    "description": "Branch name for push (default 'main'), or the branch to check out for 'worktree' add."
+                },
+                "worktree_action": {
+                    "type": "string",
+                    "enum": ["list", "add", "remove"],
+                    "description": "Worktree operation (for 'worktree'). 'add' and 'remove' need 'path'."
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Worktree location, relative to the repository (for 'worktree')."
                 },
                 "max_count": {
                     "type": "integer",
-                    "description": "Number of commits to retrieve for 'get_log' (default: 5)."
+                    "description": "Number of commits to retrieve for 'get_log' (default: 5) or 'log' (default: 20)."
+                },
+                "since": {
+                    "type": "string",
+                    "description": "Only commits more recent than this date, e.g. '2 weeks ago' (for 'log')."
+                },
+                "until": {
+                    "type": "string",
+                    "description": "Only commits older than this date (for 'log')."
+                },
+                "author": {
+                    "type": "string",
+                    "description": "Only commits whose author matches this pattern (for 'log')."
+                },
+                "stash_action": {
+                    "type": "string",
+                    "enum": ["push", "pop", "list", "drop"],
+                    "description": "Stash operation (for 'stash'). 'push' uses 'message' if given."
+                },
+                "stash_index": {
+                    "type": "integer",
+                    "description": "Stash entry for 'pop' and 'drop' (default: 0)."
+                },
+                "file_path": {
+                    "type": "string",
+                    "description": "File to blame, relative to the repository (for 'blame')."
+                },
+                "start_line": {
+                    "type": "integer",
+                    "description": "First line to blame (for 'blame'). At most 500 lines are returned."
+                },
+                "end_line": {
+                    "type": "integer",
+                    "description": "Last line to blame (for 'blame')."
+                },
+                "grep": {
+                    "type": "string",
+                    "description": "Only commits whose message matches this pattern (for 'log')."
                 }
             },
             "required": ["action"],
             "additionalProperties": false
-        })
+        }),
+        output_schema: None,
     }
 }
@@ -37,7 +37,8 @@ pub struct GmailToken {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GoogleOAuthConfig {
     pub client_id: String,
-    pub client_secret: String,
+    /// Not needed in PKCE mode
+    pub client_secret: Option<String>,
     pub redirect_uri: String,
     #[serde(default = "default_auth_uri")]
     pub auth_uri: String,
@@ -45,6 +46,9 @@ pub struct GoogleOAuthConfig {
     pub token_uri: String,
     #[serde(default = "default_scopes")]
     pub scopes: Vec<String>,
+    /// Use PKCE (RFC 7636) instead of the client secret, for clients that can't keep one
+    #[serde(default)]
+    pub use_pkce: bool,
 }
 
 fn default_auth_uri() -> String {
@@ -65,12 +69,15 @@ fn default_scopes() -> Vec<String> {
 
 impl GoogleOAuthConfig {
     pub fn from_env() -> Result<Self> {
+        let use_pkce = std::env::var("GOOGLE_OAUTH_USE_PKCE")
+            .is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
+
         // Check all required environment variables upfront
-        let missing_vars: Vec<&str> = vec![
-            "GOOGLE_OAUTH_CLIENT_ID",
-            "GOOGLE_OAUTH_CLIENT_SECRET",
-            "GOOGLE_OAUTH_REDIRECT_URI"
-        ]
+        let mut required_vars = vec!["GOOGLE_OAUTH_CLIENT_ID", "GOOGLE_OAUTH_REDIRECT_URI"];
+        if !use_pkce {
+            required_vars.push("GOOGLE_OAUTH_CLIENT_SECRET");
+        }
+        let missing_vars: Vec<&str> = required_vars
             .into_iter()
             .filter(|&var| std::env::var(var).is_err())
             .collect();
@@ -86,8 +93,9 @@ impl GoogleOAuthConfig {
 
         Ok(Self {
             client_id: std::env::var("GOOGLE_OAUTH_CLIENT_ID").unwrap(),
-            client_secret: std::env::var("GOOGLE_OAUTH_CLIENT_SECRET").unwrap(),
+            client_secret: std::env::var("GOOGLE_OAUTH_CLIENT_SECRET").ok(),
             redirect_uri: std::env::var("GOOGLE_OAUTH_REDIRECT_URI").unwrap(),
+            use_pkce,
             ..Default::default()
         })
     }
@@ -97,11 +105,12 @@ impl Default for GoogleOAuthConfig {
     fn default() -> Self {
         Self {
             client_id: "".into(),
-            client_secret: "".into(),
+            client_secret: None,
             redirect_uri: "".into(),
             auth_uri: default_auth_uri(),
             token_uri: default_token_uri(),
             scopes: default_scopes(),
+            use_pkce: false,
         }
     }
 }
@@ -120,7 +129,8 @@ pub struct TokenResponse {
 /// Parameters accepted by our Gmail tool.
 #[derive(Debug, Serialize, Deserialize)]
 struct GmailParams {
-    /// "auth_init", "auth_exchange", "send_message", "list_messages", "read_message", "search_messages", "modify_message"
+    /// "auth_init", "auth_exchange", "send_message", "list_messages", "read_message", "search_messages", "modify_message",
+    /// "reply", "forward", "get_thread", "create_draft", "update_draft", "list_drafts", "get_draft", "send_draft"
     action: String,
 
     /// For "auth_exchange"
@@ -130,10 +140,20 @@ struct GmailParams {
     to: Option<String>,
     subject: Option<String>,
     body: Option<String>,
+    /// HTML version of `body`, sent as a `multipart/alternative`
+    html_body: Option<String>,
+    /// Files to attach
+    attachment_paths: Option<Vec<String>>,
 
     /// For "read_message" or "modify_message"
     message_id: Option<String>,
 
+    /// For "reply", "forward" and "get_thread"
+    thread_id: Option<String>,
+
+    /// For "update_draft", "get_draft" and "send_draft"
+    draft_id: Option<String>,
+
     /// For pagination, listing, etc.
     page_size: Option<u32>,
 
@@ -177,13 +197,21 @@ pub fn gmail_tool_info() -> ToolInfo {
             "properties": {
                 "action": {
                     "type": "string",
-                    "description": "Action to perform: 'auth_init', 'auth_exchange', 'send_message', 'list_messages', 'read_message', 'search_messages', 'modify_message'"
+                    "description": "Action to perform: 'auth_init', 'auth_exchange', 'send_message', 'list_messages', 'read_message', 'search_messages', 'modify_message', 'reply', 'forward', 'get_thread', 'create_draft', 'update_draft', 'list_drafts', 'get_draft', 'send_draft'"
                 },
                 "code": {"type": "string", "description": "Authorization code (if 'auth_exchange')."},
                 "to": {"type": "string", "description": "Recipient email for sending messages."},
                 "subject": {"type": "string", "description": "Subject of the email to send."},
-                "body": {"type": "string", "description": "Body of the email to send."},
+                "body": {"type": "string", "description": "Plain text body of the email to send."},
+                "html_body": {"type": "string", "description": "Optional HTML body, sent alongside the plain text body."},
+                "attachment_paths": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Paths of local files to attach to the email."
+                },
                 "message_id": {"type": "string", "description": "Message ID to read or modify."},
+                "draft_id": {"type": "string", "description": "Draft ID for 'update_draft', 'get_draft' and 'send_draft'. 'update_draft' keeps 'to', 'subject' and 'body' of the draft unless given."},
+                "thread_id": {"type": "string", "description": "Thread ID for 'reply', 'forward' and 'get_thread'. 'reply' and 'forward' use the latest message of the thread; for 'forward', 'body' is an optional note placed above the forwarded message."},
                 "page_size": {"type": "number", "description": "How many messages to list, for 'list_messages'."},
                 "search_query": {
                     "type": "string", 
@@ -212,6 +240,7 @@ pub fn gmail_tool_info() -> ToolInfo {
             },
             "required": ["action"]
         }),
+        output_schema: None,
     }
 }
 
@@ -236,8 +265,7 @@ pub async fn handle_gmail_tool_call(
                     success_response(
                         id,
                         serde_json::to_value(CallToolResult {
-                            content: vec![ToolResponseContent {
-                                type_: "text".into(),
+                            content: vec![ToolResponseContent::Text {
                                 text: content.to_string(),
                                 annotations: None,
                             }],
@@ -251,7 +279,12 @@ pub async fn handle_gmail_tool_call(
             } else {
                 let config = GoogleOAuthConfig::from_env()
                     .map_err(|e| anyhow!("Failed to load OAuth config: {}", e))?;
-                let auth_url = build_auth_url(&config)
+                let code_challenge = if config.use_pkce {
+                    Some(start_pkce()?)
+                } else {
+                    None
+                };
+                let auth_url = build_auth_url(&config, code_challenge.as_deref())
                     .map_err(|e| anyhow!("Failed to build auth URL: {}", e))?;
 
                 let content = format!(
@@ -263,8 +296,7 @@ pub async fn handle_gmail_tool_call(
                     success_response(
                         id,
                         serde_json::to_value(CallToolResult {
-                            content: vec![ToolResponseContent {
-                                type_: "text".into(),
+                            content: vec![ToolResponseContent::Text {
                                 text: content,
                                 annotations: None,
                             }],
@@ -307,8 +339,7 @@ pub async fn handle_gmail_tool_call(
                 success_response(
                     id,
                     serde_json::to_value(CallToolResult {
-                        content: vec![ToolResponseContent {
-                            type_: "text".into(),
+                        content: vec![ToolResponseContent::Text {
                             text: success_message.to_string(),
                             annotations: None,
                         }],
@@ -348,14 +379,21 @@ pub async fn handle_gmail_tool_call(
                 .clone()
                 .ok_or_else(|| anyhow!("'body' is required for 'send_message'"))?;
 
-            send_gmail_message(&token.access_token, &to, &subject, &body).await?;
+            let email = OutgoingEmail {
+                to: to.clone(),
+                subject,
+                body,
+                html_body: gmail_params.html_body.clone(),
+                attachment_paths: gmail_params.attachment_paths.clone().unwrap_or_default(),
+                ..Default::default()
+            };
+            send_gmail_message(&token.access_token, &email).await?;
 
             Ok(
                 success_response(
                     id,
                     serde_json::to_value(CallToolResult {
-                        content: vec![ToolResponseContent {
-                            type_: "text".into(),
+                        content: vec![ToolResponseContent::Text {
                             text: format!("Email to '{}' sent successfully.", to),
                             annotations: None,
                         }],
@@ -408,8 +446,7 @@ pub async fn handle_gmail_tool_call(
                 success_response(
                     id,
                     serde_json::to_value(CallToolResult {
-                        content: vec![ToolResponseContent {
-                            type_: "text".into(),
+                        content: vec![ToolResponseContent::Text {
                             text: output,
                             annotations: None,
                         }],
@@ -448,8 +485,7 @@ pub async fn handle_gmail_tool_call(
                 success_response(
                     id,
                     serde_json::to_value(CallToolResult {
-                        content: vec![ToolResponseContent {
-                            type_: "text".into(),
+                        content: vec![ToolResponseContent::Text {
                             text: format!("Message ID: {}\n\n{}", msg_id, msg_body),
                             annotations: None,
                         }],
@@ -492,8 +528,7 @@ pub async fn handle_gmail_tool_call(
                 success_response(
                     id,
                     serde_json::to_value(CallToolResult {
-                        content: vec![ToolResponseContent {
-                            type_: "text".into(),
+                        content: vec![ToolResponseContent::Text {
                             text: format!(
                                 "Found {} messages matching '{}':\n{}",
                                 messages.len(),
@@ -564,8 +599,7 @@ pub async fn handle_gmail_tool_call(
                 success_response(
                     id,
                     serde_json::to_value(CallToolResult {
-                        content: vec![ToolResponseContent {
-                            type_: "text".into(),
+                        content: vec![ToolResponseContent::Text {
                             text: summary,
                             annotations: None,
                         }],
@@ -578,6 +612,186 @@ pub async fn handle_gmail_tool_call(
             )
         }
 
+        "get_thread" => {
+            let token = match get_or_refresh_token().await {
+                Ok(t) => t,
+                Err(e) => {
+                    return Ok(
+                        missing_auth_response(
+                            id,
+                            &format!("Failed to get a valid token: {}.", e)
+                        )
+                    )
+                }
+            };
+
+            let thread_id = gmail_params.thread_id
+                .clone()
+                .ok_or_else(|| anyhow!("'thread_id' is required for 'get_thread'"))?;
+
+            let messages = get_gmail_thread(&token.access_token, &thread_id).await?;
+            let json_output = serde_json::to_string_pretty(&messages)?;
+
+            Ok(
+                success_response(
+                    id,
+                    serde_json::to_value(CallToolResult {
+                        content: vec![ToolResponseContent::Text {
+                            text: format!(
+                                "Thread {} has {} messages:\n{}",
+                                thread_id,
+                                messages.len(),
+                                json_output
+                            ),
+                            annotations: None,
+                        }],
+                        is_error: Some(false),
+                        _meta: None,
+                        progress: None,
+                        total: None,
+                    })?
+                )
+            )
+        }
+
+        "reply" | "forward" => {
+            let token = match get_or_refresh_token().await {
+                Ok(t) => t,
+                Err(e) => {
+                    return Ok(
+                        missing_auth_response(
+                            id,
+                            &format!("Failed to get a valid token: {}.", e)
+                        )
+                    )
+                }
+            };
+
+            let action = gmail_params.action.as_str();
+            let thread_id = gmail_params.thread_id
+                .clone()
+                .ok_or_else(|| anyhow!("'thread_id' is required for '{}'", action))?;
+            let to = gmail_params.to
+                .clone()
+                .ok_or_else(|| anyhow!("'to' is required for '{}'", action))?;
+
+            let messages = get_gmail_thread(&token.access_token, &thread_id).await?;
+            let last = messages
+                .last()
+                .ok_or_else(|| anyhow!("Thread {} has no messages", thread_id))?;
+
+            let email = if action == "reply" {
+                let body = gmail_params.body
+                    .clone()
+                    .ok_or_else(|| anyhow!("'body' is required for 'reply'"))?;
+                reply_email(last, to.clone(), body)
+            } else {
+                forward_email(last, to.clone(), gmail_params.body.as_deref())
+            };
+            let email = OutgoingEmail {
+                html_body: gmail_params.html_body.clone(),
+                attachment_paths: gmail_params.attachment_paths.clone().unwrap_or_default(),
+                ..email
+            };
+            send_gmail_message(&token.access_token, &email).await?;
+
+            let verb = if action == "reply" { "Replied" } else { "Forwarded" };
+            Ok(
+                success_response(
+                    id,
+                    serde_json::to_value(CallToolResult {
+                        content: vec![ToolResponseContent::Text {
+                            text: format!("{} to '{}' in thread {}.", verb, to, thread_id),
+                            annotations: None,
+                        }],
+                        is_error: Some(false),
+                        _meta: None,
+                        progress: None,
+                        total: None,
+                    })?
+                )
+            )
+        }
+
+        "create_draft" | "update_draft" | "list_drafts" | "get_draft" | "send_draft" => {
+            let token = match get_or_refresh_token().await {
+                Ok(t) => t,
+                Err(e) => {
+                    return Ok(
+                        missing_auth_response(
+                            id,
+                            &format!("Failed to get a valid token: {}.", e)
+                        )
+                    )
+                }
+            };
+
+            let action = gmail_params.action.as_str();
+            let draft_id = || gmail_params.draft_id
+                .clone()
+                .ok_or_else(|| anyhow!("'draft_id' is required for '{}'", action));
+
+            let text = match action {
+                "create_draft" => {
+                    let email = OutgoingEmail {
+                        to: gmail_params.to.clone()
+                            .ok_or_else(|| anyhow!("'to' is required for 'create_draft'"))?,
+                        subject: gmail_params.subject.clone().unwrap_or_default(),
+                        body: gmail_params.body.clone().unwrap_or_default(),
+                        html_body: gmail_params.html_body.clone(),
+                        attachment_paths: gmail_params.attachment_paths.clone().unwrap_or_default(),
+                        ..Default::default()
+                    };
+                    let draft_id = create_gmail_draft(&token.access_token, &email).await?;
+                    format!("Draft created. draft_id: {}", draft_id)
+                }
+                "update_draft" => {
+                    let draft_id = draft_id()?;
+                    let existing = get_gmail_draft(&token.access_token, &draft_id).await?.message;
+                    let email = OutgoingEmail {
+                        to: gmail_params.to.clone().or(existing.to).unwrap_or_default(),
+                        subject: gmail_params.subject.clone().or(existing.subject).unwrap_or_default(),
+                        body: gmail_params.body.clone().or(existing.body).unwrap_or_default(),
+                        html_body: gmail_params.html_body.clone(),
+                        attachment_paths: gmail_params.attachment_paths.clone().unwrap_or_default(),
+                        thread_id: Some(existing.thread_id).filter(|t| !t.is_empty()),
+                        ..Default::default()
+                    };
+                    update_gmail_draft(&token.access_token, &draft_id, &email).await?;
+                    format!("Draft {} updated.", draft_id)
+                }
+                "list_drafts" => {
+                    let drafts = list_gmail_drafts(&token.access_token, gmail_params.page_size.unwrap_or(10)).await?;
+                    format!("Found {} drafts:\n{}", drafts.len(), serde_json::to_string_pretty(&drafts)?)
+                }
+                "get_draft" => {
+                    let draft = get_gmail_draft(&token.access_token, &draft_id()?).await?;
+                    serde_json::to_string_pretty(&draft)?
+                }
+                _ => {
+                    let draft_id = draft_id()?;
+                    let message_id = send_gmail_draft(&token.access_token, &draft_id).await?;
+                    format!("Draft {} sent as message {}.", draft_id, message_id)
+                }
+            };
+
+            Ok(
+                success_response(
+                    id,
+                    serde_json::to_value(CallToolResult {
+                        content: vec![ToolResponseContent::Text {
+                            text,
+                            annotations: None,
+                        }],
+                        is_error: Some(false),
+                        _meta: None,
+                        progress: None,
+                        total: None,
+                    })?
+                )
+            )
+        }
+
         _ => {
             // Invalid action
             Err(anyhow!("Invalid action '{}'", gmail_params.action))
@@ -588,17 +802,69 @@ pub async fn handle_gmail_tool_call(
 /// ---------------------------------------
 /// Helper: Build the Google OAuth 2.0 authorization URL
 /// ---------------------------------------
-fn build_auth_url(config: &GoogleOAuthConfig) -> Result<String> {
+fn build_auth_url(config: &GoogleOAuthConfig, code_challenge: Option<&str>) -> Result<String> {
     let scopes_str = config.scopes.join(" ");
-    Ok(
-        format!(
-            "{}?client_id={}&redirect_uri={}&response_type=code&scope={}&access_type=offline&prompt=consent",
-            config.auth_uri,
-            urlencoding::encode(&config.client_id),
-            urlencoding::encode(&config.redirect_uri),
-            urlencoding::encode(&scopes_str)
-        )
-    )
+    let mut url = format!(
+        "{}?client_id={}&redirect_uri={}&response_type=code&scope={}&access_type=offline&prompt=consent",
+        config.auth_uri,
+        urlencoding::encode(&config.client_id),
+        urlencoding::encode(&config.redirect_uri),
+        urlencoding::encode(&scopes_str)
+    );
+    if let Some(challenge) = code_challenge {
+        url.push_str(&format!("&code_challenge={}&code_challenge_method=S256", challenge));
+    }
+    Ok(url)
+}
+
+/// ---------------------------------------
+/// Helper: PKCE code verifier handling
+/// ---------------------------------------
+fn pkce_verifier_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| anyhow!("Unable to determine the user's config directory"))?
+        .join("mcp");
+    fs::create_dir_all(&config_dir)
+        .map_err(|e| anyhow!("Failed to create {}: {}", config_dir.display(), e))?;
+    Ok(config_dir.join("gmail_pkce_verifier"))
+}
+
+/// Generate a code verifier, keep it for `auth_exchange` and return its S256 challenge
+fn start_pkce() -> Result<String> {
+    use rand::Rng;
+    use sha2::{ Digest, Sha256 };
+
+    // RFC 7636 allows 43-128 characters from the unreserved set
+    const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~";
+    let mut rng = rand::thread_rng();
+    let verifier: String = (0..64)
+        .map(|_| CHARSET[rng.gen_range(0..CHARSET.len())] as char)
+        .collect();
+
+    // A verifier left by an earlier `auth_init` belongs to a flow that is now abandoned
+    let path = pkce_verifier_path()?;
+    let _ = fs::remove_file(&path);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(&path)
+        .and_then(|mut file| io::Write::write_all(&mut file, verifier.as_bytes()))
+        .map_err(|e| anyhow!("Failed to store PKCE code verifier: {}", e))?;
+
+    let digest = Sha256::digest(verifier.as_bytes());
+    Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(digest))
+}
+
+/// Read the verifier stored by `start_pkce`. It is kept until an exchange succeeds, so a
+/// mistyped code can be retried.
+fn read_pkce_verifier() -> Result<String> {
+    let verifier = fs::read_to_string(pkce_verifier_path()?)
+        .map_err(|_| anyhow!("No PKCE code verifier found. Run 'auth_init' first."))?;
+    Ok(verifier.trim().to_string())
 }
 
 /// ---------------------------------------
@@ -606,20 +872,29 @@ fn build_auth_url(config: &GoogleOAuthConfig) -> Result<String> {
 /// ---------------------------------------
 async fn exchange_code_for_token(config: &GoogleOAuthConfig, code: &str) -> Result<TokenResponse> {
     let client = Client::new();
-    let params = [
-        ("client_id", config.client_id.as_str()),
-        ("client_secret", config.client_secret.as_str()),
-        ("code", code),
-        ("redirect_uri", config.redirect_uri.as_str()),
-        ("grant_type", "authorization_code"),
+    let mut params = vec![
+        ("client_id", config.client_id.clone()),
+        ("code", code.to_string()),
+        ("redirect_uri", config.redirect_uri.clone()),
+        ("grant_type", "authorization_code".to_string()),
     ];
+    if config.use_pkce {
+        params.push(("code_verifier", read_pkce_verifier()?));
+    } else if let Some(secret) = &config.client_secret {
+        params.push(("client_secret", secret.clone()));
+    }
 
     let response = client
         .post(&config.token_uri)
         .form(&params)
         .send().await?
+        .error_for_status()?
         .json::<TokenResponse>().await?;
 
+    // A verifier is only valid for one exchange
+    if config.use_pkce {
+        let _ = fs::remove_file(pkce_verifier_path()?);
+    }
     Ok(response)
 }
 
@@ -635,12 +910,15 @@ async fn refresh_access_token(token: &GmailToken) -> Result<GmailToken> {
     }
 
     let client = Client::new();
-    let params = [
+    let mut params = vec![
         ("client_id", config.client_id.as_str()),
-        ("client_secret", config.client_secret.as_str()),
         ("refresh_token", token.refresh_token.as_ref().unwrap().as_str()),
         ("grant_type", "refresh_token"),
     ];
+    // PKCE clients refresh with the client ID alone
+    if let Some(secret) = &config.client_secret {
+        params.push(("client_secret", secret.as_str()));
+    }
 
     let response = client
         .post(&config.token_uri)
@@ -698,22 +976,140 @@ async fn get_or_refresh_token() -> Result<GmailToken> {
     Ok(token)
 }
 
+/// An email to send, before MIME encoding
+#[derive(Debug, Clone, Default)]
+pub struct OutgoingEmail {
+    pub to: String,
+    pub subject: String,
+    /// Plain text body, always included
+    pub body: String,
+    /// Optional HTML alternative to `body`
+    pub html_body: Option<String>,
+    /// Files attached to the message
+    pub attachment_paths: Vec<String>,
+    /// Gmail thread the message belongs to, for replies
+    pub thread_id: Option<String>,
+    /// `Message-ID` of the message being replied to
+    pub in_reply_to: Option<String>,
+    /// `References` header listing the earlier messages of the thread
+    pub references: Option<String>,
+}
+
+/// Base64 with line breaks every 76 characters, as MIME requires
+fn mime_base64(data: &[u8]) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+    encoded.as_bytes()
+        .chunks(76)
+        .map(|line| String::from_utf8_lossy(line).into_owned())
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+/// RFC 2047 encoding for header values that aren't plain ASCII
+fn encode_header_value(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!("=?UTF-8?B?{}?=", base64::engine::general_purpose::STANDARD.encode(value))
+    }
+}
+
+fn text_part(content_type: &str, text: &str) -> String {
+    format!(
+        "Content-Type: {}; charset=\"UTF-8\"\r\nContent-Transfer-Encoding: base64\r\n\r\n{}\r\n",
+        content_type,
+        mime_base64(text.as_bytes())
+    )
+}
+
+fn multipart(subtype: &str, parts: &[String]) -> String {
+    let boundary = format!("mcp_{}_{}", subtype, uuid::Uuid::new_v4().simple());
+    let mut out = format!("Content-Type: multipart/{}; boundary=\"{}\"\r\n\r\n", subtype, boundary);
+    for part in parts {
+        out.push_str(&format!("--{}\r\n{}", boundary, part));
+    }
+    out.push_str(&format!("--{}--\r\n", boundary));
+    out
+}
+
+/// Header values come from the caller, so a line break could add headers of its own
+fn check_header_value(name: &str, value: &str) -> Result<()> {
+    if value.contains(['\r', '\n']) {
+        return Err(anyhow!("'{}' must not contain line breaks", name));
+    }
+    Ok(())
+}
+
+/// Attachments are read under the same rules as the filesystem tool, so only files inside
+/// the allowed directories can be mailed out
+fn attachment_part(path: &str) -> Result<String> {
+    let allowed_dirs = crate::filesystem_tool::allowed_dirs_from_env();
+    let resolved = crate::filesystem_tool::resolve_allowed_path(&allowed_dirs, path)?;
+    let data = fs::read(&resolved).map_err(|e| anyhow!("Failed to read attachment '{}': {}", path, e))?;
+    let filename = std::path::Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| anyhow!("Invalid attachment path '{}'", path))?;
+    let mime_type = mime_guess::from_path(path).first_or_octet_stream();
+    let filename = encode_header_value(&filename).replace('"', "");
+    Ok(format!(
+        "Content-Type: {}; name=\"{}\"\r\nContent-Disposition: attachment; filename=\"{}\"\r\nContent-Transfer-Encoding: base64\r\n\r\n{}\r\n",
+        mime_type,
+        filename,
+        filename,
+        mime_base64(&data)
+    ))
+}
+
+/// Build the raw RFC 5322 message: plain text, `multipart/alternative` when there is an
+/// HTML body, wrapped in `multipart/mixed` when there are attachments.
+pub fn build_mime_message(email: &OutgoingEmail) -> Result<String> {
+    check_header_value("to", &email.to)?;
+    check_header_value("subject", &email.subject)?;
+    for (name, value) in [("in_reply_to", &email.in_reply_to), ("references", &email.references)] {
+        if let Some(value) = value {
+            check_header_value(name, value)?;
+        }
+    }
+    let mut content = match &email.html_body {
+        Some(html) => multipart("alternative", &[
+            text_part("text/plain", &email.body),
+            text_part("text/html", html),
+        ]),
+        None => text_part("text/plain", &email.body),
+    };
+
+    if !email.attachment_paths.is_empty() {
+        let mut parts = vec![content];
+        for path in &email.attachment_paths {
+            parts.push(attachment_part(path)?);
+        }
+        content = multipart("mixed", &parts);
+    }
+
+    let mut headers = format!("From: me\r\nTo: {}\r\nSubject: {}\r\n", email.to, encode_header_value(&email.subject));
+    if let Some(in_reply_to) = &email.in_reply_to {
+        headers.push_str(&format!("In-Reply-To: {}\r\n", in_reply_to));
+    }
+    if let Some(references) = &email.references {
+        headers.push_str(&format!("References: {}\r\n", references));
+    }
+    Ok(format!("{}MIME-Version: 1.0\r\n{}", headers, content))
+}
+
 /// ---------------------------------------
 /// Send a Gmail message
 /// ---------------------------------------
-pub async fn send_gmail_message(
-    access_token: &str,
-    to: &str,
-    subject: &str,
-    body: &str
-) -> Result<()> {
+pub async fn send_gmail_message(access_token: &str, email: &OutgoingEmail) -> Result<()> {
     let client = Client::new();
-    let email_content = format!("From: me\r\nTo: {}\r\nSubject: {}\r\n\r\n{}", to, subject, body);
-    let encoded_email = base64::encode(email_content.as_bytes());
+    let encoded_email = URL_SAFE.encode(build_mime_message(email)?.as_bytes());
 
-    let payload = serde_json::json!({
+    let mut payload = serde_json::json!({
         "raw": encoded_email
     });
+    if let Some(thread_id) = &email.thread_id {
+        payload["threadId"] = json!(thread_id);
+    }
 
     let resp = client
         .post("https://gmail.googleapis.com/gmail/v1/users/me/messages/send")
@@ -878,6 +1274,270 @@ pub async fn read_gmail_message(access_token: &str, message_id: &str) -> Result<
     Err(anyhow!("Could not find message body"))
 }
 
+/// A message of a thread, with the headers needed to reply to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadMessage {
+    pub id: String,
+    pub thread_id: String,
+    pub subject: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub date: Option<String>,
+    /// The RFC 5322 `Message-ID` header, not the Gmail ID
+    pub message_id: Option<String>,
+    pub references: Option<String>,
+    pub snippet: Option<String>,
+    /// Plain text body, if the message has one
+    pub body: Option<String>,
+}
+
+/// The first `text/plain` part of a message payload, searching nested multiparts
+fn find_plain_text(payload: &Value) -> Result<Option<String>> {
+    let mime_type = payload.get("mimeType").and_then(|m| m.as_str()).unwrap_or("");
+    if mime_type == "text/plain" {
+        if let Some(data) = payload.get("body").and_then(|b| b.get("data")).and_then(|d| d.as_str()) {
+            return Ok(Some(String::from_utf8(URL_SAFE.decode(data)?)?));
+        }
+    }
+    if let Some(parts) = payload.get("parts").and_then(|p| p.as_array()) {
+        for part in parts {
+            if let Some(text) = find_plain_text(part)? {
+                return Ok(Some(text));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Parse a message fetched with `format=full`
+fn parse_full_message(msg: &Value) -> Result<ThreadMessage> {
+    let payload = msg.get("payload").cloned().unwrap_or_default();
+    let header = |wanted: &str| {
+        payload.get("headers")
+            .and_then(|h| h.as_array())
+            .and_then(|headers| headers.iter().find(|header| {
+                header.get("name")
+                    .and_then(|n| n.as_str())
+                    .is_some_and(|name| name.eq_ignore_ascii_case(wanted))
+            }))
+            .and_then(|header| header.get("value"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    };
+    let field = |name: &str| msg.get(name).and_then(|v| v.as_str()).map(|s| s.to_string());
+
+    Ok(ThreadMessage {
+        id: field("id").unwrap_or_default(),
+        thread_id: field("threadId").unwrap_or_default(),
+        subject: header("Subject"),
+        from: header("From"),
+        to: header("To"),
+        date: header("Date"),
+        message_id: header("Message-ID"),
+        references: header("References"),
+        snippet: field("snippet"),
+        body: find_plain_text(&payload)?,
+    })
+}
+
+/// ---------------------------------------
+/// Fetch every message of a thread, oldest first
+/// ---------------------------------------
+pub async fn get_gmail_thread(access_token: &str, thread_id: &str) -> Result<Vec<ThreadMessage>> {
+    let client = Client::new();
+    let url = format!("https://gmail.googleapis.com/gmail/v1/users/me/threads/{}?format=full", thread_id);
+
+    let resp = client
+        .get(&url)
+        .bearer_auth(access_token)
+        .send().await?;
+
+    if !resp.status().is_success() {
+        let msg = resp.text().await.unwrap_or_default();
+        error!("Gmail thread error: {}", msg);
+        return Err(anyhow!("Failed to get thread {}: {}", thread_id, msg));
+    }
+    let thread = resp.json::<serde_json::Value>().await?;
+
+    thread.get("messages")
+        .and_then(|m| m.as_array())
+        .into_iter()
+        .flatten()
+        .map(parse_full_message)
+        .collect()
+}
+
+/// Prefix `subject` with `prefix` ("Re:" / "Fwd:") unless it already has it
+fn prefixed_subject(prefix: &str, subject: Option<&str>) -> String {
+    let subject = subject.unwrap_or("");
+    if subject.to_lowercase().starts_with(&prefix.to_lowercase()) {
+        subject.to_string()
+    } else {
+        format!("{} {}", prefix, subject)
+    }
+}
+
+/// A reply to `original` that Gmail and other clients will thread with it
+pub fn reply_email(original: &ThreadMessage, to: String, body: String) -> OutgoingEmail {
+    let references = match (&original.references, &original.message_id) {
+        (Some(references), Some(message_id)) => Some(format!("{} {}", references, message_id)),
+        (None, Some(message_id)) => Some(message_id.clone()),
+        (references, None) => references.clone(),
+    };
+    OutgoingEmail {
+        to,
+        subject: prefixed_subject("Re:", original.subject.as_deref()),
+        body,
+        thread_id: Some(original.thread_id.clone()),
+        in_reply_to: original.message_id.clone(),
+        references,
+        ..Default::default()
+    }
+}
+
+/// `original` forwarded inline, below the optional `note`
+pub fn forward_email(original: &ThreadMessage, to: String, note: Option<&str>) -> OutgoingEmail {
+    let field = |value: &Option<String>| value.clone().unwrap_or_default();
+    let body = format!(
+        "{}---------- Forwarded message ---------\nFrom: {}\nDate: {}\nSubject: {}\nTo: {}\n\n{}",
+        note.map(|note| format!("{}\n\n", note)).unwrap_or_default(),
+        field(&original.from),
+        field(&original.date),
+        field(&original.subject),
+        field(&original.to),
+        field(&original.body)
+    );
+    OutgoingEmail {
+        to,
+        subject: prefixed_subject("Fwd:", original.subject.as_deref()),
+        body,
+        ..Default::default()
+    }
+}
+
+/// Summary of a draft, as returned by `list_drafts`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraftInfo {
+    pub id: String,
+    pub message_id: String,
+    pub subject: Option<String>,
+    pub to: Option<String>,
+    pub snippet: Option<String>,
+}
+
+/// A draft with its full message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Draft {
+    pub id: String,
+    pub message: ThreadMessage,
+}
+
+const DRAFTS_URL: &str = "https://gmail.googleapis.com/gmail/v1/users/me/drafts";
+
+/// Return the JSON body of a successful Gmail response, or its error text
+async fn gmail_json(resp: reqwest::Response, what: &str) -> Result<Value> {
+    if !resp.status().is_success() {
+        let msg = resp.text().await.unwrap_or_default();
+        error!("Gmail {} error: {}", what, msg);
+        return Err(anyhow!("Failed to {}: {}", what, msg));
+    }
+    Ok(resp.json::<Value>().await?)
+}
+
+fn draft_message(email: &OutgoingEmail) -> Result<Value> {
+    let mut message = json!({ "raw": URL_SAFE.encode(build_mime_message(email)?.as_bytes()) });
+    if let Some(thread_id) = &email.thread_id {
+        message["threadId"] = json!(thread_id);
+    }
+    Ok(message)
+}
+
+/// ---------------------------------------
+/// Save `email` as a new draft, returning its ID
+/// ---------------------------------------
+pub async fn create_gmail_draft(access_token: &str, email: &OutgoingEmail) -> Result<String> {
+    let resp = Client::new()
+        .post(DRAFTS_URL)
+        .bearer_auth(access_token)
+        .json(&json!({ "message": draft_message(email)? }))
+        .send().await?;
+    let draft = gmail_json(resp, "create draft").await?;
+    draft.get("id")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .ok_or_else(|| anyhow!("No draft ID in Gmail response"))
+}
+
+/// ---------------------------------------
+/// Replace the content of an existing draft
+/// ---------------------------------------
+pub async fn update_gmail_draft(access_token: &str, draft_id: &str, email: &OutgoingEmail) -> Result<()> {
+    let resp = Client::new()
+        .put(format!("{}/{}", DRAFTS_URL, draft_id))
+        .bearer_auth(access_token)
+        .json(&json!({ "id": draft_id, "message": draft_message(email)? }))
+        .send().await?;
+    gmail_json(resp, "update draft").await?;
+    Ok(())
+}
+
+/// ---------------------------------------
+/// Fetch a draft with its full message
+/// ---------------------------------------
+pub async fn get_gmail_draft(access_token: &str, draft_id: &str) -> Result<Draft> {
+    let resp = Client::new()
+        .get(format!("{}/{}?format=full", DRAFTS_URL, draft_id))
+        .bearer_auth(access_token)
+        .send().await?;
+    let draft = gmail_json(resp, "get draft").await?;
+    let message = draft.get("message").ok_or_else(|| anyhow!("No message in Gmail draft"))?;
+    Ok(Draft {
+        id: draft_id.to_string(),
+        message: parse_full_message(message)?,
+    })
+}
+
+/// ---------------------------------------
+/// List drafts with their subject and snippet
+/// ---------------------------------------
+pub async fn list_gmail_drafts(access_token: &str, page_size: u32) -> Result<Vec<DraftInfo>> {
+    let resp = Client::new()
+        .get(format!("{}?maxResults={}", DRAFTS_URL, page_size))
+        .bearer_auth(access_token)
+        .send().await?;
+    let list = gmail_json(resp, "list drafts").await?;
+
+    // The list only has IDs, so fetch each draft for its headers
+    let mut results = Vec::new();
+    for draft in list.get("drafts").and_then(|d| d.as_array()).into_iter().flatten() {
+        let Some(draft_id) = draft.get("id").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let draft = get_gmail_draft(access_token, draft_id).await?;
+        results.push(DraftInfo {
+            id: draft.id,
+            message_id: draft.message.id,
+            subject: draft.message.subject,
+            to: draft.message.to,
+            snippet: draft.message.snippet,
+        });
+    }
+    Ok(results)
+}
+
+/// ---------------------------------------
+/// Send a draft, returning the ID of the sent message
+/// ---------------------------------------
+pub async fn send_gmail_draft(access_token: &str, draft_id: &str) -> Result<String> {
+    let resp = Client::new()
+        .post(format!("{}/send", DRAFTS_URL))
+        .bearer_auth(access_token)
+        .json(&json!({ "id": draft_id }))
+        .send().await?;
+    let message = gmail_json(resp, "send draft").await?;
+    Ok(message.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string())
+}
+
 /// ---------------------------------------
 /// Search for messages matching `query`
 /// and return basic metadata
@@ -1058,8 +1718,7 @@ fn missing_auth_response(id: Option<Value>, msg: &str) -> JsonRpcResponse {
     success_response(
         id,
         json!(CallToolResult {
-            content: vec![ToolResponseContent {
-                type_: "text".into(),
+            content: vec![ToolResponseContent::Text {
                 text: msg.to_string(),
                 annotations: None,
             }],
@@ -0,0 +1,311 @@
+use anyhow::{anyhow, Result};
+use reqwest::header::{HeaderMap, AUTHORIZATION, COOKIE, LOCATION, PROXY_AUTHORIZATION};
+use reqwest::redirect::Policy;
+use reqwest::{Method, StatusCode};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use shared_protocol_objects::{CallToolParams, JsonRpcErrorCode, ToolInfo};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use url::{Host, Url};
+
+use crate::tool_trait::{
+    standard_error_response, standard_success_response, standard_tool_result, ExecuteFuture, Tool,
+};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const MAX_REDIRECTS: usize = 10;
+/// Longer bodies are cut off so a large download doesn't flood the conversation
+const MAX_BODY_CHARS: usize = 100_000;
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum HttpAction {
+    Get,
+    Post,
+    Put,
+    Delete,
+    Head,
+}
+
+impl From<&HttpAction> for Method {
+    fn from(action: &HttpAction) -> Self {
+        match action {
+            HttpAction::Get => Method::GET,
+            HttpAction::Post => Method::POST,
+            HttpAction::Put => Method::PUT,
+            HttpAction::Delete => Method::DELETE,
+            HttpAction::Head => Method::HEAD,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct HttpClientParams {
+    action: HttpAction,
+    url: String,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default)]
+    body: Option<Value>,
+    #[serde(default)]
+    timeout_secs: Option<u64>,
+    #[serde(default = "default_true")]
+    follow_redirects: bool,
+}
+
+pub fn http_client_tool_info() -> ToolInfo {
+    ToolInfo {
+        name: "http_client".to_string(),
+        description: Some(
+            "Make a direct HTTP request and return the status, headers and body.
+            Use scrape_url for web pages that need a browser; use this for APIs and plain fetches.
+
+            Actions: get, post, put, delete, head.
+            A string 'body' is sent as is, any other JSON value is sent as application/json.
+            The response body is returned as text and, when it parses, as JSON in 'body_json'.
+
+            Requests to localhost and private network addresses are refused.".to_string()
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["get", "post", "put", "delete", "head"],
+                    "description": "The HTTP method."
+                },
+                "url": {
+                    "type": "string",
+                    "description": "The http or https URL to request."
+                },
+                "headers": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Request headers."
+                },
+                "body": {
+                    "description": "Request body (for post, put and delete)."
+                },
+                "timeout_secs": {
+                    "type": "integer",
+                    "description": "Request timeout in seconds (default 30)."
+                },
+                "follow_redirects": {
+                    "type": "boolean",
+                    "description": "Follow redirects (default true)."
+                }
+            },
+            "required": ["action", "url"],
+            "additionalProperties": false
+        }),
+        output_schema: Some(json!({
+            "type": "object",
+            "properties": {
+                "url": { "type": "string", "description": "Final URL after redirects." },
+                "status": { "type": "integer" },
+                "headers": { "type": "object", "additionalProperties": { "type": "string" } },
+                "body_text": { "type": "string" },
+                "body_json": { "description": "The body parsed as JSON, or null." },
+                "truncated": { "type": "boolean" }
+            }
+        })),
+    }
+}
+
+fn local_requests_allowed() -> bool {
+    std::env::var("ALLOW_LOCAL_HTTP").is_ok()
+}
+
+/// Loopback, private (RFC 1918 / unique local), carrier-grade NAT (100.64.0.0/10),
+/// link-local and unspecified addresses
+fn is_local_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [first, second, ..] = v4.octets();
+            v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || (first == 100 && (second & 0xc0) == 64)
+        }
+        IpAddr::V6(v6) => {
+            let first = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+                || v6.to_ipv4_mapped().is_some_and(|v4| is_local_ip(IpAddr::V4(v4)))
+        }
+    }
+}
+
+/// Refuse URLs that point at this machine or the local network. Domain names are resolved
+/// so a public name pointing at a private address is caught too; the address that was
+/// checked is returned so the request can be pinned to it and a second lookup can't
+/// answer differently.
+async fn check_destination(url: &Url) -> Result<Option<(String, SocketAddr)>> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(anyhow!("Only http and https URLs are supported"));
+    }
+    if local_requests_allowed() {
+        return Ok(None);
+    }
+
+    let blocked = || Err(anyhow!("Requests to local addresses are blocked: {}", url));
+    match url.host() {
+        Some(Host::Ipv4(ip)) if is_local_ip(IpAddr::V4(ip)) => blocked(),
+        Some(Host::Ipv6(ip)) if is_local_ip(IpAddr::V6(ip)) => blocked(),
+        Some(Host::Domain(domain)) => {
+            let domain = domain.to_lowercase();
+            if domain == "localhost" || domain.ends_with(".localhost") {
+                return blocked();
+            }
+            let port = url.port_or_known_default().unwrap_or(80);
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((domain.as_str(), port)).await
+                .map_err(|e| anyhow!("Failed to resolve {}: {}", domain, e))?
+                .collect();
+            if addrs.iter().any(|addr| is_local_ip(addr.ip())) {
+                return blocked();
+            }
+            let addr = addrs.first().ok_or_else(|| anyhow!("No addresses found for {}", domain))?;
+            Ok(Some((domain, *addr)))
+        }
+        Some(_) => Ok(None),
+        None => Err(anyhow!("URL has no host: {}", url)),
+    }
+}
+
+fn headers_to_json(headers: &HeaderMap) -> Value {
+    let mut map = Map::new();
+    for (name, value) in headers {
+        let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+        // Repeated headers such as set-cookie are joined like a single comma-separated header
+        match map.get_mut(name.as_str()) {
+            Some(Value::String(existing)) => {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            }
+            _ => {
+                map.insert(name.as_str().to_string(), Value::String(value));
+            }
+        }
+    }
+    Value::Object(map)
+}
+
+async fn run(params: HttpClientParams) -> Result<Value> {
+    let timeout = Duration::from_secs(params.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+
+    let mut url = Url::parse(&params.url).map_err(|e| anyhow!("Invalid URL '{}': {}", params.url, e))?;
+    let mut method = Method::from(&params.action);
+    let mut headers = params.headers;
+    let mut body = params.body;
+    let mut redirects = 0;
+
+    let response = loop {
+        // Redirects are followed by hand so every hop goes through check_destination
+        let mut client = reqwest::Client::builder()
+            .redirect(Policy::none())
+            .timeout(timeout);
+        if let Some((domain, addr)) = check_destination(&url).await? {
+            client = client.resolve(&domain, addr);
+        }
+        let client = client.build()?;
+
+        let mut request = client.request(method.clone(), url.clone());
+        for (name, value) in &headers {
+            request = request.header(name, value);
+        }
+        request = match &body {
+            Some(Value::String(text)) => request.body(text.clone()),
+            Some(value) => request.json(value),
+            None => request,
+        };
+        let response = request.send().await?;
+
+        if !params.follow_redirects || !response.status().is_redirection() {
+            break response;
+        }
+        let Some(location) = response.headers().get(LOCATION).and_then(|l| l.to_str().ok()) else {
+            break response;
+        };
+        redirects += 1;
+        if redirects > MAX_REDIRECTS {
+            return Err(anyhow!("Too many redirects"));
+        }
+        let next = url.join(location)?;
+        // Credentials meant for one site aren't handed to another
+        if next.origin() != url.origin() {
+            headers.retain(|name, _| {
+                ![AUTHORIZATION, COOKIE, PROXY_AUTHORIZATION].iter().any(|h| h.as_str().eq_ignore_ascii_case(name))
+            });
+        }
+        url = next;
+        // Only 307 and 308 keep the method and body
+        if !matches!(response.status(), StatusCode::TEMPORARY_REDIRECT | StatusCode::PERMANENT_REDIRECT)
+            && method != Method::HEAD
+        {
+            method = Method::GET;
+            body = None;
+        }
+    };
+
+    let status = response.status();
+    let headers = headers_to_json(response.headers());
+    let mut body_text = response.text().await?;
+    let body_json = serde_json::from_str::<Value>(&body_text).ok();
+    let truncated = body_text.chars().count() > MAX_BODY_CHARS;
+    if truncated {
+        body_text = body_text.chars().take(MAX_BODY_CHARS).collect();
+    }
+
+    Ok(json!({
+        "url": url.as_str(),
+        "status": status.as_u16(),
+        "headers": headers,
+        "body_text": body_text,
+        "body_json": if truncated { None } else { body_json },
+        "truncated": truncated,
+    }))
+}
+
+// HTTP Client Tool Implementation
+#[derive(Debug)]
+pub struct HttpClientTool;
+
+impl Tool for HttpClientTool {
+    fn name(&self) -> &str {
+        "http_client"
+    }
+
+    fn info(&self) -> ToolInfo {
+        http_client_tool_info()
+    }
+
+    fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture {
+        Box::pin(async move {
+            let http_params: HttpClientParams = match serde_json::from_value(params.arguments) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Ok(standard_error_response(
+                        id,
+                        JsonRpcErrorCode::InvalidParams,
+                        &format!("Invalid parameters: {}", e),
+                    ));
+                }
+            };
+
+            let tool_res = match run(http_params).await {
+                Ok(value) => standard_tool_result(serde_json::to_string_pretty(&value)?, None),
+                Err(e) => standard_tool_result(format!("HTTP error: {}", e), Some(true)),
+            };
+            Ok(standard_success_response(id, json!(tool_res)))
+        })
+    }
+}
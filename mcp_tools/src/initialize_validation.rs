@@ -0,0 +1,104 @@
+use jsonschema::Validator;
+use lazy_static::lazy_static;
+use serde_json::{json, Value};
+
+lazy_static! {
+    static ref VALIDATOR: Validator = jsonschema::validator_for(&initialize_params_schema())
+        .expect("the initialize params schema is valid");
+}
+
+/// Draft-07 schema of `InitializeParams`. `protocolVersion` may be left out, the latest
+/// supported version is assumed then.
+fn initialize_params_schema() -> Value {
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "required": ["capabilities", "clientInfo"],
+        "properties": {
+            "protocolVersion": { "type": "string" },
+            "capabilities": {
+                "type": "object",
+                "properties": {
+                    "experimental": { "type": ["object", "null"] },
+                    "sampling": { "type": ["object", "null"] },
+                    "roots": {
+                        "type": ["object", "null"],
+                        "properties": {
+                            "listChanged": { "type": "boolean" },
+                            "list_changed": { "type": "boolean" }
+                        }
+                    }
+                }
+            },
+            "clientInfo": {
+                "type": "object",
+                "required": ["name", "version"],
+                "properties": {
+                    "name": { "type": "string" },
+                    "version": { "type": "string" }
+                }
+            }
+        }
+    })
+}
+
+/// Checks the params of an `initialize` request before any of them are used
+pub struct InitializeParamsValidator;
+
+impl InitializeParamsValidator {
+    /// Every way `params` doesn't match the schema, as `<path>: <message>`
+    pub fn validate(params: &Value) -> Result<(), Vec<String>> {
+        let errors: Vec<String> = VALIDATOR.iter_errors(params)
+            .map(|e| format!("{}: {}", e.instance_path, e))
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_valid_params() {
+        let params = json!({
+            "protocolVersion": "2024-11-05",
+            "capabilities": { "roots": { "listChanged": true }, "sampling": {} },
+            "clientInfo": { "name": "client", "version": "1.0" }
+        });
+        assert!(InitializeParamsValidator::validate(&params).is_ok());
+    }
+
+    #[test]
+    fn protocol_version_is_optional() {
+        let params = json!({
+            "capabilities": {},
+            "clientInfo": { "name": "client", "version": "1.0" }
+        });
+        assert!(InitializeParamsValidator::validate(&params).is_ok());
+    }
+
+    #[test]
+    fn reports_every_problem_with_its_path() {
+        let params = json!({
+            "protocolVersion": 1,
+            "capabilities": { "roots": { "listChanged": "yes" } },
+            "clientInfo": { "name": "client" }
+        });
+        let errors = InitializeParamsValidator::validate(&params).unwrap_err();
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors.iter().any(|e| e.starts_with("/protocolVersion:")));
+        assert!(errors.iter().any(|e| e.starts_with("/capabilities/roots/listChanged:")));
+        assert!(errors.iter().any(|e| e.starts_with("/clientInfo:") && e.contains("version")));
+    }
+
+    #[test]
+    fn requires_capabilities_and_client_info() {
+        let errors = InitializeParamsValidator::validate(&json!({})).unwrap_err();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+    }
+}
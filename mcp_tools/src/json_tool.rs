@@ -0,0 +1,220 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use shared_protocol_objects::{CallToolParams, JsonRpcErrorCode, ToolInfo};
+use std::path::PathBuf;
+
+use crate::filesystem_tool::{allowed_dirs_from_env, resolve_allowed_path};
+use crate::tool_trait::{
+    standard_error_response, standard_success_response, standard_tool_result, ExecuteFuture, Tool,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum JsonAction {
+    Query,
+    Read,
+    Write,
+    Merge,
+    Schema,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonParams {
+    action: JsonAction,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    data: Option<Value>,
+    #[serde(default)]
+    expression: Option<String>,
+}
+
+pub fn json_tool_info() -> ToolInfo {
+    ToolInfo {
+        name: "json".to_string(),
+        description: Some(
+            "Query and transform JSON documents, from a file ('path') or inline ('data').
+
+            Actions:
+            - query: evaluate the JMESPath 'expression', e.g. 'items[?price > `10`].name'
+            - read: return the JSON file at 'path'
+            - write: overwrite the file at 'path' with 'data'
+            - merge: deep merge 'data' into the document at 'path' and return the result; without
+              'path', 'data' must be an array of two objects, the second merged into the first
+            - schema: infer a JSON Schema from the document
+
+            Files must be inside the allowed directories of the filesystem tool.".to_string()
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["query", "read", "write", "merge", "schema"],
+                    "description": "The operation to perform."
+                },
+                "path": {
+                    "type": "string",
+                    "description": "JSON file to operate on."
+                },
+                "data": {
+                    "description": "Inline JSON document, or the value to write or merge."
+                },
+                "expression": {
+                    "type": "string",
+                    "description": "JMESPath expression (for 'query')."
+                }
+            },
+            "required": ["action"],
+            "additionalProperties": false
+        }),
+        output_schema: None,
+    }
+}
+
+/// Merge `overlay` into `base`: objects are merged key by key, anything else is replaced
+fn deep_merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                deep_merge(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Build a JSON Schema describing `value`. Array item schemas come from the first element.
+fn infer_schema(value: &Value) -> Value {
+    match value {
+        Value::Null => json!({ "type": "null" }),
+        Value::Bool(_) => json!({ "type": "boolean" }),
+        Value::Number(n) if n.is_i64() || n.is_u64() => json!({ "type": "integer" }),
+        Value::Number(_) => json!({ "type": "number" }),
+        Value::String(_) => json!({ "type": "string" }),
+        Value::Array(items) => match items.first() {
+            Some(first) => json!({ "type": "array", "items": infer_schema(first) }),
+            None => json!({ "type": "array" }),
+        },
+        Value::Object(fields) => {
+            let properties: Map<String, Value> = fields.iter()
+                .map(|(key, value)| (key.clone(), infer_schema(value)))
+                .collect();
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": fields.keys().collect::<Vec<_>>(),
+            })
+        }
+    }
+}
+
+// JSON Tool Implementation
+#[derive(Debug)]
+pub struct JsonTool {
+    allowed_dirs: Vec<PathBuf>,
+}
+
+impl JsonTool {
+    pub fn new(allowed_dirs: Vec<PathBuf>) -> Self {
+        Self { allowed_dirs }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(allowed_dirs_from_env())
+    }
+}
+
+async fn read_file(allowed_dirs: &[PathBuf], path: &str) -> Result<Value> {
+    let path = resolve_allowed_path(allowed_dirs, path)?;
+    let content = tokio::fs::read_to_string(&path).await?;
+    serde_json::from_str(&content).map_err(|e| anyhow!("Invalid JSON in {}: {}", path.display(), e))
+}
+
+/// The document to work on: the file at `path` if given, otherwise the inline `data`
+async fn document(allowed_dirs: &[PathBuf], params: &JsonParams) -> Result<Value> {
+    match (&params.path, &params.data) {
+        (Some(path), _) => read_file(allowed_dirs, path).await,
+        (None, Some(data)) => Ok(data.clone()),
+        (None, None) => Err(anyhow!("Either 'path' or 'data' is required")),
+    }
+}
+
+async fn run(allowed_dirs: &[PathBuf], params: JsonParams) -> Result<Value> {
+    match params.action {
+        JsonAction::Query => {
+            let expression = params.expression.as_deref()
+                .ok_or_else(|| anyhow!("Missing 'expression' parameter"))?;
+            let document = document(allowed_dirs, &params).await?;
+            // jmespath results are Rc-based, so evaluate without crossing an await
+            let result = jmespath::compile(expression)?.search(document)?;
+            Ok(serde_json::to_value(&*result)?)
+        }
+        JsonAction::Read => {
+            let path = params.path.as_deref().ok_or_else(|| anyhow!("Missing 'path' parameter"))?;
+            read_file(allowed_dirs, path).await
+        }
+        JsonAction::Write => {
+            let path = params.path.as_deref().ok_or_else(|| anyhow!("Missing 'path' parameter"))?;
+            let data = params.data.as_ref().ok_or_else(|| anyhow!("Missing 'data' parameter"))?;
+            let resolved = resolve_allowed_path(allowed_dirs, path)?;
+            tokio::fs::write(&resolved, serde_json::to_string_pretty(data)?).await?;
+            Ok(Value::String(format!("Wrote {}", resolved.display())))
+        }
+        JsonAction::Merge => {
+            let data = params.data.clone().ok_or_else(|| anyhow!("Missing 'data' parameter"))?;
+            let (mut base, overlay) = match &params.path {
+                Some(path) => (read_file(allowed_dirs, path).await?, data),
+                None => match data {
+                    Value::Array(mut pair) if pair.len() == 2 => {
+                        let overlay = pair.pop().unwrap_or_default();
+                        (pair.pop().unwrap_or_default(), overlay)
+                    }
+                    _ => return Err(anyhow!("Without 'path', 'data' must be an array of two objects")),
+                },
+            };
+            deep_merge(&mut base, overlay);
+            Ok(base)
+        }
+        JsonAction::Schema => {
+            let mut schema = infer_schema(&document(allowed_dirs, &params).await?);
+            schema["$schema"] = json!("https://json-schema.org/draft/2020-12/schema");
+            Ok(schema)
+        }
+    }
+}
+
+impl Tool for JsonTool {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn info(&self) -> ToolInfo {
+        json_tool_info()
+    }
+
+    fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture {
+        let allowed_dirs = self.allowed_dirs.clone();
+
+        Box::pin(async move {
+            let json_params: JsonParams = match serde_json::from_value(params.arguments) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Ok(standard_error_response(
+                        id,
+                        JsonRpcErrorCode::InvalidParams,
+                        &format!("Invalid parameters: {}", e),
+                    ));
+                }
+            };
+
+            let tool_res = match run(&allowed_dirs, json_params).await {
+                Ok(Value::String(text)) => standard_tool_result(text, None),
+                Ok(value) => standard_tool_result(serde_json::to_string_pretty(&value)?, None),
+                Err(e) => standard_tool_result(format!("JSON error: {}", e), Some(true)),
+            };
+            Ok(standard_success_response(id, json!(tool_res)))
+        })
+    }
+}
@@ -5,9 +5,22 @@ pub mod bash;
 pub mod brave_search;
 pub mod oracle_tool;
 pub mod scraping_bee;
+pub mod robots;
 pub mod gmail_integration;
 pub mod email_validator;
 pub mod long_running_task;
 pub mod aider;
 pub mod tool_trait;
+pub mod tool_middleware;
+pub mod initialize_validation;
+pub mod tool_registry;
 pub mod tool_impls;
+pub mod prompts;
+pub mod sqlite_tool;
+pub mod filesystem_tool;
+pub mod csv_tool;
+pub mod diff_tool;
+pub mod archive_tool;
+pub mod code_search_tool;
+pub mod http_client_tool;
+pub mod json_tool;
@@ -1,26 +1,37 @@
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use cron::Schedule;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::process::Stdio;
 
 use tokio::{fs, sync::Mutex};
-use tokio::process::Command;
+use tokio::process::{Child, Command};
 use futures::StreamExt;
 use tokio_util::codec::{FramedRead, LinesCodec};
-use tracing::debug;
+use tracing::{debug, error, info, warn};
+
+use crate::bash::{default_cwd, BashExecutor, BashParams};
 
 use shared_protocol_objects::{
     error_response, success_response,
     CallToolParams, CallToolResult, JsonRpcResponse,
-    ToolInfo, ToolResponseContent, INVALID_PARAMS
+    ToolInfo, ToolResponseContent, JsonRpcErrorCode
 };
 
+/// The process of a running task. `cancel` takes the child out to kill it.
+type ChildSlot = Arc<Mutex<Option<Child>>>;
+
 #[derive(Clone, Debug)]
 pub struct LongRunningTaskManager {
     pub tasks_in_memory: Arc<Mutex<HashMap<String, TaskState>>>,
     pub persistence_path: std::path::PathBuf,
+    /// Processes of the tasks started in this session, by task ID
+    children: Arc<Mutex<HashMap<String, ChildSlot>>>,
+    pub scheduler: TaskScheduler,
 }
 
 /// Each task includes the original command, partial logs, final status, and a reason.
@@ -37,14 +48,25 @@ pub struct TaskState {
     /// A new field to store *why* we created this task.
     #[serde(default)]
     pub reason: String,
+    /// Tasks that must end successfully before this one starts
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Why a `Blocked` task will never run
+    #[serde(default)]
+    pub blocked_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TaskStatus {
     Created,
+    /// Waiting for its dependencies to end
+    Pending,
+    /// A dependency failed or was cancelled
+    Blocked,
     Running,
     Ended,
     Error,
+    Cancelled,
 }
 impl Default for TaskStatus {
     fn default() -> Self {
@@ -53,17 +75,17 @@ impl Default for TaskStatus {
 }
 
 impl LongRunningTaskManager {
-    pub fn new(filename: String) -> Self {
-        let path = dirs::home_dir()
-            .expect("Could not find home directory")
-            .join(filename);
+    pub fn new(filename: String) -> Result<Self> {
+        let path = home_file(&filename)?;
 
         debug!("LongRunningTaskManager storing tasks at: {}", path.display());
 
-        Self {
+        Ok(Self {
             tasks_in_memory: Arc::new(Mutex::new(HashMap::new())),
             persistence_path: path,
-        }
+            children: Arc::new(Mutex::new(HashMap::new())),
+            scheduler: TaskScheduler::new("recurring_tasks.json".to_string())?,
+        })
     }
 
     pub async fn load_persistent_tasks(&self) -> Result<()> {
@@ -71,9 +93,21 @@ impl LongRunningTaskManager {
             return Ok(());
         }
         let data = fs::read_to_string(&self.persistence_path).await?;
-        let tasks: HashMap<String, TaskState> = serde_json::from_str(&data)?;
-        let mut guard = self.tasks_in_memory.lock().await;
-        guard.extend(tasks);
+        let mut tasks: HashMap<String, TaskState> = serde_json::from_str(&data)?;
+        // Their processes died with the previous run, or never started
+        for ts in tasks.values_mut()
+            .filter(|ts| matches!(ts.status, TaskStatus::Created | TaskStatus::Running))
+        {
+            ts.status = TaskStatus::Error;
+            ts.stderr.push_str("Interrupted by restart\n");
+        }
+        {
+            let mut guard = self.tasks_in_memory.lock().await;
+            guard.extend(tasks);
+        }
+        let _ = self.save().await;
+        // Dependencies may have ended while we were not running
+        self.schedule_pending().await;
         Ok(())
     }
 
@@ -84,42 +118,113 @@ impl LongRunningTaskManager {
         Ok(())
     }
 
-    /// Spawns a background task that reads partial stdout/stderr
-    pub async fn spawn_task(&self, command: &str, reason: &str) -> Result<String> {
+    /// Create a task. It starts right away, or once every task in `depends_on` has ended
+    /// successfully.
+    pub async fn spawn_task(&self, command: &str, reason: &str, depends_on: &[String]) -> Result<String> {
         let task_id = format!("task-{}", uuid::Uuid::new_v4());
-        let task_id_clone = task_id.clone();
-        let mut state = TaskState {
+        let state = TaskState {
             task_id: task_id.clone(),
             command: command.to_string(),
-            status: TaskStatus::Created,
+            status: if depends_on.is_empty() { TaskStatus::Created } else { TaskStatus::Pending },
             stdout: String::new(),
             stderr: String::new(),
             reason: reason.to_string(),
+            depends_on: depends_on.to_vec(),
+            blocked_reason: None,
         };
 
-        // Insert initial record in the tasks map
+        // Insert initial record in the tasks map. Dependencies must already exist, which
+        // also rules out cycles.
         {
             let mut guard = self.tasks_in_memory.lock().await;
+            if let Some(missing) = depends_on.iter().find(|dep| !guard.contains_key(*dep)) {
+                return Err(anyhow!("Unknown dependency: {}", missing));
+            }
             guard.insert(task_id.clone(), state.clone());
         }
 
+        if depends_on.is_empty() {
+            self.launch(state);
+        } else {
+            self.schedule_pending().await;
+        }
+        Ok(task_id)
+    }
+
+    /// Start or block every pending task whose dependencies have settled
+    async fn schedule_pending(&self) {
+        loop {
+            let mut to_launch = Vec::new();
+            let mut blocked_any = false;
+            {
+                let mut guard = self.tasks_in_memory.lock().await;
+                let statuses: HashMap<String, TaskStatus> = guard.iter()
+                    .map(|(id, ts)| (id.clone(), ts.status.clone()))
+                    .collect();
+
+                for ts in guard.values_mut().filter(|ts| ts.status == TaskStatus::Pending) {
+                    let mut ready = true;
+                    for dep in &ts.depends_on {
+                        let why = match statuses.get(dep) {
+                            Some(TaskStatus::Ended) => continue,
+                            Some(TaskStatus::Error) => "failed",
+                            Some(TaskStatus::Cancelled) => "was cancelled",
+                            Some(TaskStatus::Blocked) => "is blocked",
+                            None => "no longer exists",
+                            Some(_) => {
+                                ready = false;
+                                continue;
+                            }
+                        };
+                        ts.status = TaskStatus::Blocked;
+                        ts.blocked_reason = Some(format!("Dependency {} {}", dep, why));
+                        blocked_any = true;
+                        break;
+                    }
+                    if ready && ts.status == TaskStatus::Pending {
+                        ts.status = TaskStatus::Created;
+                        to_launch.push(ts.clone());
+                    }
+                }
+            }
+
+            let launched_any = !to_launch.is_empty();
+            for state in to_launch {
+                self.launch(state);
+            }
+            if launched_any || blocked_any {
+                let _ = self.save().await;
+            }
+            // A newly blocked task can block its own dependents in turn
+            if !blocked_any {
+                break;
+            }
+        }
+    }
+
+    /// Spawns a background task that runs the command and reads partial stdout/stderr
+    fn launch(&self, mut state: TaskState) {
+        let task_id = state.task_id.clone();
         let manager_clone = self.clone();
         tokio::spawn(async move {
-            // Mark as Running
-            state.status = TaskStatus::Running;
+            // Cancelled before it got here
+            if manager_clone.tasks_in_memory.lock().await
+                .get(&task_id).is_some_and(|ts| ts.status == TaskStatus::Cancelled)
             {
-                let mut guard = manager_clone.tasks_in_memory.lock().await;
-                guard.insert(task_id.clone(), state.clone());
+                return;
             }
-            let _ = manager_clone.save().await;
 
-            // Launch the process
-            let mut child = Command::new("bash")
+            // Launch the process in its own process group, so `cancel` also reaches the
+            // commands bash starts
+            let mut command = Command::new("bash");
+            command
                 .arg("-c")
                 .arg(&state.command)
                 .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn();
+                .stderr(Stdio::piped());
+            #[cfg(unix)]
+            command.process_group(0);
+            let child = command.spawn();
 
             match child {
                 Ok(mut child) => {
@@ -182,22 +287,60 @@ impl LongRunningTaskManager {
                         });
                     }
 
-                    // Wait on final exit
-                    match child.wait().await {
-                        Ok(status) => {
+                    // Hand the child to the manager so `cancel` can reach it before the task
+                    // shows as Running, then poll for the exit without holding the lock
+                    let slot: ChildSlot = Arc::new(Mutex::new(Some(child)));
+                    manager_clone.children.lock().await.insert(task_id.clone(), slot.clone());
+                    let cancelled = {
+                        let mut guard = manager_clone.tasks_in_memory.lock().await;
+                        let cancelled = guard.get(&task_id).is_some_and(|ts| ts.status == TaskStatus::Cancelled);
+                        if !cancelled {
+                            state.status = TaskStatus::Running;
+                            guard.insert(task_id.clone(), state.clone());
+                        }
+                        cancelled
+                    };
+                    if cancelled {
+                        // `cancel` found no process yet, so it is stopped here
+                        if let Some(child) = slot.lock().await.take() {
+                            if let Err(e) = kill_child(child).await {
+                                warn!("Failed to kill cancelled task {}: {}", task_id, e);
+                            }
+                        }
+                    } else {
+                        let _ = manager_clone.save().await;
+                    }
+
+                    let exit = loop {
+                        let result = match slot.lock().await.as_mut() {
+                            Some(child) => child.try_wait(),
+                            // Taken and killed by `cancel`
+                            None => break None,
+                        };
+                        match result {
+                            Ok(Some(status)) => break Some(Ok(status)),
+                            Ok(None) => tokio::time::sleep(CHILD_POLL_INTERVAL).await,
+                            Err(e) => break Some(Err(e)),
+                        }
+                    };
+                    manager_clone.children.lock().await.remove(&task_id);
+
+                    match exit {
+                        Some(Ok(status)) => {
                             if status.success() {
                                 state.status = TaskStatus::Ended;
                             } else {
                                 state.status = TaskStatus::Error;
                             }
                         }
-                        Err(e) => {
+                        Some(Err(e)) => {
                             state.stderr.push_str(&format!(
                                 "Failed waiting on command: {}\n",
                                 e
                             ));
                             state.status = TaskStatus::Error;
                         }
+                        None => state.status = TaskStatus::Cancelled,
                     }
                 }
                 Err(e) => {
@@ -212,14 +355,86 @@ impl LongRunningTaskManager {
                 if let Some(ts) = guard.get(&task_id) {
                     state.stdout = ts.stdout.clone();
                     state.stderr = ts.stderr.clone();
+                    // A cancel that raced with the exit wins
+                    if ts.status == TaskStatus::Cancelled {
+                        state.status = TaskStatus::Cancelled;
+                    }
                 }
                 // Overwrite aggregator with final state
                 guard.insert(task_id.clone(), state.clone());
             }
             let _ = manager_clone.save().await;
+
+            // This task settled, so its dependents may now run or be blocked
+            manager_clone.schedule_pending().await;
         });
+    }
 
-        Ok(task_id_clone)
+    /// The dependency DAG as an adjacency list: for each task, what it depends on and what
+    /// depends on it
+    pub async fn task_graph(&self) -> Value {
+        let guard = self.tasks_in_memory.lock().await;
+        let mut graph = serde_json::Map::new();
+        for (id, ts) in guard.iter() {
+            let mut dependents: Vec<&String> = guard.values()
+                .filter(|other| other.depends_on.contains(id))
+                .map(|other| &other.task_id)
+                .collect();
+            dependents.sort();
+            graph.insert(id.clone(), json!({
+                "status": ts.status,
+                "command": ts.command,
+                "depends_on": ts.depends_on,
+                "dependents": dependents,
+                "blocked_reason": ts.blocked_reason,
+            }));
+        }
+        Value::Object(graph)
+    }
+
+    /// Kill the process of a running task and mark it `Cancelled`
+    pub async fn cancel(&self, task_id: &str) -> Result<TaskState> {
+        let status = self.get_task_status(task_id).await?.status;
+        match status {
+            TaskStatus::Ended => return Err(anyhow!("Task {} has already completed", task_id)),
+            TaskStatus::Error => return Err(anyhow!("Task {} has already failed", task_id)),
+            TaskStatus::Cancelled => return Err(anyhow!("Task {} was already cancelled", task_id)),
+            TaskStatus::Blocked => return Err(anyhow!("Task {} is blocked and will never run", task_id)),
+            TaskStatus::Created | TaskStatus::Pending | TaskStatus::Running => {}
+        }
+
+        let slot = self.children.lock().await.get(task_id).cloned();
+        let child = match slot {
+            Some(slot) => slot.lock().await.take(),
+            None => None,
+        };
+        match child {
+            Some(child) => {
+                kill_child(child).await
+                    .map_err(|e| anyhow!("Failed to kill task {}: {}", task_id, e))?;
+            }
+            // Not spawned yet, or spawned but not handed over; the background task sees the
+            // status and stops it
+            None if matches!(status, TaskStatus::Created | TaskStatus::Pending) => {}
+            None => {
+                return Err(anyhow!(
+                    "Task {} has no process in this session (it was started by an earlier run)",
+                    task_id
+                ));
+            }
+        }
+
+        let state = {
+            let mut guard = self.tasks_in_memory.lock().await;
+            let ts = guard
+                .get_mut(task_id)
+                .ok_or_else(|| anyhow!("Task not found: {}", task_id))?;
+            ts.status = TaskStatus::Cancelled;
+            ts.clone()
+        };
+        self.save().await?;
+        self.schedule_pending().await;
+        Ok(state)
     }
 
     /// Return partial or final logs
@@ -250,6 +465,234 @@ impl LongRunningTaskManager {
     }
 }
 
+/// Kill a task's process together with the commands bash started
+async fn kill_child(mut child: Child) -> std::io::Result<()> {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // The group ID equals the pid of bash; the kill below reaps bash itself
+        unsafe {
+            libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+        }
+    }
+    child.kill().await
+}
+
+/// A command run on a cron schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurringTask {
+    pub id: String,
+    pub cron_expression: String,
+    pub command: String,
+    pub last_run: Option<DateTime<Utc>>,
+    pub next_run: DateTime<Utc>,
+}
+
+/// Runs recurring tasks. The schedule is kept in memory and persisted like the one-shot tasks.
+#[derive(Clone, Debug)]
+pub struct TaskScheduler {
+    pub tasks: Arc<Mutex<Vec<RecurringTask>>>,
+    pub persistence_path: std::path::PathBuf,
+}
+
+/// How often the scheduler looks for tasks that are due
+const SCHEDULER_TICK: std::time::Duration = std::time::Duration::from_secs(60);
+
+fn home_file(filename: &str) -> Result<std::path::PathBuf> {
+    Ok(dirs::home_dir()
+        .ok_or_else(|| anyhow!("Could not find home directory"))?
+        .join(filename))
+}
+
+/// A standard cron weekday, 0 or 7 for Sunday through 6 for Saturday, or its English name
+fn parse_weekday(day: &str) -> Option<u32> {
+    let number = match day.to_lowercase().as_str() {
+        "sun" | "sunday" => 0,
+        "mon" | "monday" => 1,
+        "tue" | "tuesday" => 2,
+        "wed" | "wednesday" => 3,
+        "thu" | "thursday" => 4,
+        "fri" | "friday" => 5,
+        "sat" | "saturday" => 6,
+        number => number.parse().ok()?,
+    };
+    (number <= 7).then_some(number)
+}
+
+/// Rewrite a standard weekday field, where Sunday is 0 or 7, for the `cron` crate, which
+/// numbers the days from 1 for Sunday to 7 for Saturday. Lists, ranges and steps are
+/// expanded to the days they cover.
+fn translate_weekdays(field: &str) -> Option<String> {
+    if field == "*" || field == "?" {
+        return Some(field.to_string());
+    }
+    let mut days = std::collections::BTreeSet::new();
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<usize>().ok().filter(|&step| step > 0)?)),
+            None => (item, None),
+        };
+        let (first, last) = match range.split_once('-') {
+            _ if range == "*" => (0, 6),
+            Some((first, last)) => (parse_weekday(first)?, parse_weekday(last)?),
+            // `5/2` means every second day from Friday on
+            None if step.is_some() => (parse_weekday(range)?, 6),
+            None => (parse_weekday(range)?, parse_weekday(range)?),
+        };
+        if first > last {
+            return None;
+        }
+        days.extend((first..=last).step_by(step.unwrap_or(1)).map(|day| day % 7 + 1));
+    }
+    Some(days.iter().map(u32::to_string).collect::<Vec<_>>().join(","))
+}
+
+/// Parse a cron expression. The usual five fields (minute hour day month weekday) are
+/// accepted with standard weekday numbers, Sunday being 0 or 7. The six or seven field
+/// form with seconds and year is passed to the `cron` crate as is, so there Sunday is 1.
+fn parse_cron(expression: &str) -> Result<Schedule> {
+    let expression = expression.trim();
+    let invalid = |reason: &dyn std::fmt::Display| anyhow!("Invalid cron expression '{}': {}", expression, reason);
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    let full = match fields.as_slice() {
+        [minute, hour, day, month, weekday] => {
+            let weekday = translate_weekdays(weekday)
+                .ok_or_else(|| invalid(&format!("invalid weekday field '{}'", weekday)))?;
+            format!("0 {} {} {} {} {}", minute, hour, day, month, weekday)
+        }
+        _ => expression.to_string(),
+    };
+    Schedule::from_str(&full).map_err(|e| invalid(&e))
+}
+
+fn next_run_after(expression: &str, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    parse_cron(expression)?
+        .after(&after)
+        .next()
+        .ok_or_else(|| anyhow!("Cron expression '{}' never fires again", expression))
+}
+
+impl TaskScheduler {
+    pub fn new(filename: String) -> Result<Self> {
+        let path = home_file(&filename)?;
+
+        debug!("TaskScheduler storing recurring tasks at: {}", path.display());
+
+        Ok(Self {
+            tasks: Arc::new(Mutex::new(Vec::new())),
+            persistence_path: path,
+        })
+    }
+
+    pub async fn load(&self) -> Result<()> {
+        if !self.persistence_path.exists() {
+            return Ok(());
+        }
+        let data = fs::read_to_string(&self.persistence_path).await?;
+        let tasks: Vec<RecurringTask> = serde_json::from_str(&data)?;
+        *self.tasks.lock().await = tasks;
+        Ok(())
+    }
+
+    pub async fn save(&self) -> Result<()> {
+        let guard = self.tasks.lock().await;
+        let json = serde_json::to_string_pretty(&*guard)?;
+        fs::write(&self.persistence_path, json).await?;
+        Ok(())
+    }
+
+    pub async fn schedule(&self, cron_expression: &str, command: &str) -> Result<RecurringTask> {
+        let task = RecurringTask {
+            id: format!("schedule-{}", uuid::Uuid::new_v4()),
+            cron_expression: cron_expression.trim().to_string(),
+            command: command.to_string(),
+            last_run: None,
+            next_run: next_run_after(cron_expression, Utc::now())?,
+        };
+        self.tasks.lock().await.push(task.clone());
+        self.save().await?;
+        Ok(task)
+    }
+
+    pub async fn unschedule(&self, id: &str) -> Result<RecurringTask> {
+        let removed = {
+            let mut guard = self.tasks.lock().await;
+            let index = guard.iter()
+                .position(|t| t.id == id)
+                .ok_or_else(|| anyhow!("Scheduled task not found"))?;
+            guard.remove(index)
+        };
+        self.save().await?;
+        Ok(removed)
+    }
+
+    pub async fn list(&self) -> Vec<RecurringTask> {
+        self.tasks.lock().await.clone()
+    }
+
+    /// Start the background loop that runs due tasks once a minute
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SCHEDULER_TICK);
+            loop {
+                interval.tick().await;
+                scheduler.run_due().await;
+            }
+        })
+    }
+
+    /// Spawn every task whose `next_run` has passed. Runs missed while the server was down
+    /// are not caught up; the task just runs once and moves on to its next time.
+    async fn run_due(&self) {
+        let now = Utc::now();
+        let due: Vec<RecurringTask> = {
+            let mut guard = self.tasks.lock().await;
+            guard.iter_mut()
+                .filter(|t| t.next_run <= now)
+                .filter_map(|t| {
+                    t.last_run = Some(now);
+                    match next_run_after(&t.cron_expression, now) {
+                        Ok(next) => t.next_run = next,
+                        Err(e) => {
+                            warn!("Not rescheduling {}: {}", t.id, e);
+                            return None;
+                        }
+                    }
+                    Some(t.clone())
+                })
+                .collect()
+        };
+        if due.is_empty() {
+            return;
+        }
+        if let Err(e) = self.save().await {
+            error!("Failed to save recurring tasks: {}", e);
+        }
+
+        for task in due {
+            info!("Running recurring task {}: {}", task.id, task.command);
+            tokio::spawn(async move {
+                let params = BashParams {
+                    command: task.command.clone(),
+                    cwd: default_cwd(),
+                    stream: false,
+                };
+                match BashExecutor::new().execute(params).await {
+                    Ok(result) if result.success => info!("Recurring task {} finished", task.id),
+                    Ok(result) => warn!(
+                        "Recurring task {} exited with status {}: {}",
+                        task.id, result.status, result.stderr
+                    ),
+                    Err(e) => error!("Recurring task {} failed to run: {}", task.id, e),
+                }
+            });
+        }
+    }
+}
+
+/// How often a task's process is checked for exit
+const CHILD_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
 /// A helper function to retrieve the last `n` lines from a string.
 fn last_n_lines(s: &str, n: usize) -> String {
     let lines: Vec<&str> = s.lines().collect();
@@ -270,6 +713,9 @@ pub fn long_running_tool_info() -> ToolInfo {
             2. **Status monitoring** with `get_status`: Check if tasks are still running and view their real-time output
             3. **Output inspection** with `get_status`: Review both standard output and error streams from running or completed tasks
             4. **Task organization** with `list_tasks`: View all active and completed tasks with filtering options
+            5. **Cancellation** with `cancel_task`: Kill the process of a running task
+            6. **Dependencies**: pass `dependsOn` to `start_task` to run a task only after others end successfully; if one fails the task is blocked. `get_task_graph` shows the dependency graph
+            7. **Recurring tasks** with `schedule`, `unschedule` and `list_scheduled`: Run a command on a cron schedule (`cronExpression`, e.g. `0 3 * * *` for every day at 03:00 UTC)
             
             Key benefits:
             - Runs asynchronously in the background, independent of API timeouts
@@ -291,7 +737,7 @@ pub fn long_running_tool_info() -> ToolInfo {
             "properties": {
                 "command": {
                     "type": "string",
-                    "enum": ["start_task", "get_status", "list_tasks"],
+                    "enum": ["start_task", "get_status", "list_tasks", "cancel_task", "get_task_graph", "schedule", "unschedule", "list_scheduled"],
                     "description": "The command to run against the long-running tool."
                 },
                 "commandString": {
@@ -300,15 +746,24 @@ pub fn long_running_tool_info() -> ToolInfo {
                 },
                 "taskId": {
                     "type": "string",
-                    "description": "The ID of the task to retrieve status for."
+                    "description": "The ID of the task to retrieve status for or cancel, or of the scheduled task to `unschedule`."
+                },
+                "cronExpression": {
+                    "type": "string",
+                    "description": "When to run the `commandString` given to `schedule`: minute hour day-of-month month day-of-week, in UTC."
                 },
                 "reason": {
                     "type": "string",
                     "description": "A human-friendly reason or rationale for creating this task."
                 },
+                "dependsOn": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "IDs of tasks that must end successfully before this task starts (for `start_task`)."
+                },
                 "status": {
                     "type": "string",
-                    "description": "Optional filter for `list_tasks` (created, running, ended, error)."
+                    "description": "Optional filter for `list_tasks` (created, pending, blocked, running, ended, error, cancelled)."
                 },
                 "lines": {
                     "type": "integer",
@@ -317,6 +772,7 @@ pub fn long_running_tool_info() -> ToolInfo {
             },
             "required": ["command"]
         }),
+        output_schema: None,
     }
 }
 
@@ -344,13 +800,19 @@ pub async fn handle_long_running_tool_call(
                 .and_then(Value::as_str)
                 .unwrap_or("No reason given");
 
-            let task_id = manager.spawn_task(command_string, reason).await?;
+            let depends_on: Vec<String> = match params.arguments.get("dependsOn") {
+                Some(deps) => serde_json::from_value(deps.clone())
+                    .map_err(|_| anyhow!("'dependsOn' must be an array of task IDs"))?,
+                None => Vec::new(),
+            };
+
+            let task_id = manager.spawn_task(command_string, reason, &depends_on).await?;
 
             let tool_res = CallToolResult {
-                content: vec![ToolResponseContent {
-                    type_: "text".into(),
+                content: vec![ToolResponseContent::Text {
                     text: format!(
-                        "Task started with id: {}\nReason: {}",
+                        "Task {} with id: {}\nReason: {}",
+                        if depends_on.is_empty() { "started" } else { "queued" },
                         task_id, reason
                     ),
                     annotations: Some(HashMap::from([
@@ -385,12 +847,12 @@ pub async fn handle_long_running_tool_call(
             let stderr_short = last_n_lines(&state.stderr, lines_to_return);
 
             let tool_res = CallToolResult {
-                content: vec![ToolResponseContent {
-                    type_: "text".into(),
+                content: vec![ToolResponseContent::Text {
                     text: format!(
-                        "Task ID: {}\nStatus: {:?}\nReason: {}\nCommand: {}\n\n(Showing last {} lines) STDOUT:\n{}\n\n(Showing last {} lines) STDERR:\n{}",
+                        "Task ID: {}\nStatus: {:?}{}\nReason: {}\nCommand: {}\n\n(Showing last {} lines) STDOUT:\n{}\n\n(Showing last {} lines) STDERR:\n{}",
                         task_id,
                         state.status,
+                        state.blocked_reason.as_ref().map(|r| format!(" ({})", r)).unwrap_or_default(),
                         state.reason,
                         state.command,
                         lines_to_return,
@@ -415,9 +877,12 @@ pub async fn handle_long_running_tool_call(
             // Convert status_str => Option<TaskStatus>
             let filter_status = match status_str {
                 Some("created") => Some(TaskStatus::Created),
+                Some("pending") => Some(TaskStatus::Pending),
+                Some("blocked") => Some(TaskStatus::Blocked),
                 Some("running") => Some(TaskStatus::Running),
                 Some("ended") => Some(TaskStatus::Ended),
                 Some("error") => Some(TaskStatus::Error),
+                Some("cancelled") => Some(TaskStatus::Cancelled),
                 None => None,        // no filter => all tasks
                 _ => None,           // unrecognized => return all
             };
@@ -436,8 +901,7 @@ pub async fn handle_long_running_tool_call(
             }).collect();
 
             let tool_res = CallToolResult {
-                content: vec![ToolResponseContent {
-                    type_: "text".to_string(),
+                content: vec![ToolResponseContent::Text {
                     text: serde_json::to_string_pretty(&tasks_json)
                         .unwrap_or("[]".to_string()),
                     annotations: None,
@@ -449,9 +913,158 @@ pub async fn handle_long_running_tool_call(
             };
             Ok(success_response(id, serde_json::to_value(tool_res)?))
         }
+        "cancel_task" => {
+            let task_id = params.arguments
+                .get("taskId")
+                .and_then(Value::as_str)
+                .ok_or_else(|| anyhow!("Missing 'taskId'"))?;
+
+            let (text, is_error) = match manager.cancel(task_id).await {
+                Ok(state) => (format!("Task {} cancelled.\nCommand: {}", task_id, state.command), false),
+                Err(e) => (e.to_string(), true),
+            };
+
+            let tool_res = CallToolResult {
+                content: vec![ToolResponseContent::Text {
+                    text,
+                    annotations: None,
+                }],
+                is_error: Some(is_error),
+                _meta: None,
+                progress: None,
+                total: None,
+            };
+            Ok(success_response(id, serde_json::to_value(tool_res)?))
+        }
+        "get_task_graph" => {
+            let graph = manager.task_graph().await;
+            let tool_res = CallToolResult {
+                content: vec![ToolResponseContent::Text {
+                    text: serde_json::to_string_pretty(&graph)?,
+                    annotations: None,
+                }],
+                is_error: Some(false),
+                _meta: None,
+                progress: None,
+                total: None,
+            };
+            Ok(success_response(id, serde_json::to_value(tool_res)?))
+        }
+        "schedule" => {
+            let cron_expression = params.arguments
+                .get("cronExpression")
+                .and_then(Value::as_str)
+                .ok_or_else(|| anyhow!("Missing 'cronExpression'"))?;
+            let command_string = params.arguments
+                .get("commandString")
+                .and_then(Value::as_str)
+                .ok_or_else(|| anyhow!("Missing 'commandString'"))?;
+
+            let (text, is_error) = match manager.scheduler.schedule(cron_expression, command_string).await {
+                Ok(task) => (format!(
+                    "Scheduled with id: {}\nCommand: {}\nNext run: {}",
+                    task.id, task.command, task.next_run
+                ), false),
+                Err(e) => (e.to_string(), true),
+            };
+
+            let tool_res = CallToolResult {
+                content: vec![ToolResponseContent::Text {
+                    text,
+                    annotations: None,
+                }],
+                is_error: Some(is_error),
+                _meta: None,
+                progress: None,
+                total: None,
+            };
+            Ok(success_response(id, serde_json::to_value(tool_res)?))
+        }
+        "unschedule" => {
+            let schedule_id = params.arguments
+                .get("taskId")
+                .and_then(Value::as_str)
+                .ok_or_else(|| anyhow!("Missing 'taskId'"))?;
+
+            let (text, is_error) = match manager.scheduler.unschedule(schedule_id).await {
+                Ok(task) => (format!("Unscheduled {}.\nCommand: {}", task.id, task.command), false),
+                Err(e) => (e.to_string(), true),
+            };
+
+            let tool_res = CallToolResult {
+                content: vec![ToolResponseContent::Text {
+                    text,
+                    annotations: None,
+                }],
+                is_error: Some(is_error),
+                _meta: None,
+                progress: None,
+                total: None,
+            };
+            Ok(success_response(id, serde_json::to_value(tool_res)?))
+        }
+        "list_scheduled" => {
+            let tasks = manager.scheduler.list().await;
+            let tool_res = CallToolResult {
+                content: vec![ToolResponseContent::Text {
+                    text: serde_json::to_string_pretty(&tasks)?,
+                    annotations: None,
+                }],
+                is_error: Some(false),
+                _meta: None,
+                progress: None,
+                total: None,
+            };
+            Ok(success_response(id, serde_json::to_value(tool_res)?))
+        }
         _ => {
-            let msg = format!("Invalid command '{}'. Use start_task, get_status, or list_tasks", command);
-            Ok(error_response(id, INVALID_PARAMS, &msg))
+            let msg = format!("Invalid command '{}'. Use start_task, get_status, list_tasks, cancel_task, get_task_graph, schedule, unschedule, or list_scheduled", command);
+            Ok(error_response(id, JsonRpcErrorCode::InvalidParams, &msg))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, TimeZone, Weekday};
+
+    fn next_weekday(expression: &str) -> Weekday {
+        // A Wednesday
+        let after = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        next_run_after(expression, after).unwrap().weekday()
+    }
+
+    #[test]
+    fn weekday_numbers_follow_standard_cron() {
+        assert_eq!(next_weekday("0 9 * * 0"), Weekday::Sun);
+        assert_eq!(next_weekday("0 9 * * 7"), Weekday::Sun);
+        assert_eq!(next_weekday("0 9 * * 1"), Weekday::Mon);
+        assert_eq!(next_weekday("0 9 * * 6"), Weekday::Sat);
+    }
+
+    #[test]
+    fn weekday_names_ranges_and_steps() {
+        assert_eq!(translate_weekdays("mon-fri").as_deref(), Some("2,3,4,5,6"));
+        assert_eq!(translate_weekdays("1-5").as_deref(), Some("2,3,4,5,6"));
+        assert_eq!(translate_weekdays("5-7").as_deref(), Some("1,6,7"));
+        assert_eq!(translate_weekdays("0,Sat").as_deref(), Some("1,7"));
+        assert_eq!(translate_weekdays("*/2").as_deref(), Some("1,3,5,7"));
+        assert_eq!(translate_weekdays("*").as_deref(), Some("*"));
+        assert_eq!(next_weekday("0 9 * * sun"), Weekday::Sun);
+    }
+
+    #[test]
+    fn invalid_weekdays_are_rejected() {
+        assert!(translate_weekdays("8").is_none());
+        assert!(translate_weekdays("5-1").is_none());
+        assert!(translate_weekdays("*/0").is_none());
+        assert!(parse_cron("0 9 * * funday").is_err());
+    }
+
+    #[test]
+    fn six_field_form_is_passed_through() {
+        // The `cron` crate's own numbering, where 1 is Sunday
+        assert_eq!(next_weekday("0 0 9 * * 1"), Weekday::Sun);
+    }
+}
@@ -5,28 +5,33 @@ use mcp_tools::aider::{handle_aider_tool_call, AiderParams};
 use mcp_tools::bash::{handle_quick_bash, BashExecutor, BashParams, QuickBashParams};
 use mcp_tools::brave_search::BraveSearchClient;
 use mcp_tools::email_validator::handle_neverbounce_tool_call;
+use mcp_tools::filesystem_tool::roots_from_env;
 use mcp_tools::git_integration::handle_git_tool_call;
 use mcp_tools::gmail_integration::handle_gmail_tool_call;
+use mcp_tools::initialize_validation::InitializeParamsValidator;
 use mcp_tools::long_running_task::{handle_long_running_tool_call, LongRunningTaskManager};
 use mcp_tools::oracle_tool::handle_oracle_select_tool_call;
 use mcp_tools::process_html::extract_text_from_html;
+use mcp_tools::prompts::{default_prompts, render_prompt};
 use mcp_tools::regex_replace::handle_regex_replace_tool_call;
 use mcp_tools::scraping_bee::{ScrapingBeeClient, ScrapingBeeResponse};
 use mcp_tools::tool_impls::{create_tools, LongRunningTaskTool};
-use mcp_tools::tool_trait::{Tool, standard_error_response};
+use mcp_tools::tool_middleware::MetricsMiddleware;
+use mcp_tools::tool_registry::ToolRegistry;
+use mcp_tools::tool_trait::standard_error_response;
 use serde_json::{json, Value};
 use shared_protocol_objects::{
-    create_notification, error_response, success_response, CallToolParams, CallToolResult, 
-    ClientCapabilities, Implementation, InitializeResult, JsonRpcRequest, JsonRpcResponse, 
-    ListResourcesResult, ListToolsResult, PromptsCapability, ReadResourceParams, ReadResourceResult, 
-    ResourceContent, ResourceInfo, ResourcesCapability, ServerCapabilities, ToolInfo, 
-    ToolResponseContent, ToolsCapability, INTERNAL_ERROR, INVALID_PARAMS, LATEST_PROTOCOL_VERSION, 
-    PARSE_ERROR, SUPPORTED_PROTOCOL_VERSIONS,
+    error_response, notification_response, success_response, CallToolParams, CallToolResult, 
+    ClientCapabilities, GetPromptParams, Implementation, InitializeResult, JsonRpcErrorCode, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse,
+    ListPromptsResult, ListResourceTemplatesResult, ListRootsResult, ListResourcesResult, ListToolsResult, PromptDefinition, PromptsCapability, ReadResourceParams, ReadResourceResult, RequestId,
+    ResourceContent, ResourceInfo, ResourceTemplate, ResourceUpdateNotification, ResourcesCapability, RootsCapability, ServerCapabilities, SubscribeResourceParams, WriteResourceParams,
+    ToolResponseContent, ToolsCapability, LATEST_PROTOCOL_VERSION, SUPPORTED_PROTOCOL_VERSIONS,
 };
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::io::{stdout, AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::sync::{mpsc, Mutex};
+use tokio::task::{AbortHandle, JoinHandle};
 use tokio::{io, task};
 use tokio_stream::wrappers::LinesStream;
 use tracing::{debug, error, info, warn, Level};
@@ -64,14 +69,26 @@ async fn main() {
     info!("Starting MCP server...");
 
     // Create a new manager with a persistence filename
-    let my_manager = LongRunningTaskManager::new("tasks.json".to_string());
+    let my_manager = match LongRunningTaskManager::new("tasks.json".to_string()) {
+        Ok(manager) => manager,
+        Err(err) => {
+            error!("Failed to set up the task manager: {}", err);
+            return;
+        }
+    };
     // If you like, load any persisted tasks now
     if let Err(err) = my_manager.load_persistent_tasks().await {
         error!("Failed to load tasks: {}", err);
     }
+    if let Err(err) = my_manager.scheduler.load().await {
+        error!("Failed to load recurring tasks: {}", err);
+    }
+    my_manager.scheduler.start();
+
+    let (tx_out, mut rx_out) = mpsc::unbounded_channel::<JsonRpcResponse>();
 
     // Create tool implementations
-    let mut tool_impls = match create_tools().await {
+    let mut tool_impls = match create_tools(tx_out.clone()).await {
         Ok(tools) => tools,
         Err(e) => {
             error!("Failed to create tools: {}", e);
@@ -82,25 +99,56 @@ async fn main() {
     // Add LongRunningTaskTool which needs the manager
     let manager_arc = Arc::new(Mutex::new(my_manager.clone()));
     tool_impls.push(Box::new(LongRunningTaskTool::new(manager_arc)));
-    
-    // Extract tool info for registration
-    let tool_infos = tool_impls.iter().map(|t| t.info()).collect();
+
+    // Every call is validated against its input schema, logged and counted; `tools/stats`
+    // reports the counts
+    let tool_stats = MetricsMiddleware::new();
+    let tools = ToolRegistry::new(tool_impls, &tool_stats, tx_out.clone());
+    // The reader loop uses it to point malformed requests at the right tool
+    let tool_registry = tools.clone();
+
+    let mut resource_templates = Vec::new();
+    if tools.contains("scrape_url") {
+        resource_templates.push(TemplateHandler {
+            template: ResourceTemplate {
+                uri_template: "web://{url}".into(),
+                name: "Web page".into(),
+                description: Some("Text content of a web page fetched through ScrapingBee".into()),
+                mime_type: Some("text/plain".into()),
+            },
+            tool: "scrape_url".into(),
+        });
+    }
+
+    // Shared with the reader loop so requests can be registered without locking the whole state
+    let in_flight: InFlightRequests = Arc::new(Mutex::new(HashMap::new()));
 
     let state = Arc::new(Mutex::new(MCPServerState {
         resources: vec![], // No sample resources
-        tools: tool_infos,
-        tool_impls,
+        resource_templates,
+        prompts: default_prompts(),
+        tools,
         client_capabilities: None,
         client_info: None,
         long_running_manager: my_manager,
+        tool_stats,
+        in_flight: Arc::clone(&in_flight),
+        subscriptions: HashMap::new(),
+        written_resources: HashMap::new(),
+        started_at: std::time::Instant::now(),
     }));
 
-    let (tx_out, mut rx_out) = mpsc::unbounded_channel::<JsonRpcResponse>();
+    // Batch responses must be written as a single JSON array, so they get their own channel
+    let (tx_batch, mut rx_batch) = mpsc::unbounded_channel::<Vec<JsonRpcResponse>>();
 
     let printer_handle = tokio::spawn(async move {
         let mut out = stdout();
-        while let Some(resp) = rx_out.recv().await {
-            let serialized = serde_json::to_string(&resp).unwrap();
+        loop {
+            let serialized = tokio::select! {
+                Some(resp) = rx_out.recv() => serde_json::to_string(&resp).unwrap(),
+                Some(batch) = rx_batch.recv() => serde_json::to_string(&batch).unwrap(),
+                else => break,
+            };
             debug!("Sending response: {}", serialized);
             let _ = out.write_all(serialized.as_bytes()).await;
             let _ = out.write_all(b"\n").await;
@@ -119,18 +167,29 @@ async fn main() {
         }
 
         debug!("Received input: {}", line);
-        let parsed: Result<JsonRpcRequest, _> = serde_json::from_str(&line);
-        let req = match parsed {
-            Ok(req) => {
-                debug!("Parsed request: {:?}", req);
-                req
+        let parsed: Result<JsonRpcMessage, _> = serde_json::from_str(&line);
+        let message = match parsed {
+            Ok(message) => {
+                debug!("Parsed message: {:?}", message);
+                message
             }
             Err(e) => {
                 error!("Failed to parse request: {}", e);
                 // Try parsing as raw JSON first
                 if let Ok(raw_json) = serde_json::from_str::<Value>(&line) {
+                    // A malformed batch gets a single error response for the whole array
+                    if raw_json.is_array() {
+                        let resp = error_response(
+                            Some(Value::Null),
+                            JsonRpcErrorCode::InvalidRequest,
+                            &format!("Invalid batch request: {}", e),
+                        );
+                        let _ = tx_out.send(resp);
+                        continue;
+                    }
+
                     // Check if this looks like an attempted tool call
-                    if let Some(intended_tool) = detect_intended_tool_call(&raw_json) {
+                    if let Some(intended_tool) = tool_registry.detect_intended_tool(&raw_json) {
                         let error_msg = format!(
                             "It looks like you were trying to use the '{}' tool, but the request wasn't properly formatted.\n\
                             Tool calls must use this format:\n\
@@ -149,7 +208,7 @@ async fn main() {
                         );
                         let resp = error_response(
                             Some(Value::Number((1).into())),
-                            PARSE_ERROR,
+                            JsonRpcErrorCode::ParseError,
                             &error_msg,
                         );
                         let _ = tx_out.send(resp);
@@ -157,7 +216,7 @@ async fn main() {
                     }
                 }
                 let resp =
-                    error_response(Some(Value::Number((1).into())), PARSE_ERROR, "Parse error");
+                    error_response(Some(Value::Number((1).into())), JsonRpcErrorCode::ParseError, "Parse error");
                 let _ = tx_out.send(resp);
                 continue;
             }
@@ -166,40 +225,189 @@ async fn main() {
         let state = Arc::clone(&state);
         let tx_out_clone = tx_out.clone();
 
-        task::spawn(async move {
-            debug!("Handling request: {:?}", req);
-            let resp = handle_request(req, &state, tx_out_clone.clone()).await;
-            if let Some(resp) = resp {
-                debug!("Got response: {:?}", resp);
-                let _ = tx_out_clone.send(resp);
-            } else {
-                warn!("No response generated for request");
+        match message {
+            JsonRpcMessage::Single(req) => {
+                debug!("Handling request: {:?}", req);
+                let handle = spawn_request(req, state, Arc::clone(&in_flight), tx_out_clone.clone()).await;
+                task::spawn(async move {
+                    match handle.await {
+                        Ok(Some(resp)) => {
+                            debug!("Got response: {:?}", resp);
+                            let _ = tx_out_clone.send(resp);
+                        }
+                        Ok(None) => warn!("No response generated for request"),
+                        Err(e) if e.is_cancelled() => info!("Request was cancelled by the client"),
+                        Err(e) => error!("Request task failed: {}", e),
+                    }
+                });
             }
-        });
+            JsonRpcMessage::Batch(reqs) => {
+                if reqs.is_empty() {
+                    let resp = error_response(Some(Value::Null), JsonRpcErrorCode::InvalidRequest, "Empty batch request");
+                    let _ = tx_out.send(resp);
+                    continue;
+                }
+
+                debug!("Handling batch of {} requests", reqs.len());
+                let mut handles = Vec::with_capacity(reqs.len());
+                for req in reqs {
+                    handles.push(
+                        spawn_request(req, Arc::clone(&state), Arc::clone(&in_flight), tx_out_clone.clone()).await,
+                    );
+                }
+
+                let tx_batch_clone = tx_batch.clone();
+                task::spawn(async move {
+                    let mut responses = Vec::new();
+                    for handle in handles {
+                        match handle.await {
+                            Ok(Some(resp)) => responses.push(resp),
+                            Ok(None) => {}
+                            Err(e) if e.is_cancelled() => info!("Batch request was cancelled by the client"),
+                            Err(e) => error!("Batch request task failed: {}", e),
+                        }
+                    }
+
+                    // A batch made up only of notifications produces no output at all
+                    if responses.is_empty() {
+                        warn!("No responses generated for batch");
+                    } else {
+                        debug!("Got {} batch responses", responses.len());
+                        let _ = tx_batch_clone.send(responses);
+                    }
+                });
+            }
+        }
     }
 
     drop(tx_out);
+    drop(tx_batch);
+    // Tools and subscriptions hold their own senders; release them so the printer can finish
+    drop(state);
     let _ = printer_handle.await;
 }
 
 #[derive(Debug)]
 struct MCPServerState {
     resources: Vec<ResourceInfo>,
-    tools: Vec<ToolInfo>,
-    tool_impls: Vec<Box<dyn Tool>>,
+    resource_templates: Vec<TemplateHandler>,
+    prompts: Vec<PromptDefinition>,
+    tools: ToolRegistry,
     client_capabilities: Option<ClientCapabilities>,
     client_info: Option<Implementation>,
     long_running_manager: LongRunningTaskManager,
+    tool_stats: MetricsMiddleware,
+    in_flight: InFlightRequests,
+    /// Clients subscribed to `notifications/resources/updated`, keyed by resource URI
+    subscriptions: HashMap<String, Vec<mpsc::UnboundedSender<JsonRpcResponse>>>,
+    /// Contents stored through `resources/write`, keyed by URI
+    written_resources: HashMap<String, ResourceContent>,
+    started_at: std::time::Instant,
 }
 
-// Helper function to create standardized error responses
-fn create_error_response(id: Option<Value>, code: i64, message: &str) -> JsonRpcResponse {
-    error_response(id, code, message)
+/// A resource template and the tool that produces its contents. Reading a matching URI
+/// calls the tool with the template variables as arguments.
+#[derive(Debug, Clone)]
+struct TemplateHandler {
+    template: ResourceTemplate,
+    tool: String,
 }
 
-// Helper function to create standardized success responses
-fn create_success_response(id: Option<Value>, result: Value) -> JsonRpcResponse {
-    success_response(id, result)
+/// Turn the result of a template's tool call into a `resources/read` response
+fn read_templated_resource(
+    id: Option<Value>,
+    uri: &str,
+    template: &ResourceTemplate,
+    call: anyhow::Result<JsonRpcResponse>,
+) -> JsonRpcResponse {
+    let response = match call {
+        Ok(response) => response,
+        Err(e) => return error_response(id, JsonRpcErrorCode::InternalError, &e.to_string()),
+    };
+    if let Some(error) = response.error {
+        return error_response(id, error.code, &error.message);
+    }
+    let tool_result: CallToolResult = match serde_json::from_value(response.result.unwrap_or_default()) {
+        Ok(result) => result,
+        Err(e) => return error_response(id, JsonRpcErrorCode::InternalError, &format!("Invalid tool result: {}", e)),
+    };
+    if tool_result.is_error == Some(true) {
+        let message = tool_result.content.iter().find_map(|content| match content {
+            ToolResponseContent::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        });
+        return error_response(id, JsonRpcErrorCode::InternalError, message.unwrap_or("Tool call failed"));
+    }
+
+    let contents = tool_result.content.into_iter().map(|content| match content {
+        ToolResponseContent::Text { text, .. } => ResourceContent {
+            uri: uri.to_string(),
+            mime_type: template.mime_type.clone(),
+            text: Some(text),
+            blob: None,
+        },
+        ToolResponseContent::Image { data, mime_type, .. } => ResourceContent {
+            uri: uri.to_string(),
+            mime_type: Some(mime_type),
+            text: None,
+            blob: Some(data),
+        },
+    }).collect();
+
+    success_response(id, json!(ReadResourceResult { contents, _meta: None }))
+}
+
+/// Push a `notifications/resources/updated` notification to every subscriber of `uri`,
+/// dropping subscribers whose channel has closed.
+async fn notify_resource_updated(state: &Arc<Mutex<MCPServerState>>, uri: &str) {
+    let message = notification_response(
+        "notifications/resources/updated",
+        serde_json::to_value(ResourceUpdateNotification { uri: uri.to_string() }).ok(),
+    );
+
+    let mut guard = state.lock().await;
+    if let Some(subscribers) = guard.subscriptions.get_mut(uri) {
+        debug!("Notifying {} subscribers that {} was updated", subscribers.len(), uri);
+        subscribers.retain(|tx| tx.send(message.clone()).is_ok());
+    }
+}
+
+/// Tell the client that the resource list changed, as it does when a write creates a resource
+fn notify_resources_list_changed(tx_out: &mpsc::UnboundedSender<JsonRpcResponse>) {
+    let _ = tx_out.send(notification_response("notifications/resources/list_changed", Some(json!({}))));
+}
+
+/// Abort handles for requests that are still being processed, keyed by request id
+type InFlightRequests = Arc<Mutex<HashMap<RequestId, AbortHandle>>>;
+
+/// Spawn `handle_request` on its own task, registering the task's abort handle
+/// under the request id so a later `notifications/cancelled` can stop it.
+async fn spawn_request(
+    req: JsonRpcRequest,
+    state: Arc<Mutex<MCPServerState>>,
+    in_flight: InFlightRequests,
+    tx_out: mpsc::UnboundedSender<JsonRpcResponse>,
+) -> JoinHandle<Option<JsonRpcResponse>> {
+    let id = RequestId::from(req.id.clone());
+
+    // Hold the registry lock until the handle is inserted so a fast request
+    // can't try to deregister itself before it was registered
+    let mut guard = in_flight.lock().await;
+    let registry = Arc::clone(&in_flight);
+    let task_id = id.clone();
+    let handle = task::spawn(async move {
+        let resp = handle_request(req, &state, tx_out).await;
+        if task_id != RequestId::Null {
+            registry.lock().await.remove(&task_id);
+        }
+        resp
+    });
+
+    // Notifications have no id and can't be cancelled
+    if id != RequestId::Null {
+        guard.insert(id, handle.abort_handle());
+    }
+    handle
 }
 
 // This function is not needed as we're using the Tool trait implementations directly
@@ -212,21 +420,75 @@ async fn handle_request(
     state: &Arc<Mutex<MCPServerState>>,
     tx_out: mpsc::UnboundedSender<JsonRpcResponse>,
 ) -> Option<JsonRpcResponse> {
-    // Keep the id (even if null) to ensure proper JSON-RPC format
-    let id = Some(req.id.clone());
+    // Notifications have no id
+    let id = (!req.id.is_null()).then(|| req.id.clone());
 
     match req.method.as_str() {
+        "notifications/cancelled" => {
+            let request_id = RequestId::from(
+                req.params
+                    .as_ref()
+                    .and_then(|p| p.get("requestId"))
+                    .cloned()
+                    .unwrap_or(Value::Null),
+            );
+
+            let in_flight = {
+                let guard = state.lock().await;
+                Arc::clone(&guard.in_flight)
+            };
+            match in_flight.lock().await.remove(&request_id) {
+                Some(handle) => {
+                    info!("Cancelling request {}", request_id);
+                    handle.abort();
+                }
+                None => debug!("Cancellation for unknown or finished request {}", request_id),
+            }
+
+            // Notifications never get a response
+            None
+        }
+
         "prompts/list" => {
-            // Return an empty list of prompts
-            let result = json!({
-                "prompts": []
-            });
-            return Some(success_response(id, result));
+            let guard = state.lock().await;
+            let result = ListPromptsResult {
+                prompts: guard.prompts.clone(),
+                _meta: None,
+            };
+            return Some(success_response(id, json!(result)));
         }
 
         "prompts/get" => {
-            // Always return "prompt not found" error
-            return Some(error_response(id, -32601, "Prompt not found"));
+            let params: GetPromptParams = match serde_json::from_value(req.params.unwrap_or(Value::Null)) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Some(error_response(
+                        id,
+                        JsonRpcErrorCode::InvalidParams,
+                        &format!("Invalid params: {}", e),
+                    ));
+                }
+            };
+
+            let guard = state.lock().await;
+            let Some(prompt) = guard.prompts.iter().find(|p| p.name == params.name) else {
+                return Some(error_response(id, JsonRpcErrorCode::InvalidParams, "Prompt not found"));
+            };
+            return match render_prompt(prompt, &params.arguments) {
+                Ok(result) => Some(success_response(id, json!(result))),
+                Err(e) => Some(error_response(id, JsonRpcErrorCode::InvalidParams, &e.to_string())),
+            };
+        }
+
+        // Not part of MCP: a liveness check that works before `initialize`
+        "health" => {
+            let guard = state.lock().await;
+            Some(success_response(id, json!({
+                "status": "ok",
+                "uptime_secs": guard.started_at.elapsed().as_secs(),
+                "tools_count": guard.tools.infos().len(),
+                "version": env!("CARGO_PKG_VERSION"),
+            })))
         }
 
         "initialize" => {
@@ -234,13 +496,25 @@ async fn handle_request(
                 Some(p) => p,
                 None => {
                     return Some(error_response(
-                        Some(id.unwrap_or(Value::Number((1).into()))),
-                        INVALID_PARAMS,
+                        id,
+                        JsonRpcErrorCode::InvalidParams,
                         "Missing params",
                     ));
                 }
             };
 
+            if let Err(errors) = InitializeParamsValidator::validate(&params) {
+                let mut response = error_response(
+                    id,
+                    JsonRpcErrorCode::InvalidParams,
+                    &format!("Invalid params: {}", errors[0]),
+                );
+                if let Some(error) = response.error.as_mut() {
+                    error.data = Some(json!({ "errors": errors }));
+                }
+                return Some(response);
+            }
+
             let protocol_version = params
                 .get("protocolVersion")
                 .and_then(|v| v.as_str())
@@ -248,8 +522,8 @@ async fn handle_request(
 
             if !SUPPORTED_PROTOCOL_VERSIONS.contains(&protocol_version) {
                 return Some(error_response(
-                    Some(id.unwrap_or(Value::Number((1).into()))),
-                    INVALID_PARAMS,
+                    id,
+                    JsonRpcErrorCode::InvalidParams,
                     "Unsupported protocol version",
                 ));
             }
@@ -278,10 +552,12 @@ async fn handle_request(
                         list_changed: true, // We support prompts and prompt updates
                     }),
                     resources: Some(ResourcesCapability {
-                        subscribe: false,
+                        subscribe: true,
                         list_changed: true,
+                        write: true,
                     }),
                     tools: Some(ToolsCapability { list_changed: true }),
+                    roots: Some(RootsCapability { list_changed: false }),
                 },
                 server_info: Implementation {
                     name: "rust-mcp-server".into(),
@@ -301,13 +577,21 @@ async fn handle_request(
                         "logging": result.capabilities.logging,
                         "prompts": result.capabilities.prompts,
                         "resources": result.capabilities.resources,
-                        "tools": result.capabilities.tools
+                        "tools": result.capabilities.tools,
+                        "roots": result.capabilities.roots
                     }
                 })),
                 error: None,
             })
         }
 
+        // The directories the file tools may access, from `FILESYSTEM_ALLOWED_DIRS` or else
+        // `MCP_ROOTS`
+        "roots/list" => {
+            let result = ListRootsResult { roots: roots_from_env() };
+            Some(success_response(id, json!(result)))
+        }
+
         "resources/list" => {
             let guard = state.lock().await;
             let result = ListResourcesResult {
@@ -317,6 +601,15 @@ async fn handle_request(
             Some(success_response(id, json!(result)))
         }
 
+        "resources/templates/list" => {
+            let guard = state.lock().await;
+            let result = ListResourceTemplatesResult {
+                resource_templates: guard.resource_templates.iter().map(|h| h.template.clone()).collect(),
+                _meta: None,
+            };
+            Some(success_response(id, json!(result)))
+        }
+
         "resources/read" => {
             let params_res: Result<ReadResourceParams, _> =
                 serde_json::from_value(req.params.unwrap_or(Value::Null));
@@ -325,14 +618,37 @@ async fn handle_request(
                 Err(e) => {
                     return Some(error_response(
                         id,
-                        INVALID_PARAMS,
+                        JsonRpcErrorCode::InvalidParams,
                         &format!("Invalid params: {}", e),
                     ));
                 }
             };
 
             let guard = state.lock().await;
+            if let Some(content) = guard.written_resources.get(&params.uri) {
+                let result = ReadResourceResult {
+                    contents: vec![content.clone()],
+                    _meta: None,
+                };
+                return Some(success_response(id, json!(result)));
+            }
+
             let res = guard.resources.iter().find(|r| r.uri == params.uri);
+            if res.is_none() {
+                let matched = guard.resource_templates.iter().find_map(|handler| {
+                    handler.template.match_uri(&params.uri).map(|vars| (handler.clone(), vars))
+                });
+                if let Some((handler, vars)) = matched {
+                    let Some(call) = guard.tools.execute(
+                        CallToolParams { name: handler.tool.clone(), arguments: json!(vars), _meta: None },
+                        id.clone(),
+                    ) else {
+                        return Some(error_response(id, JsonRpcErrorCode::InternalError, "Template tool not available"));
+                    };
+                    drop(guard);
+                    return Some(read_templated_resource(id, &params.uri, &handler.template, call.await));
+                }
+            }
             match res {
                 Some(r) => {
                     let content = ResourceContent {
@@ -351,15 +667,115 @@ async fn handle_request(
             }
         }
 
+        "resources/write" => {
+            let params: WriteResourceParams = match serde_json::from_value(req.params.unwrap_or(Value::Null)) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Some(error_response(
+                        id,
+                        JsonRpcErrorCode::InvalidParams,
+                        &format!("Invalid params: {}", e),
+                    ));
+                }
+            };
+
+            let created = {
+                let mut guard = state.lock().await;
+                let created = !guard.resources.iter().any(|r| r.uri == params.uri);
+                if created {
+                    let name = params.uri.rsplit('/').next().unwrap_or(&params.uri).to_string();
+                    guard.resources.push(ResourceInfo {
+                        uri: params.uri.clone(),
+                        name,
+                        mime_type: params.mime_type.clone(),
+                        description: None,
+                    });
+                }
+                guard.written_resources.insert(params.uri.clone(), ResourceContent {
+                    uri: params.uri.clone(),
+                    mime_type: params.mime_type,
+                    text: Some(params.content),
+                    blob: None,
+                });
+                created
+            };
+
+            info!("Wrote resource {}", params.uri);
+            if created {
+                notify_resources_list_changed(&tx_out);
+            }
+            notify_resource_updated(state, &params.uri).await;
+            Some(success_response(id, json!({})))
+        }
+
+        "ping" => {
+            // A ping request gets an empty result; a ping notification gets a pong notification
+            if id.is_none() {
+                Some(notification_response("pong", Some(json!({}))))
+            } else {
+                Some(success_response(id, json!({})))
+            }
+        }
+
+        "resources/subscribe" => {
+            let params: SubscribeResourceParams = match serde_json::from_value(req.params.unwrap_or(Value::Null)) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Some(error_response(
+                        id,
+                        JsonRpcErrorCode::InvalidParams,
+                        &format!("Invalid params: {}", e),
+                    ));
+                }
+            };
+
+            let mut guard = state.lock().await;
+            let subscribers = guard.subscriptions.entry(params.uri.clone()).or_default();
+            if !subscribers.iter().any(|tx| tx.same_channel(&tx_out)) {
+                subscribers.push(tx_out);
+            }
+            info!("Client subscribed to resource {}", params.uri);
+            Some(success_response(id, json!({})))
+        }
+
+        "resources/unsubscribe" => {
+            let params: SubscribeResourceParams = match serde_json::from_value(req.params.unwrap_or(Value::Null)) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Some(error_response(
+                        id,
+                        JsonRpcErrorCode::InvalidParams,
+                        &format!("Invalid params: {}", e),
+                    ));
+                }
+            };
+
+            let mut guard = state.lock().await;
+            if let Some(subscribers) = guard.subscriptions.get_mut(&params.uri) {
+                subscribers.retain(|tx| !tx.same_channel(&tx_out));
+                if subscribers.is_empty() {
+                    guard.subscriptions.remove(&params.uri);
+                }
+            }
+            info!("Client unsubscribed from resource {}", params.uri);
+            Some(success_response(id, json!({})))
+        }
+
         "tools/list" => {
             let guard = state.lock().await;
             let result = ListToolsResult {
-                tools: guard.tools.clone(),
+                tools: guard.tools.infos(),
                 _meta: None,
             };
             Some(success_response(id, json!(result)))
         }
 
+        // Not part of MCP: per-tool call counters collected by MetricsMiddleware
+        "tools/stats" => {
+            let guard = state.lock().await;
+            Some(success_response(id, json!({ "tools": guard.tool_stats.snapshot() })))
+        }
+
         "tools/call" => {
             let params_res: Result<CallToolParams, _> =
                 serde_json::from_value(req.params.clone().unwrap_or(Value::Null));
@@ -368,34 +784,42 @@ async fn handle_request(
                 Err(e) => {
                     return Some(standard_error_response(
                         id,
-                        INVALID_PARAMS,
+                        JsonRpcErrorCode::InvalidParams,
                         &format!("Invalid params: {}", e),
                     ));
                 }
             };
 
-            // Find the tool implementation by name and execute it directly
+            // Find the tool implementation by name and start it while holding the lock
             let result = {
                 let guard = state.lock().await;
-                if let Some(tool) = guard.tool_impls.iter().find(|t| t.name() == params.name) {
-                    // Execute the tool while holding the lock
-                    debug!("Executing tool: {}", tool.name());
-                    Some(tool.execute(params.clone(), id.clone()))
-                } else {
-                    None
-                }
+                debug!("Executing tool: {}", params.name);
+                guard.tools.execute(params.clone(), id.clone())
             };
             
             match result {
                 Some(future) => {
                     // Await the future outside the lock
                     match future.await {
-                        Ok(response) => Some(response),
+                        Ok(response) => {
+                            // Tools report the resources they modified in `_meta.updatedResources`
+                            let updated_uris: Vec<String> = response
+                                .result
+                                .as_ref()
+                                .and_then(|r| r.pointer("/_meta/updatedResources"))
+                                .and_then(Value::as_array)
+                                .map(|uris| uris.iter().filter_map(|u| u.as_str().map(String::from)).collect())
+                                .unwrap_or_default();
+                            for uri in &updated_uris {
+                                notify_resource_updated(state, uri).await;
+                            }
+                            Some(response)
+                        }
                         Err(e) => {
                             error!("Tool execution error: {}", e);
                             Some(standard_error_response(
                                 id,
-                                INTERNAL_ERROR,
+                                JsonRpcErrorCode::InternalError,
                                 &format!("Tool execution failed: {}", e)
                             ))
                         }
@@ -413,51 +837,10 @@ async fn handle_request(
 
         }
 
+        // Notifications we don't handle, like `notifications/initialized`, need no response
+        _ if id.is_none() && req.method.starts_with("notifications/") => None,
         _ => Some(error_response(id, -32601, "Method not found")), // -32601 is standard code for method not found
     }
 }
 
 // We're removing all the individual tool handler functions since we're using the Tool trait implementations directly
-
-fn detect_intended_tool_call(json: &Value) -> Option<String> {
-    // Common tool parameter names and their likely tools
-    let tool_hints = [
-        (vec!["file_path", "pattern", "replacement"], "regex_replace"),
-        (vec!["command"], "bash"),
-        (vec!["url"], "scrape_url"),
-        (vec!["query"], "brave_search"),
-        (vec!["action", "repo_path", "files", "message"], "git"),
-        (
-            vec![
-                "command",
-                "name",
-                "description",
-                "content",
-                "parent",
-                "relation",
-                "tags",
-            ],
-            "graph_tool",
-        ),
-        (vec!["action", "params"], "sequential_thinking"),
-        (vec!["action", "params"], "memory"),
-        (vec!["action", "params"], "task_planning"),
-    ];
-
-    // If it's an object, check its fields
-    if let Some(obj) = json.as_object() {
-        for (params, tool_name) in tool_hints.iter() {
-            // Count how many of the hint parameters are present
-            let matches = params
-                .iter()
-                .filter(|&param| obj.contains_key(*param))
-                .count();
-
-            // If we find more than half of the expected parameters, this is probably the intended tool
-            if matches >= (params.len() + 1) / 2 {
-                return Some((*tool_name).to_string());
-            }
-        }
-    }
-    None
-}
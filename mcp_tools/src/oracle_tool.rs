@@ -5,7 +5,7 @@ use tokio::time::timeout;
 use std::time::Duration;
 use serde::{Deserialize, Serialize};
 use shared_protocol_objects::{ToolInfo, CallToolResult, ToolResponseContent};
-use shared_protocol_objects::{success_response, error_response, JsonRpcResponse, INVALID_PARAMS};
+use shared_protocol_objects::{success_response, error_response, JsonRpcResponse, JsonRpcErrorCode};
 use shared_protocol_objects::CallToolParams;
 use base64::Engine;
 use oracle::{SqlValue};
@@ -53,6 +53,7 @@ pub fn oracle_select_tool_info() -> ToolInfo {
             "required": ["sql_query"],
             "additionalProperties": false
         }),
+        output_schema: None,
     }
 }
 
@@ -65,7 +66,7 @@ pub async fn handle_oracle_select_tool_call(
         Err(e) => {
             return Ok(error_response(
                 id,
-                INVALID_PARAMS,
+                JsonRpcErrorCode::InvalidParams,
                 &format!("Invalid parameters provided. Ensure 'sql_query' is provided and is a string. Error: {}", e)
             ))
         }
@@ -75,7 +76,7 @@ pub async fn handle_oracle_select_tool_call(
     if !query_trimmed.starts_with("SELECT") {
         return Ok(error_response(
             id,
-            INVALID_PARAMS,
+            JsonRpcErrorCode::InvalidParams,
             "Only SELECT statements are allowed. Please modify the query to start with 'SELECT'."
         ));
     }
@@ -86,7 +87,7 @@ pub async fn handle_oracle_select_tool_call(
         Err(_) => {
             return Ok(error_response(
                 id,
-                INVALID_PARAMS,
+                JsonRpcErrorCode::InvalidParams,
                 "Environment variable ORACLE_USER not set. Please set ORACLE_USER before running queries."
             ))
         }
@@ -97,7 +98,7 @@ pub async fn handle_oracle_select_tool_call(
         Err(_) => {
             return Ok(error_response(
                 id,
-                INVALID_PARAMS,
+                JsonRpcErrorCode::InvalidParams,
                 "Environment variable ORACLE_PASSWORD not set. Please set ORACLE_PASSWORD before running queries."
             ))
         }
@@ -108,7 +109,7 @@ pub async fn handle_oracle_select_tool_call(
         Err(_) => {
             return Ok(error_response(
                 id,
-                INVALID_PARAMS,
+                JsonRpcErrorCode::InvalidParams,
                 "Environment variable ORACLE_CONNECT_STRING not set. Please set ORACLE_CONNECT_STRING before running queries."
             ))
         }
@@ -122,7 +123,7 @@ pub async fn handle_oracle_select_tool_call(
             Err(_) => {
                 return Ok(error_response(
                     id,
-                    INVALID_PARAMS,
+                    JsonRpcErrorCode::InvalidParams,
                     "No connection string provided and ORACLE_CONNECT_STRING environment variable not set."
                 ))
             }
@@ -134,8 +135,7 @@ pub async fn handle_oracle_select_tool_call(
         Ok(rows) => rows,
         Err(e) => {
             let tool_res = CallToolResult {
-                content: vec![ToolResponseContent {
-                    type_: "text".into(),
+                content: vec![ToolResponseContent::Text {
                     text: format!("Error executing query: {}. Consider checking:\n\
                     - That the database is reachable and credentials are correct\n\
                     - The query syntax and table/column names\n\
@@ -154,8 +154,7 @@ pub async fn handle_oracle_select_tool_call(
     };
 
     let tool_res = CallToolResult {
-        content: vec![ToolResponseContent {
-            type_: "text".into(),
+        content: vec![ToolResponseContent::Text {
             text: serde_json::to_string_pretty(&rows)?,
             annotations: None,
         }],
@@ -1,14 +1,19 @@
+use html2md_rs::parser::safe_parse_html;
+use html2md_rs::structs::{Node, NodeType};
 use html2md_rs::to_md::from_html_to_md;
+use serde::Serialize;
+use serde_json::Value;
 use tracing::{debug, warn};
 use url::Url;
 
-/// Extracts text from HTML content and converts it to Markdown
+/// Extracts HTML content as Markdown
 ///
 /// This function uses html2md-rs to convert HTML to Markdown following
-/// the CommonMark specification. It also adds source information if a URL is provided.
-pub fn extract_text_from_html(html: &str, url: Option<&str>) -> String {
+/// the CommonMark specification, keeping headers, links and fenced code blocks.
+/// It also adds source information if a URL is provided.
+pub fn extract_markdown_from_html(html: &str, base_url: Option<&str>) -> String {
     // Convert HTML to Markdown
-    let mut markdown = match std::panic::catch_unwind(|| from_html_to_md(html.to_string())) {
+    let markdown = match std::panic::catch_unwind(|| from_html_to_md(html.to_string())) {
         Ok(md) => md,
         Err(e) => {
             warn!("Failed to parse HTML: {:?}", e);
@@ -16,26 +21,308 @@ pub fn extract_text_from_html(html: &str, url: Option<&str>) -> String {
             return format!("Failed to parse HTML\n\nRaw content:\n{}", html);
         }
     };
-    
-    // If URL provided, add it as reference
+
+    append_source(markdown, base_url)
+}
+
+/// Extracts the readable text from HTML content, dropping all markup
+///
+/// Scripts, styles and the document head are skipped; block elements are
+/// separated by newlines. Adds source information if a URL is provided.
+pub fn extract_text_from_html(html: &str, url: Option<&str>) -> String {
+    let root = match safe_parse_html(html.to_string()) {
+        Ok(root) => root,
+        Err(e) => {
+            warn!("Failed to parse HTML: {:?}", e);
+            return format!("Failed to parse HTML\n\nRaw content:\n{}", html);
+        }
+    };
+
+    let mut text = String::new();
+    collect_text(&root, &mut text);
+
+    // Collapse the blank lines left behind by nested block elements
+    let text = text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    debug!("Extracted {} characters of text", text.len());
+
+    append_source(text, url)
+}
+
+fn collect_text(node: &Node, out: &mut String) {
+    match &node.tag_name {
+        Some(NodeType::Head | NodeType::Script | NodeType::Style | NodeType::Comment | NodeType::Meta | NodeType::Link) => return,
+        Some(NodeType::Text) | None => {
+            if let Some(value) = &node.value {
+                out.push_str(value);
+            }
+        }
+        Some(NodeType::Br) => out.push('\n'),
+        _ => {}
+    }
+
+    for child in &node.children {
+        collect_text(child, out);
+    }
+
+    if matches!(
+        node.tag_name,
+        Some(NodeType::P | NodeType::Div | NodeType::Li | NodeType::Pre | NodeType::Blockquote
+            | NodeType::H1 | NodeType::H2 | NodeType::H3 | NodeType::H4 | NodeType::H5 | NodeType::H6)
+    ) {
+        out.push('\n');
+    }
+}
+
+/// Metadata from a page's `<head>`: title, description, Open Graph tags and JSON-LD blocks
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PageMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub og_title: Option<String>,
+    pub og_description: Option<String>,
+    pub og_image: Option<String>,
+    pub canonical_url: Option<String>,
+    pub json_ld: Vec<Value>,
+}
+
+/// Extracts the `<title>`, `<meta name="description">`, `<meta property="og:*">`,
+/// `<link rel="canonical">` and `<script type="application/ld+json">` contents of a page
+///
+/// Malformed JSON-LD blocks are skipped. Returns empty metadata if the HTML cannot be parsed.
+pub fn extract_metadata_from_html(html: &str) -> PageMetadata {
+    let mut metadata = PageMetadata::default();
+    match safe_parse_html(html.to_string()) {
+        Ok(root) => collect_metadata(&root, &mut metadata),
+        Err(e) => warn!("Failed to parse HTML for metadata: {:?}", e),
+    }
+    metadata
+}
+
+fn collect_metadata(node: &Node, metadata: &mut PageMetadata) {
+    let attr = |key: &str| {
+        node.attributes.as_ref()
+            .and_then(|attributes| attributes.get(key))
+            .map(|value| value.to_string())
+    };
+
+    match &node.tag_name {
+        Some(NodeType::Title) if metadata.title.is_none() => {
+            let mut title = String::new();
+            collect_text(node, &mut title);
+            let title = title.trim();
+            if !title.is_empty() {
+                metadata.title = Some(title.to_string());
+            }
+        }
+        Some(NodeType::Meta) => {
+            let content = attr("content");
+            let field = match attr("name").or_else(|| attr("property")).as_deref() {
+                Some("description") => Some(&mut metadata.description),
+                Some("og:title") => Some(&mut metadata.og_title),
+                Some("og:description") => Some(&mut metadata.og_description),
+                Some("og:image") => Some(&mut metadata.og_image),
+                _ => None,
+            };
+            if let (Some(field), Some(content)) = (field, content) {
+                field.get_or_insert(content);
+            }
+        }
+        Some(NodeType::Link) if attr("rel").as_deref() == Some("canonical") => {
+            if let Some(href) = node.attributes.as_ref().and_then(|attributes| attributes.get_href()) {
+                metadata.canonical_url.get_or_insert(href);
+            }
+        }
+        Some(NodeType::Script) if attr("type").as_deref() == Some("application/ld+json") => {
+            let body: String = node.children.iter().filter_map(|child| child.value.as_deref()).collect();
+            match serde_json::from_str(&body) {
+                Ok(json) => metadata.json_ld.push(json),
+                Err(e) => debug!("Skipping malformed JSON-LD block: {}", e),
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    // Unclosed void elements like <meta> end up holding the rest of the head as children
+    for child in &node.children {
+        collect_metadata(child, metadata);
+    }
+}
+
+/// The cells of an HTML `<table>`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HtmlTable {
+    /// The header row: the `<thead>` row, or a first row made only of `<th>` cells. Empty if
+    /// the table has neither.
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Extracts every `<table>` of a page, in document order, keeping its rows and columns
+///
+/// A table nested in a cell is returned as a table of its own; its text also stays in the
+/// cell holding it. Tables without rows are skipped.
+pub fn extract_tables_from_html(html: &str) -> Vec<HtmlTable> {
+    let mut tables = Vec::new();
+    match safe_parse_html(html.to_string()) {
+        Ok(root) => collect_tables(&root, &mut tables),
+        Err(e) => warn!("Failed to parse HTML for tables: {:?}", e),
+    }
+    debug!("Extracted {} tables", tables.len());
+    tables
+}
+
+fn is_tag(node: &Node, tag: &str) -> bool {
+    matches!(&node.tag_name, Some(NodeType::Unknown(name)) if name == tag)
+}
+
+fn collect_tables(node: &Node, tables: &mut Vec<HtmlTable>) {
+    if is_tag(node, "table") {
+        let mut rows = Vec::new();
+        collect_rows(node, false, &mut rows);
+        if !rows.is_empty() {
+            let mut table = HtmlTable::default();
+            let (in_head, is_header_row, _) = &rows[0];
+            if *in_head || *is_header_row {
+                table.headers = rows.remove(0).2;
+            }
+            table.rows = rows.into_iter().map(|(_, _, cells)| cells).collect();
+            tables.push(table);
+        }
+    }
+
+    for child in &node.children {
+        collect_tables(child, tables);
+    }
+}
+
+/// The rows of a table as `(in <thead>, only <th> cells, cell texts)`, not descending into
+/// nested tables
+fn collect_rows(node: &Node, in_head: bool, rows: &mut Vec<(bool, bool, Vec<String>)>) {
+    for child in &node.children {
+        if is_tag(child, "tr") {
+            let cells: Vec<&Node> = child.children.iter()
+                .filter(|cell| is_tag(cell, "td") || is_tag(cell, "th"))
+                .collect();
+            if !cells.is_empty() {
+                let is_header_row = cells.iter().all(|cell| is_tag(cell, "th"));
+                let texts = cells.into_iter()
+                    .map(|cell| {
+                        let mut text = String::new();
+                        collect_text(cell, &mut text);
+                        text.split_whitespace().collect::<Vec<_>>().join(" ")
+                    })
+                    .collect();
+                rows.push((in_head, is_header_row, texts));
+            }
+        } else if !is_tag(child, "table") {
+            collect_rows(child, in_head || is_tag(child, "thead"), rows);
+        }
+    }
+}
+
+/// Most links `extract_links_from_html` returns
+pub const MAX_LINKS: usize = 200;
+
+/// Which hosts `extract_links_from_html` keeps links to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkScope {
+    All,
+    /// Links to the page's own host
+    SameDomain,
+    /// Links to other hosts
+    External,
+}
+
+/// An `<a href>` of a page, with the href resolved against the page URL
+#[derive(Debug, Clone, Serialize)]
+pub struct PageLink {
+    pub href: String,
+    pub text: String,
+    pub rel: Option<String>,
+}
+
+/// Extracts the `<a href>` links of a page in document order, keeping the first
+/// `MAX_LINKS` within `scope`
+///
+/// Relative hrefs are resolved against `base_url`. Fragment-only, `javascript:` and
+/// unresolvable hrefs are skipped, as are repeats of a link already returned.
+pub fn extract_links_from_html(html: &str, base_url: &str, scope: LinkScope) -> Vec<PageLink> {
+    let Ok(base) = Url::parse(base_url) else {
+        warn!("Cannot resolve links against invalid URL {}", base_url);
+        return Vec::new();
+    };
+    let mut links = Vec::new();
+    match safe_parse_html(html.to_string()) {
+        Ok(root) => collect_links(&root, &base, scope, &mut links),
+        Err(e) => warn!("Failed to parse HTML for links: {:?}", e),
+    }
+    debug!("Extracted {} links from {}", links.len(), base_url);
+    links
+}
+
+fn collect_links(node: &Node, base: &Url, scope: LinkScope, links: &mut Vec<PageLink>) {
+    if links.len() >= MAX_LINKS {
+        return;
+    }
+
+    if node.tag_name == Some(NodeType::A) {
+        let attributes = node.attributes.as_ref();
+        let href = attributes.and_then(|attributes| attributes.get_href())
+            .filter(|href| !href.starts_with('#') && !href.trim_start().starts_with("javascript:"))
+            .and_then(|href| base.join(href.trim()).ok());
+        if let Some(href) = href.filter(|href| in_scope(href, base, scope)) {
+            let href = href.to_string();
+            if !links.iter().any(|link| link.href == href) {
+                let mut text = String::new();
+                collect_text(node, &mut text);
+                links.push(PageLink {
+                    href,
+                    text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+                    rel: attributes.and_then(|attributes| attributes.get("rel")).map(|rel| rel.to_string()),
+                });
+            }
+        }
+    }
+
+    for child in &node.children {
+        collect_links(child, base, scope, links);
+    }
+}
+
+fn in_scope(href: &Url, base: &Url, scope: LinkScope) -> bool {
+    let same_domain = href.host_str() == base.host_str();
+    match scope {
+        LinkScope::All => true,
+        LinkScope::SameDomain => same_domain,
+        LinkScope::External => !same_domain,
+    }
+}
+
+/// Append `Source:` and `Domain:` lines for `url`, if it parses
+fn append_source(mut content: String, url: Option<&str>) -> String {
     if let Some(url_str) = url {
         if let Ok(parsed_url) = Url::parse(url_str) {
             // Add a blank line before source information
-            if !markdown.ends_with("\n\n") {
-                if markdown.ends_with('\n') {
-                    markdown.push('\n');
+            if !content.ends_with("\n\n") {
+                if content.ends_with('\n') {
+                    content.push('\n');
                 } else {
-                    markdown.push_str("\n\n");
+                    content.push_str("\n\n");
                 }
             }
-            
-            markdown.push_str(&format!("Source: {}", url_str));
+
+            content.push_str(&format!("Source: {}", url_str));
 
             if let Some(domain) = parsed_url.domain() {
-                markdown.push_str(&format!("\nDomain: {}", domain));
+                content.push_str(&format!("\nDomain: {}", domain));
             }
         }
     }
 
-    markdown
+    content
 }
@@ -0,0 +1,80 @@
+use anyhow::{anyhow, Result};
+use shared_protocol_objects::{
+    GetPromptResult, PromptArgument, PromptDefinition, PromptMessage, Role, ToolResponseContent,
+};
+use std::collections::HashMap;
+
+fn argument(name: &str, description: &str, required: bool) -> PromptArgument {
+    PromptArgument {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        required,
+    }
+}
+
+/// Prompt templates served through `prompts/list` and `prompts/get`
+pub fn default_prompts() -> Vec<PromptDefinition> {
+    vec![
+        PromptDefinition {
+            name: "summarize_url".to_string(),
+            description: Some("Summarize the content of a web page".to_string()),
+            arguments: vec![
+                argument("url", "Address of the page to summarize", true),
+                argument("focus", "Aspect of the page to concentrate on", false),
+            ],
+            template: "Use the scrape_url tool to fetch {{url}}, then write a concise summary of the page. \
+                Lead with the main point, list the key facts as bullets and note anything that looks outdated. \
+                Focus: {{focus}}"
+                .to_string(),
+        },
+        PromptDefinition {
+            name: "explain_git_diff".to_string(),
+            description: Some("Explain the changes in a git diff".to_string()),
+            arguments: vec![
+                argument("diff", "Output of `git diff`", true),
+                argument("context", "What the change is meant to achieve", false),
+            ],
+            template: "Explain the following git diff for a code reviewer. Describe what changed and why it \
+                likely changed, point out risky edits and anything that deserves a test.\n\
+                Context: {{context}}\n\n```diff\n{{diff}}\n```"
+                .to_string(),
+        },
+        PromptDefinition {
+            name: "analyze_graph_node".to_string(),
+            description: Some("Analyze a knowledge graph node and its connections".to_string()),
+            arguments: vec![
+                argument("node_name", "Name of the node to analyze", true),
+            ],
+            template: "Use the graph_tool to look up the node \"{{node_name}}\" and its connected nodes. \
+                Summarize what the node represents, how it relates to its neighbours, and suggest \
+                missing connections or information worth adding."
+                .to_string(),
+        },
+    ]
+}
+
+/// Substitute `arguments` into the prompt's `{{placeholders}}`. Optional arguments that were
+/// not provided render as empty strings.
+pub fn render_prompt(prompt: &PromptDefinition, arguments: &HashMap<String, String>) -> Result<GetPromptResult> {
+    let mut text = prompt.template.clone();
+    for arg in &prompt.arguments {
+        let value = match arguments.get(&arg.name) {
+            Some(value) => value.as_str(),
+            None if arg.required => return Err(anyhow!("Missing required argument: {}", arg.name)),
+            None => "",
+        };
+        text = text.replace(&format!("{{{{{}}}}}", arg.name), value);
+    }
+
+    Ok(GetPromptResult {
+        description: prompt.description.clone(),
+        messages: vec![PromptMessage {
+            role: Role::User,
+            content: ToolResponseContent::Text {
+                text,
+                annotations: None,
+            },
+        }],
+        _meta: None,
+    })
+}
@@ -1,15 +1,31 @@
 use anyhow::{anyhow, Result};
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
 use serde::Deserialize;
-use serde_json::Value;
-use shared_protocol_objects::{error_response, success_response, CallToolParams, CallToolResult, JsonRpcResponse, ToolResponseContent, INTERNAL_ERROR};
+use serde_json::{json, Value};
+use shared_protocol_objects::{error_response, success_response, CallToolParams, CallToolResult, JsonRpcResponse, ToolResponseContent, JsonRpcErrorCode};
 use std::fs;
 use std::io::{Read, Write};
+use similar::{ChangeTag, TextDiff};
 use std::path::Path;
 
+use crate::filesystem_tool::{allowed_dirs_from_env, resolve_allowed_path};
+
+/// How many lines of unified diff a dry run shows
+const DIFF_PREVIEW_LINES: usize = 20;
+/// Most files a `file_glob` replacement may touch unless `max_files` says otherwise
+const DEFAULT_MAX_FILES: usize = 100;
+
 #[derive(Debug, Deserialize)]
 pub struct RegexReplaceParams {
-    pub file_path: String,
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// Replace in every file under `root_dir` matching this glob instead of `file_path`
+    #[serde(default)]
+    pub file_glob: Option<String>,
+    #[serde(default)]
+    pub root_dir: Option<String>,
+    #[serde(default)]
+    pub max_files: Option<usize>,
     pub start_pattern: String,
     pub end_pattern: String,
     pub replacement: String,
@@ -23,6 +39,9 @@ pub struct RegexReplaceParams {
     pub create_backup: bool,
     #[serde(default)]
     pub context_lines: Option<u32>,
+    /// Regex flags: `i` case-insensitive, `m` multi-line, `s` dot matches newline
+    #[serde(default)]
+    pub flags: Option<String>,
 }
 
 /// Attempts to validate if a regex pattern string has common issues
@@ -32,16 +51,6 @@ fn validate_regex_pattern(pattern: &str) -> Result<(), String> {
         return Err("Pattern cannot be empty".to_string());
     }
 
-    // Check for unescaped special characters in string literals
-    if pattern.contains("\\(") || pattern.contains("\\)") {
-        // These are properly escaped, continue checking other issues
-    } else {
-        // Look for potentially unescaped parentheses
-        if pattern.contains('(') || pattern.contains(')') {
-            return Err("Warning: Pattern contains parentheses '(' or ')' that may need to be escaped as '\\(' or '\\)'".to_string());
-        }
-    }
-    
     // Look for potentially unescaped braces
     if pattern.contains('{') || pattern.contains('}') {
         if !pattern.contains("\\{") && !pattern.contains("\\}") {
@@ -112,6 +121,20 @@ fn validate_regex_pattern(pattern: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Compile `pattern` with the single-letter `flags` (`i`, `m`, `s`) applied
+fn build_regex(pattern: &str, flags: Option<&str>) -> Result<Regex> {
+    let mut builder = RegexBuilder::new(pattern);
+    for flag in flags.unwrap_or_default().chars() {
+        match flag {
+            'i' => builder.case_insensitive(true),
+            'm' => builder.multi_line(true),
+            's' => builder.dot_matches_new_line(true),
+            other => return Err(anyhow!("Unknown regex flag '{}', expected i, m or s", other)),
+        };
+    }
+    Ok(builder.build()?)
+}
+
 /// Detect and preserve the original line endings in a file
 fn detect_line_endings(content: &str) -> &str {
     if content.contains("\r\n") {
@@ -157,72 +180,40 @@ fn get_context(lines: &[&str], line_idx: usize, context_lines: u32, total_lines:
     result
 }
 
-pub async fn handle_regex_replace_tool_call(params: CallToolParams, id: Option<Value>) -> Result<JsonRpcResponse> {
-    // Ensure id is never null to satisfy Claude Desktop client
-    let id = Some(id.unwrap_or(Value::String("regex_replace".into())));
-    let args: RegexReplaceParams = serde_json::from_value(params.arguments)
-        .map_err(|e| anyhow!("Invalid arguments: {}", e))?;
-
-    // Validate file exists and is readable
-    let file_path = Path::new(&args.file_path);
-    if !file_path.exists() {
-        return Ok(error_response(id, INTERNAL_ERROR, "File not found"));
-    }
-    
-    if !file_path.is_file() {
-        return Ok(error_response(id, INTERNAL_ERROR, "Path exists but is not a regular file"));
-    }
-    
-    // Try to check if the file is readable by opening it
-    if fs::File::open(&args.file_path).is_err() {
-        return Ok(error_response(id, INTERNAL_ERROR, "File exists but could not be opened for reading (check permissions)"));
-    }
+/// What a dry run reports: both versions of the file, the start of the diff between them
+/// and how many blocks matched
+fn dry_run_payload(file_path: &str, original: &str, modified: &str, matches_count: usize) -> Value {
+    let diff = TextDiff::from_lines(original, modified);
+    let lines_changed = diff.iter_all_changes()
+        .filter(|change| change.tag() != ChangeTag::Equal)
+        .count();
+    let unified = diff.unified_diff()
+        .header(file_path, file_path)
+        .to_string();
+    let preview: Vec<&str> = unified.lines().take(DIFF_PREVIEW_LINES).collect();
 
-    // Validate regex patterns are not empty
-    if args.start_pattern.trim().is_empty() {
-        return Ok(error_response(id, INTERNAL_ERROR, "Start pattern cannot be empty"));
-    }
-    
-    if args.end_pattern.trim().is_empty() {
-        return Ok(error_response(id, INTERNAL_ERROR, "End pattern cannot be empty"));
-    }
+    json!({
+        "would_change": original != modified,
+        "matches_count": matches_count,
+        "original": original,
+        "modified": modified,
+        "diff_summary": format!("{} lines changed\n{}", lines_changed, preview.join("\n")),
+    })
+}
 
-    // Validate regex patterns before proceeding
-    if let Err(warning) = validate_regex_pattern(&args.start_pattern) {
-        return Ok(error_response(id, INTERNAL_ERROR, &format!("{} in start pattern: '{}'", warning, args.start_pattern)));
-    }
-    
-    if let Err(warning) = validate_regex_pattern(&args.end_pattern) {
-        return Ok(error_response(id, INTERNAL_ERROR, &format!("{} in end pattern: '{}'", warning, args.end_pattern)));
-    }
+/// New lines of a file and the (start, end) line indices of the blocks replaced in it
+type ReplacedBlocks = (Vec<String>, Vec<(usize, usize)>);
 
-    // Read file content
-    let mut file = fs::File::open(&args.file_path)?;
-    let mut content = String::new();
-    file.read_to_string(&mut content)
-        .map_err(|e| anyhow!("Failed to read file contents: {}", e))?;
-    
-    // Check if file is empty
-    if content.trim().is_empty() {
-        return Ok(error_response(id, INTERNAL_ERROR, "File is empty, nothing to replace"));
-    }
-    
-    // Detect and store original line endings
-    let line_ending = detect_line_endings(&content);
-    
-    // Split content into lines to work with line-based matching
-    let lines: Vec<&str> = content.split(line_ending).collect();
-    
-    // Create regex patterns for start and end
-    let start_re = match Regex::new(&args.start_pattern) {
-        Ok(re) => re,
-        Err(e) => return Ok(error_response(id, INTERNAL_ERROR, &format!("Invalid start regex pattern: {}", e))),
-    };
-    
-    let end_re = match Regex::new(&args.end_pattern) {
-        Ok(re) => re,
-        Err(e) => return Ok(error_response(id, INTERNAL_ERROR, &format!("Invalid end regex pattern: {}", e))),
-    };
+/// Replace the start..end blocks selected by `match_all` / `match_occurrence` with the
+/// replacement. Blocks are listed last first; there are none when the start pattern
+/// doesn't match.
+fn replace_blocks(
+    lines: &[&str],
+    start_re: &Regex,
+    end_re: &Regex,
+    args: &RegexReplaceParams,
+) -> Result<ReplacedBlocks, String> {
+    let mut new_lines = lines.iter().map(|s| s.to_string()).collect::<Vec<String>>();
     let start_matches: Vec<usize> = lines.iter()
         .enumerate()
         .filter(|(_, line)| start_re.is_match(line))
@@ -230,7 +221,7 @@ pub async fn handle_regex_replace_tool_call(params: CallToolParams, id: Option<V
         .collect();
 
     if start_matches.is_empty() {
-        return Ok(error_response(id, INTERNAL_ERROR, "No matches found for start pattern, no changes made."));
+        return Ok((new_lines, Vec::new()));
     }
 
     // Determine which occurrence(s) to replace
@@ -238,33 +229,19 @@ pub async fn handle_regex_replace_tool_call(params: CallToolParams, id: Option<V
         start_matches.clone()
     } else if let Some(occurrence) = args.match_occurrence {
         if occurrence == 0 || occurrence as usize > start_matches.len() {
-            return Ok(error_response(
-                id, 
-                INTERNAL_ERROR, 
-                &format!("Invalid occurrence {}. Found {} matches for start pattern.", 
-                         occurrence, start_matches.len())
-            ));
+            return Err(format!("Invalid occurrence {}. Found {} matches for start pattern.", 
+                               occurrence, start_matches.len()));
         }
         vec![start_matches[occurrence as usize - 1]]
     } else if start_matches.len() == 1 {
         // Default behavior when only one match is found
         start_matches.clone()
     } else {
-        return Ok(error_response(
-            id, 
-            INTERNAL_ERROR, 
-            &format!("Found {} matches for start pattern. Please specify which occurrence to replace using the match_occurrence parameter, or set match_all to true.", 
-                     start_matches.len())
-        ));
+        return Err(format!("Found {} matches for start pattern. Please specify which occurrence to replace using the match_occurrence parameter, or set match_all to true.", 
+                           start_matches.len()));
     };
 
-    // Create backup if requested
-    if args.create_backup && !args.dry_run {
-        create_backup_file(&args.file_path)?;
-    }
-
-    let mut new_lines = lines.clone().into_iter().map(|s| s.to_string()).collect::<Vec<String>>();
-    let mut replacements_made = Vec::new();
+    let mut blocks = Vec::new();
     
     // Process each target start index (in reverse to maintain correct indices)
     for &start_line_idx in target_start_indices.iter().rev() {
@@ -298,81 +275,279 @@ pub async fn handle_regex_replace_tool_call(params: CallToolParams, id: Option<V
         end_matches.sort();
         
         if end_matches.is_empty() {
-            return Ok(error_response(
-                id, 
-                INTERNAL_ERROR, 
-                &format!("No matches found for end pattern '{}' after line {}. Try making your end pattern less restrictive or ensure it exists in the file after the start pattern.", 
-                         args.end_pattern, start_line_idx + 1)
-            ));
+            return Err(format!("No matches found for end pattern '{}' after line {}. Try making your end pattern less restrictive or ensure it exists in the file after the start pattern.", 
+                               args.end_pattern, start_line_idx + 1));
         }
         
         // Get the first end match that comes after the start match
         let end_line_idx = end_matches[0];
+        blocks.push((start_line_idx, end_line_idx));
         
-        // Capture context for the response
-        let context_lines = args.context_lines.unwrap_or(2);
-        let start_context = get_context(&lines, start_line_idx, context_lines, lines.len());
-        let end_context = get_context(&lines, end_line_idx, context_lines, lines.len());
-        
-        // Record this replacement
-        replacements_made.push((
-            start_line_idx, 
-            end_line_idx,
-            start_context,
-            end_context
-        ));
+        // Fill in `$1` / `$name` references from the start line's match; groups that
+        // didn't take part in the match expand to nothing
+        let mut replacement = String::new();
+        match start_re.captures(start_line) {
+            Some(captures) => captures.expand(&args.replacement, &mut replacement),
+            None => replacement.push_str(&args.replacement),
+        }
         
-        // Skip actual replacement if this is a dry run
-        if !args.dry_run {
-            // Remove the lines between start and end (inclusive)
-            new_lines.splice(start_line_idx..=end_line_idx, vec![args.replacement.clone()]);
+        // Remove the lines between start and end (inclusive)
+        new_lines.splice(start_line_idx..=end_line_idx, vec![replacement]);
+    }
+
+    Ok((new_lines, blocks))
+}
+
+/// Apply the replacement to every file under `root_dir` matching `file_glob`. Files that
+/// fail on their own (ambiguous matches, no end match, unreadable) are reported and skipped.
+fn replace_in_files(args: &RegexReplaceParams, file_glob: &str, start_re: &Regex, end_re: &Regex) -> Result<Value> {
+    let allowed_dirs = allowed_dirs_from_env();
+    let root = resolve_allowed_path(&allowed_dirs, args.root_dir.as_deref().unwrap_or("."))?;
+    let max_files = args.max_files.unwrap_or(DEFAULT_MAX_FILES);
+
+    let pattern = root.join(file_glob);
+    let mut files = Vec::new();
+    for entry in glob::glob(&pattern.to_string_lossy())? {
+        let path = entry?;
+        if !path.is_file() {
+            continue;
+        }
+        // Symlinks must not lead out of the allowed directories either
+        files.push(resolve_allowed_path(&allowed_dirs, &path.to_string_lossy())?);
+        if files.len() > max_files {
+            return Err(anyhow!(
+                "'{}' matches more than {} files, no changes made. Narrow the pattern or raise max_files.",
+                file_glob, max_files
+            ));
         }
     }
+
+    let mut results = Vec::new();
+    let (mut files_changed, mut total_matches) = (0, 0);
+    for path in &files {
+        let outcome = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read file: {}", e))
+            .and_then(|content| {
+                let line_ending = detect_line_endings(&content);
+                let lines: Vec<&str> = content.split(line_ending).collect();
+                let (new_lines, blocks) = replace_blocks(&lines, start_re, end_re, args)?;
+                if blocks.is_empty() || args.dry_run {
+                    return Ok(blocks.len());
+                }
+                if args.create_backup {
+                    create_backup_file(&path.to_string_lossy()).map_err(|e| e.to_string())?;
+                }
+                fs::write(path, new_lines.join(line_ending))
+                    .map_err(|e| format!("Failed to write modified content to file: {}", e))?;
+                Ok(blocks.len())
+            });
+
+        match outcome {
+            Ok(matches_count) => {
+                if matches_count > 0 {
+                    files_changed += 1;
+                }
+                total_matches += matches_count;
+                results.push(json!({
+                    "path": path.display().to_string(),
+                    "changed": matches_count > 0,
+                    "matches_count": matches_count,
+                }));
+            }
+            Err(error) => results.push(json!({
+                "path": path.display().to_string(),
+                "changed": false,
+                "matches_count": 0,
+                "error": error,
+            })),
+        }
+    }
+
+    Ok(json!({
+        "dry_run": args.dry_run,
+        "files": results,
+        "summary": {
+            "files_scanned": files.len(),
+            "files_changed": files_changed,
+            "total_matches": total_matches,
+        }
+    }))
+}
+
+pub async fn handle_regex_replace_tool_call(params: CallToolParams, id: Option<Value>) -> Result<JsonRpcResponse> {
+    // Ensure id is never null to satisfy Claude Desktop client
+    let id = Some(id.unwrap_or(Value::String("regex_replace".into())));
+    let args: RegexReplaceParams = serde_json::from_value(params.arguments)
+        .map_err(|e| anyhow!("Invalid arguments: {}", e))?;
+
+    // Validate regex patterns are not empty
+    if args.start_pattern.trim().is_empty() {
+        return Ok(error_response(id, JsonRpcErrorCode::InternalError, "Start pattern cannot be empty"));
+    }
     
-    if !args.dry_run {
-        // Join the lines back together with original line endings
-        let new_content = new_lines.join(line_ending);
-        
-        // Write the modified content back to the file with error handling
-        match fs::write(&args.file_path, &new_content) {
-            Ok(_) => {
-                // Verify the file was written correctly
-                match fs::read_to_string(&args.file_path) {
-                    Ok(written_content) if written_content == new_content => {
-                        // File was written successfully and content matches
-                    },
-                    Ok(_) => {
-                        return Ok(error_response(
-                            id,
-                            INTERNAL_ERROR,
-                            "File was written but content verification failed - file may be corrupted. Check backup file."
-                        ));
-                    },
-                    Err(e) => {
-                        return Ok(error_response(
-                            id,
-                            INTERNAL_ERROR,
-                            &format!("File was written but could not be verified: {}", e)
-                        ));
-                    }
+    if args.end_pattern.trim().is_empty() {
+        return Ok(error_response(id, JsonRpcErrorCode::InternalError, "End pattern cannot be empty"));
+    }
+
+    // Validate regex patterns before proceeding
+    if let Err(warning) = validate_regex_pattern(&args.start_pattern) {
+        return Ok(error_response(id, JsonRpcErrorCode::InternalError, &format!("{} in start pattern: '{}'", warning, args.start_pattern)));
+    }
+    
+    if let Err(warning) = validate_regex_pattern(&args.end_pattern) {
+        return Ok(error_response(id, JsonRpcErrorCode::InternalError, &format!("{} in end pattern: '{}'", warning, args.end_pattern)));
+    }
+
+    // Create regex patterns for start and end
+    let start_re = match build_regex(&args.start_pattern, args.flags.as_deref()) {
+        Ok(re) => re,
+        Err(e) => return Ok(error_response(id, JsonRpcErrorCode::InternalError, &format!("Invalid start regex pattern: {}", e))),
+    };
+    
+    let end_re = match build_regex(&args.end_pattern, args.flags.as_deref()) {
+        Ok(re) => re,
+        Err(e) => return Ok(error_response(id, JsonRpcErrorCode::InternalError, &format!("Invalid end regex pattern: {}", e))),
+    };
+
+    if let Some(file_glob) = &args.file_glob {
+        let result = match replace_in_files(&args, file_glob, &start_re, &end_re) {
+            Ok(result) => result,
+            Err(e) => return Ok(error_response(id, JsonRpcErrorCode::InternalError, &e.to_string())),
+        };
+        let tool_res = CallToolResult {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&result)?,
+                annotations: None,
+            }],
+            is_error: Some(false),
+            _meta: None,
+            progress: None,
+            total: None,
+        };
+        return Ok(success_response(id, serde_json::to_value(tool_res)?));
+    }
+
+    let Some(file_path) = args.file_path.clone() else {
+        return Ok(error_response(id, JsonRpcErrorCode::InvalidParams, "Either file_path or file_glob is required"));
+    };
+    // Held to the same directories as the glob form and the filesystem tool
+    let file_path = match resolve_allowed_path(&allowed_dirs_from_env(), &file_path) {
+        Ok(path) => path.to_string_lossy().into_owned(),
+        Err(e) => return Ok(error_response(id, JsonRpcErrorCode::InternalError, &e.to_string())),
+    };
+
+    // Validate file exists and is readable
+    if !Path::new(&file_path).exists() {
+        return Ok(error_response(id, JsonRpcErrorCode::InternalError, "File not found"));
+    }
+    
+    if !Path::new(&file_path).is_file() {
+        return Ok(error_response(id, JsonRpcErrorCode::InternalError, "Path exists but is not a regular file"));
+    }
+    
+    // Try to check if the file is readable by opening it
+    if fs::File::open(&file_path).is_err() {
+        return Ok(error_response(id, JsonRpcErrorCode::InternalError, "File exists but could not be opened for reading (check permissions)"));
+    }
+
+    // Read file content
+    let mut file = fs::File::open(&file_path)?;
+    let mut content = String::new();
+    file.read_to_string(&mut content)
+        .map_err(|e| anyhow!("Failed to read file contents: {}", e))?;
+    
+    // Check if file is empty
+    if content.trim().is_empty() {
+        return Ok(error_response(id, JsonRpcErrorCode::InternalError, "File is empty, nothing to replace"));
+    }
+    
+    // Detect and store original line endings
+    let line_ending = detect_line_endings(&content);
+    
+    // Split content into lines to work with line-based matching
+    let lines: Vec<&str> = content.split(line_ending).collect();
+
+    let (new_lines, blocks) = match replace_blocks(&lines, &start_re, &end_re, &args) {
+        Ok(result) => result,
+        Err(message) => return Ok(error_response(id, JsonRpcErrorCode::InternalError, &message)),
+    };
+
+    // A dry run that matches nothing is an answer too
+    if blocks.is_empty() && !args.dry_run {
+        return Ok(error_response(id, JsonRpcErrorCode::InternalError, "No matches found for start pattern, no changes made."));
+    }
+
+    // Create backup if requested
+    if args.create_backup && !args.dry_run {
+        create_backup_file(&file_path)?;
+    }
+
+    // Capture context for the response
+    let context_lines = args.context_lines.unwrap_or(2);
+    let replacements_made: Vec<_> = blocks.iter()
+        .map(|&(start_line_idx, end_line_idx)| (
+            start_line_idx,
+            end_line_idx,
+            get_context(&lines, start_line_idx, context_lines, lines.len()),
+            get_context(&lines, end_line_idx, context_lines, lines.len()),
+        ))
+        .collect();
+
+    if args.dry_run {
+        // Report the would-be result instead of writing it
+        let modified = new_lines.join(line_ending);
+        let payload = dry_run_payload(&file_path, &content, &modified, blocks.len());
+        let tool_res = CallToolResult {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&payload)?,
+                annotations: None,
+            }],
+            is_error: Some(false),
+            _meta: None,
+            progress: None,
+            total: None,
+        };
+        return Ok(success_response(id, serde_json::to_value(tool_res)?));
+    }
+
+    // Join the lines back together with original line endings
+    let new_content = new_lines.join(line_ending);
+    
+    // Write the modified content back to the file with error handling
+    match fs::write(&file_path, &new_content) {
+        Ok(_) => {
+            // Verify the file was written correctly
+            match fs::read_to_string(&file_path) {
+                Ok(written_content) if written_content == new_content => {
+                    // File was written successfully and content matches
+                },
+                Ok(_) => {
+                    return Ok(error_response(
+                        id,
+                        JsonRpcErrorCode::InternalError,
+                        "File was written but content verification failed - file may be corrupted. Check backup file."
+                    ));
+                },
+                Err(e) => {
+                    return Ok(error_response(
+                        id,
+                        JsonRpcErrorCode::InternalError,
+                        &format!("File was written but could not be verified: {}", e)
+                    ));
                 }
-            },
-            Err(e) => {
-                return Ok(error_response(
-                    id,
-                    INTERNAL_ERROR,
-                    &format!("Failed to write modified content to file: {}", e)
-                ));
             }
+        },
+        Err(e) => {
+            return Ok(error_response(
+                id,
+                JsonRpcErrorCode::InternalError,
+                &format!("Failed to write modified content to file: {}", e)
+            ));
         }
     }
     
     // Build detailed response message
-    let mut response_text = if args.dry_run {
-        "DRY RUN - No changes made to file.\n".to_string()
-    } else {
-        "".to_string()
-    };
+    let mut response_text = String::new();
     
     for (idx, (start_idx, end_idx, start_context, end_context)) in replacements_made.iter().enumerate() {
         let replaced_lines_count = (end_idx - start_idx) + 1;
@@ -399,17 +574,12 @@ pub async fn handle_regex_replace_tool_call(params: CallToolParams, id: Option<V
     }
     
     // Add summary
-    let summary = if args.dry_run {
-        format!("Dry run completed. Found {} potential replacements.", replacements_made.len())
-    } else {
-        format!("Successfully made {} replacements.", replacements_made.len())
-    };
+    let summary = format!("Successfully made {} replacements.", replacements_made.len());
     
     response_text.push_str(&format!("\n{}", summary));
     
     let tool_res = CallToolResult {
-        content: vec![ToolResponseContent {
-            type_: "text".into(),
+        content: vec![ToolResponseContent::Text {
             text: response_text,
             annotations: None,
         }],
@@ -436,16 +606,21 @@ pub fn regex_replace_tool_info() -> shared_protocol_objects::ToolInfo {
             Safety features:
             - Reports the number of matches when more than one is found
             - Preserves original file line endings and encoding
-            - Provides dry-run option to preview changes
+            - Provides dry-run option that returns the modified content and a diff without writing
             - Can create backup files automatically
             - Shows context around replacements
             - Never modifies files unless explicitly instructed
+            - Can replace across many files at once with file_glob, limited by max_files
             
             Pattern syntax guide (Rust regex):
             - Character classes: [a-z], [0-9], \\w (word), \\d (digit), \\s (whitespace)
             - Anchors: ^ (start of line), $ (end of line), \\b (word boundary)
             - Quantifiers: * (0+), + (1+), ? (0-1), {n} (exactly n), {n,m} (n to m)
-            - Groups: (pattern) creates a capture group, (?:pattern) non-capturing
+            - Groups: (pattern) creates a capture group, (?:pattern) non-capturing, (?P<name>pattern) named
+            - Replacement references: $1 or ${1} for numbered groups, $name or ${name} for named
+              groups, taken from the start pattern's match. Unmatched or unknown groups become
+              an empty string; use $$ for a literal $ (e.g. '$$HOME'). Use ${1}x when a
+              reference is directly followed by letters, digits or underscores.
             
             Example use cases:
             - Replace a function: '^function myFunc\\(\\) {$' and '^}$' with a new implementation
@@ -457,7 +632,21 @@ pub fn regex_replace_tool_info() -> shared_protocol_objects::ToolInfo {
             "properties": {
                 "file_path": {
                     "type": "string",
-                    "description": "The path to the target file."
+                    "description": "The path to the target file. Required unless file_glob is set."
+                },
+                "file_glob": {
+                    "type": "string",
+                    "description": "Apply the replacement to every file under root_dir matching this glob (e.g. '**/*.rs') instead of file_path. Returns per-file results and totals."
+                },
+                "root_dir": {
+                    "type": "string",
+                    "description": "Directory file_glob is matched in. Must be inside the allowed directories. Defaults to the working directory."
+                },
+                "max_files": {
+                    "type": "integer",
+                    "description": "Refuse to change anything if file_glob matches more files than this.",
+                    "default": 100,
+                    "minimum": 1
                 },
                 "start_pattern": {
                     "type": "string",
@@ -469,11 +658,15 @@ pub fn regex_replace_tool_info() -> shared_protocol_objects::ToolInfo {
                 },
                 "replacement": {
                     "type": "string",
-                    "description": "The text that will replace all lines between the first and last match (inclusive)."
+                    "description": "The text that will replace all lines between the first and last match (inclusive). $1, $2, ... and $name (or ${name}) insert capture groups of the start pattern's match; groups that don't exist or didn't match insert nothing. Write $$ for a literal $."
+                },
+                "flags": {
+                    "type": "string",
+                    "description": "Regex flags for both patterns: 'i' case-insensitive, 'm' multi-line (^ and $ match at line breaks), 's' dot matches newline. Combine like 'is'."
                 },
                 "dry_run": {
                     "type": "boolean",
-                    "description": "If true, shows what changes would be made without actually modifying the file.",
+                    "description": "If true, the file is left untouched and the response holds the original and modified content plus a diff summary and the number of matches.",
                     "default": false
                 },
                 "match_occurrence": {
@@ -499,8 +692,9 @@ pub fn regex_replace_tool_info() -> shared_protocol_objects::ToolInfo {
                     "maximum": 10
                 }
             },
-            "required": ["file_path", "start_pattern", "end_pattern", "replacement"],
+            "required": ["start_pattern", "end_pattern", "replacement"],
             "additionalProperties": false
         }),
+        output_schema: None,
     }
 }
\ No newline at end of file
@@ -0,0 +1,88 @@
+use anyhow::{anyhow, Result};
+use lazy_static::lazy_static;
+use robotstxt::DefaultMatcher;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+use url::Url;
+
+lazy_static! {
+    static ref SHARED: Arc<RobotsChecker> = Arc::new(RobotsChecker::new());
+}
+
+/// Answers whether a site's `robots.txt` lets a user agent fetch a URL. Each host's file is
+/// fetched once and kept for the lifetime of the checker.
+#[derive(Debug)]
+pub struct RobotsChecker {
+    client: reqwest::Client,
+    /// `robots.txt` bodies keyed by origin, e.g. `https://example.com`
+    cache: Mutex<HashMap<String, String>>,
+}
+
+impl Default for RobotsChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RobotsChecker {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self {
+            client,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The checker shared by every scrape in this process
+    pub fn shared() -> Arc<RobotsChecker> {
+        Arc::clone(&SHARED)
+    }
+
+    pub async fn is_allowed(&self, url: &str, user_agent: &str) -> Result<bool> {
+        let parsed = Url::parse(url).map_err(|e| anyhow!("Invalid URL '{}': {}", url, e))?;
+        if parsed.host_str().is_none() {
+            return Err(anyhow!("URL '{}' has no host", url));
+        }
+        let origin = parsed.origin().ascii_serialization();
+
+        let robots_txt = {
+            let cached = self.cache.lock().await.get(&origin).cloned();
+            match cached {
+                Some(robots_txt) => robots_txt,
+                None => {
+                    let robots_txt = self.fetch(&origin).await?;
+                    self.cache.lock().await.insert(origin.clone(), robots_txt.clone());
+                    robots_txt
+                }
+            }
+        };
+
+        let allowed = DefaultMatcher::default().one_agent_allowed_by_robots(&robots_txt, user_agent, url);
+        debug!("robots.txt of {} {} {} for {}", origin, if allowed { "allows" } else { "disallows" }, url, user_agent);
+        Ok(allowed)
+    }
+
+    /// A missing `robots.txt` (any 4xx) allows everything. Server errors are reported, since
+    /// the site's rules are unknown.
+    async fn fetch(&self, origin: &str) -> Result<String> {
+        let robots_url = format!("{}/robots.txt", origin);
+        info!("Fetching {}", robots_url);
+        let response = self.client.get(&robots_url).send().await
+            .map_err(|e| anyhow!("Failed to fetch {}: {}", robots_url, e))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(response.text().await?)
+        } else if status.is_client_error() {
+            debug!("{} returned {}, treating it as allowing everything", robots_url, status);
+            Ok(String::new())
+        } else {
+            Err(anyhow!("{} returned {}", robots_url, status))
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use shared_protocol_objects::{CallToolParams, JsonRpcErrorCode, ToolInfo};
+use std::collections::HashMap;
+
+use crate::tool_trait::{
+    standard_error_response, standard_success_response, standard_tool_result, ExecuteFuture, Tool,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SqliteAction {
+    Query,
+    Execute,
+    Schema,
+    ListTables,
+}
+
+#[derive(Debug, Deserialize)]
+struct SqliteParams {
+    action: SqliteAction,
+    db_path: String,
+    #[serde(default)]
+    sql: Option<String>,
+    #[serde(default)]
+    table: Option<String>,
+}
+
+pub fn sqlite_tool_info() -> ToolInfo {
+    ToolInfo {
+        name: "sqlite".to_string(),
+        description: Some(
+            "Query and modify local SQLite database files.
+
+            Actions:
+            - list_tables: names of all tables in the database
+            - schema: the CREATE TABLE statement of 'table'
+            - query: run a read-only statement (SELECT, WITH ...) and return the rows as JSON objects
+            - execute: run statements that modify the database and return the number of affected rows
+
+            Use 'schema' before writing queries against an unfamiliar table.".to_string()
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["query", "execute", "schema", "list_tables"],
+                    "description": "The operation to perform."
+                },
+                "db_path": {
+                    "type": "string",
+                    "description": "Path to the SQLite database file. 'execute' creates it if missing."
+                },
+                "sql": {
+                    "type": "string",
+                    "description": "SQL to run (for 'query' and 'execute')."
+                },
+                "table": {
+                    "type": "string",
+                    "description": "Table name (for 'schema')."
+                }
+            },
+            "required": ["action", "db_path"],
+            "additionalProperties": false
+        }),
+        output_schema: None,
+    }
+}
+
+fn open(db_path: &str, writable: bool) -> Result<Connection> {
+    let flags = if writable {
+        OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE
+    } else {
+        OpenFlags::SQLITE_OPEN_READ_ONLY
+    };
+    Connection::open_with_flags(db_path, flags)
+        .map_err(|e| anyhow!("Failed to open database '{}': {}", db_path, e))
+}
+
+fn to_json(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => json!(i),
+        ValueRef::Real(f) => json!(f),
+        ValueRef::Text(text) => Value::String(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(blob) => Value::String(base64::engine::general_purpose::STANDARD.encode(blob)),
+    }
+}
+
+fn query(db_path: &str, sql: &str) -> Result<Vec<HashMap<String, Value>>> {
+    let conn = open(db_path, false)?;
+    let mut stmt = conn.prepare(sql)?;
+    if !stmt.readonly() {
+        return Err(anyhow!("'query' only runs read-only statements; use 'execute' to modify the database"));
+    }
+
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let mut rows = stmt.query([])?;
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mut record = HashMap::new();
+        for (i, column) in columns.iter().enumerate() {
+            record.insert(column.clone(), to_json(row.get_ref(i)?));
+        }
+        result.push(record);
+    }
+    Ok(result)
+}
+
+/// Run `sql` in a transaction so a failing statement doesn't leave the batch half applied
+fn execute(db_path: &str, sql: &str) -> Result<u64> {
+    let mut conn = open(db_path, true)?;
+    let before = conn.total_changes();
+    let tx = conn.transaction()?;
+    tx.execute_batch(sql)?;
+    tx.commit()?;
+    Ok(conn.total_changes() - before)
+}
+
+fn schema(db_path: &str, table: &str) -> Result<String> {
+    let conn = open(db_path, false)?;
+    conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |row| row.get(0),
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => anyhow!("Table '{}' does not exist", table),
+        e => anyhow!(e),
+    })
+}
+
+fn list_tables(db_path: &str) -> Result<Vec<String>> {
+    let conn = open(db_path, false)?;
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )?;
+    let names = stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(names)
+}
+
+fn run(params: SqliteParams) -> Result<Value> {
+    let sql = || params.sql.as_deref().ok_or_else(|| anyhow!("Missing 'sql' parameter"));
+    match params.action {
+        SqliteAction::Query => Ok(json!(query(&params.db_path, sql()?)?)),
+        SqliteAction::Execute => Ok(json!({ "rows_affected": execute(&params.db_path, sql()?)? })),
+        SqliteAction::Schema => {
+            let table = params.table.as_deref().ok_or_else(|| anyhow!("Missing 'table' parameter"))?;
+            Ok(Value::String(schema(&params.db_path, table)?))
+        }
+        SqliteAction::ListTables => Ok(json!(list_tables(&params.db_path)?)),
+    }
+}
+
+// SQLite Tool Implementation
+#[derive(Debug)]
+pub struct SqliteTool;
+
+impl Tool for SqliteTool {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    fn info(&self) -> ToolInfo {
+        sqlite_tool_info()
+    }
+
+    fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture {
+        Box::pin(async move {
+            let sqlite_params: SqliteParams = match serde_json::from_value(params.arguments) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Ok(standard_error_response(
+                        id,
+                        JsonRpcErrorCode::InvalidParams,
+                        &format!("Invalid parameters: {}", e),
+                    ));
+                }
+            };
+
+            // rusqlite is blocking, keep it off the async workers
+            let result = tokio::task::spawn_blocking(move || run(sqlite_params)).await?;
+            let tool_res = match result {
+                Ok(Value::String(text)) => standard_tool_result(text, None),
+                Ok(value) => standard_tool_result(serde_json::to_string_pretty(&value)?, None),
+                Err(e) => standard_tool_result(format!("SQLite error: {}", e), Some(true)),
+            };
+            Ok(standard_success_response(id, json!(tool_res)))
+        })
+    }
+}
@@ -3,22 +3,33 @@ use crate::bash::{
     bash_tool_info, handle_quick_bash, quick_bash_tool_info, BashExecutor, BashParams,
     QuickBashParams,
 };
-use crate::brave_search::{search_tool_info, BraveSearchClient};
+use crate::brave_search::{search_tool_info, BraveSearchClient, DEFAULT_CACHE_TTL};
 use crate::email_validator::{handle_neverbounce_tool_call, neverbounce_tool_info};
 use crate::git_integration::{git_tool_info, handle_git_tool_call};
 use crate::gmail_integration::{gmail_tool_info, handle_gmail_tool_call};
 use crate::long_running_task::{handle_long_running_tool_call, long_running_tool_info, LongRunningTaskManager};
 use crate::oracle_tool::{handle_oracle_select_tool_call, oracle_select_tool_info};
-use crate::process_html::extract_text_from_html;
+use crate::sqlite_tool::SqliteTool;
+use crate::filesystem_tool::FilesystemTool;
+use crate::csv_tool::CsvTool;
+use crate::json_tool::JsonTool;
+use crate::diff_tool::DiffTool;
+use crate::archive_tool::ArchiveTool;
+use crate::code_search_tool::CodeSearchTool;
+use crate::http_client_tool::HttpClientTool;
+use crate::process_html::{
+    extract_links_from_html, extract_markdown_from_html, extract_metadata_from_html, extract_tables_from_html,
+    extract_text_from_html, LinkScope,
+};
 use crate::regex_replace::{handle_regex_replace_tool_call, regex_replace_tool_info};
 use crate::scraping_bee::{scraping_tool_info, ScrapingBeeClient, ScrapingBeeResponse};
 use crate::tool_trait::{ExecuteFuture, Tool, ensure_id, standard_error_response, standard_success_response, standard_tool_result};
 
 use anyhow::{anyhow, Result};
+use base64::Engine;
 use serde_json::{json, Value};
 use shared_protocol_objects::{
     CallToolParams, CallToolResult, JsonRpcResponse, ToolResponseContent,
-    INTERNAL_ERROR, INVALID_PARAMS,
 };
 use std::env;
 use std::sync::Arc;
@@ -28,7 +39,8 @@ use tracing::{debug, error, info, warn};
 // ScrapingBee Tool Implementation
 #[derive(Debug)]
 pub struct ScrapingBeeTool {
-    api_key: String,
+    // Cloned for each call; the clones share its proxy rotation
+    client: ScrapingBeeClient,
 }
 
 impl ScrapingBeeTool {
@@ -36,7 +48,7 @@ impl ScrapingBeeTool {
         let api_key = env::var("SCRAPINGBEE_API_KEY")
             .map_err(|_| anyhow!("SCRAPINGBEE_API_KEY environment variable must be set"))?;
         
-        Ok(Self { api_key })
+        Ok(Self { client: ScrapingBeeClient::new(api_key) })
     }
 }
 
@@ -48,9 +60,13 @@ impl Tool for ScrapingBeeTool {
     fn info(&self) -> shared_protocol_objects::ToolInfo {
         scraping_tool_info()
     }
+
+    fn detection_hints(&self) -> Vec<Vec<&str>> {
+        vec![vec!["url"]]
+    }
     
     fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture {
-        let api_key = self.api_key.clone();
+        let mut client = self.client.clone();
         
         Box::pin(async move {
             let url = params
@@ -59,18 +75,62 @@ impl Tool for ScrapingBeeTool {
                 .and_then(Value::as_str)
                 .ok_or_else(|| anyhow!("Missing required argument: url"))?
                 .to_string();
+            let output_format = params
+                .arguments
+                .get("output_format")
+                .and_then(Value::as_str)
+                .unwrap_or("markdown");
+            if !["markdown", "text", "tables"].contains(&output_format) {
+                return Err(anyhow!("Invalid output_format '{}': expected 'text', 'markdown' or 'tables'", output_format));
+            }
+            let flag = |name: &str| params.arguments.get(name).and_then(Value::as_bool).unwrap_or(false);
+            match params.arguments.get("action").and_then(Value::as_str).unwrap_or("scrape") {
+                "scrape" => {}
+                "extract_links" => {
+                    let scope = match (flag("filter_same_domain"), flag("filter_external")) {
+                        (false, false) => LinkScope::All,
+                        (true, false) => LinkScope::SameDomain,
+                        (false, true) => LinkScope::External,
+                        (true, true) => return Err(anyhow!("filter_same_domain and filter_external cannot both be set")),
+                    };
+                    return Ok(extract_links(client, &url, scope, id).await);
+                }
+                action => return Err(anyhow!("Invalid action '{}': expected 'scrape' or 'extract_links'", action)),
+            }
                 
-            let mut client = ScrapingBeeClient::new(api_key);
             client.url(&url).render_js(true);
             
             match client.execute().await {
                 Ok(ScrapingBeeResponse::Text(body)) => {
-                    let markdown = extract_text_from_html(&body, Some(&url));
-                    let tool_res = standard_tool_result(markdown, None);
-                    Ok(standard_success_response(id, json!(tool_res)))
+                    let content = match output_format {
+                        "text" => extract_text_from_html(&body, Some(&url)),
+                        "tables" => serde_json::to_string_pretty(&extract_tables_from_html(&body))
+                            .unwrap_or_else(|_| "[]".to_string()),
+                        _ => extract_markdown_from_html(&body, Some(&url)),
+                    };
+                    // Head metadata travels next to the body so callers don't have to re-read the page
+                    let mut tool_res = json!(standard_tool_result(content, None));
+                    tool_res["metadata"] = json!(extract_metadata_from_html(&body));
+                    Ok(standard_success_response(id, tool_res))
                 }
-                Ok(ScrapingBeeResponse::Binary(_)) => {
-                    Err(anyhow!("Can't read binary scrapes"))
+                Ok(ScrapingBeeResponse::Binary { data, content_type }) => {
+                    // Binary scrapes are screenshots; fall back to PNG if the mime type is missing
+                    let mime_type = match content_type.split(';').next().map(str::trim) {
+                        Some(mime) if !mime.is_empty() => mime.to_string(),
+                        _ => "image/png".to_string(),
+                    };
+                    let tool_res = CallToolResult {
+                        content: vec![ToolResponseContent::Image {
+                            data: base64::engine::general_purpose::STANDARD.encode(&data),
+                            mime_type,
+                            annotations: None,
+                        }],
+                        is_error: None,
+                        _meta: None,
+                        progress: None,
+                        total: None,
+                    };
+                    Ok(standard_success_response(id, json!(tool_res)))
                 }
                 Err(e) => {
                     let tool_res = standard_tool_result(format!("Error: {}", e), Some(true));
@@ -81,9 +141,36 @@ impl Tool for ScrapingBeeTool {
     }
 }
 
+/// The `extract_links` action of `scrape_url`. Links are in the served HTML, so the page is
+/// fetched without JavaScript rendering, which costs fewer ScrapingBee credits.
+async fn extract_links(mut client: ScrapingBeeClient, url: &str, scope: LinkScope, id: Option<Value>) -> JsonRpcResponse {
+    client.url(url).render_js(false);
+    let tool_res = match client.execute().await {
+        Ok(ScrapingBeeResponse::Text(body)) => {
+            let links = extract_links_from_html(&body, url, scope);
+            let links = serde_json::to_string_pretty(&links).unwrap_or_else(|_| "[]".to_string());
+            standard_tool_result(links, None)
+        }
+        Ok(ScrapingBeeResponse::Binary { content_type, .. }) => {
+            standard_tool_result(format!("Error: {} is not an HTML page ({})", url, content_type), Some(true))
+        }
+        Err(e) => standard_tool_result(format!("Error: {}", e), Some(true)),
+    };
+    standard_success_response(id, json!(tool_res))
+}
+
 // Bash Tool Implementation
 #[derive(Debug)]
-pub struct BashTool;
+pub struct BashTool {
+    // Used to stream progress notifications when `stream` is requested
+    tx_out: mpsc::UnboundedSender<JsonRpcResponse>,
+}
+
+impl BashTool {
+    pub fn new(tx_out: mpsc::UnboundedSender<JsonRpcResponse>) -> Self {
+        Self { tx_out }
+    }
+}
 
 impl Tool for BashTool {
     fn name(&self) -> &str {
@@ -93,13 +180,27 @@ impl Tool for BashTool {
     fn info(&self) -> shared_protocol_objects::ToolInfo {
         bash_tool_info()
     }
+
+    fn detection_hints(&self) -> Vec<Vec<&str>> {
+        vec![vec!["command"]]
+    }
     
     fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture {
+        let tx_out = self.tx_out.clone();
+
         Box::pin(async move {
+            // Fall back to the request id when the client didn't ask for a progress token
+            let progress_token = progress_token(&params).unwrap_or_else(|| ensure_id(id.clone()));
             let bash_params: BashParams = serde_json::from_value(params.arguments)?;
             let executor = BashExecutor::new();
-            
-            match executor.execute(bash_params).await {
+
+            let result = if bash_params.stream {
+                executor.execute_streaming(bash_params, &tx_out, progress_token).await
+            } else {
+                executor.execute(bash_params).await
+            };
+
+            match result {
                 Ok(result) => {
                     let text = format!(
                         "Command completed with status {}\n\nSTDOUT:\n{}\n\nSTDERR:\n{}",
@@ -155,7 +256,8 @@ impl Tool for QuickBashTool {
 // BraveSearch Tool Implementation
 #[derive(Debug)]
 pub struct BraveSearchTool {
-    api_key: String,
+    // Shared across calls so the response cache survives between searches
+    client: Arc<BraveSearchClient>,
 }
 
 impl BraveSearchTool {
@@ -163,7 +265,7 @@ impl BraveSearchTool {
         let api_key = env::var("BRAVE_API_KEY")
             .map_err(|_| anyhow!("BRAVE_API_KEY environment variable must be set"))?;
             
-        Ok(Self { api_key })
+        Ok(Self { client: Arc::new(BraveSearchClient::with_cache(api_key, DEFAULT_CACHE_TTL)) })
     }
 }
 
@@ -175,9 +277,13 @@ impl Tool for BraveSearchTool {
     fn info(&self) -> shared_protocol_objects::ToolInfo {
         search_tool_info()
     }
+
+    fn detection_hints(&self) -> Vec<Vec<&str>> {
+        vec![vec!["query"]]
+    }
     
     fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture {
-        let api_key = self.api_key.clone();
+        let client = Arc::clone(&self.client);
         
         Box::pin(async move {
             let query = params
@@ -194,8 +300,6 @@ impl Tool for BraveSearchTool {
                 .unwrap_or(10)
                 .min(20) as u8;
                 
-            let client = BraveSearchClient::new(api_key);
-            
             match client.search(&query).await {
                 Ok(response) => {
                     let results = match response.web {
@@ -268,6 +372,46 @@ impl Tool for AiderTool {
     }
 }
 
+// Git Tool Implementation
+#[derive(Debug)]
+pub struct GitTool;
+
+impl Tool for GitTool {
+    fn name(&self) -> &str {
+        "git"
+    }
+    
+    fn info(&self) -> shared_protocol_objects::ToolInfo {
+        git_tool_info()
+    }
+
+    fn detection_hints(&self) -> Vec<Vec<&str>> {
+        vec![vec!["action", "repo_path", "files", "message"]]
+    }
+    
+    fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture {
+        Box::pin(async move { handle_git_tool_call(params, id).await })
+    }
+}
+
+// Gmail Tool Implementation
+#[derive(Debug)]
+pub struct GmailTool;
+
+impl Tool for GmailTool {
+    fn name(&self) -> &str {
+        "gmail_tool"
+    }
+    
+    fn info(&self) -> shared_protocol_objects::ToolInfo {
+        gmail_tool_info()
+    }
+    
+    fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture {
+        Box::pin(async move { handle_gmail_tool_call(params, id).await })
+    }
+}
+
 // LongRunningTask Tool Implementation
 #[derive(Debug)]
 pub struct LongRunningTaskTool {
@@ -304,7 +448,7 @@ impl Tool for LongRunningTaskTool {
 }
 
 // Factory function to create all available tools
-pub async fn create_tools() -> Result<Vec<Box<dyn Tool>>> {
+pub async fn create_tools(tx_out: mpsc::UnboundedSender<JsonRpcResponse>) -> Result<Vec<Box<dyn Tool>>> {
     let mut tools: Vec<Box<dyn Tool>> = Vec::new();
     
     // Add ScrapingBee tool if environment variable is set
@@ -321,16 +465,47 @@ pub async fn create_tools() -> Result<Vec<Box<dyn Tool>>> {
         warn!("BraveSearch tool not available: missing API key");
     }
     
+    // Add Gmail tool if the OAuth client is configured
+    if std::env::var("GOOGLE_OAUTH_CLIENT_ID").is_ok() {
+        tools.push(Box::new(GmailTool));
+    } else {
+        warn!("Gmail tool not available: missing OAuth client ID");
+    }
+    
+    // Add Git tool only when asked for, since it can commit and change the working tree
+    if std::env::var("MCP_ENABLE_GIT_TOOL").is_ok_and(|value| value == "1") {
+        tools.push(Box::new(GitTool));
+    } else {
+        warn!("Git tool not available: set MCP_ENABLE_GIT_TOOL=1 to enable it");
+    }
+    
     // Add other tools that don't require special initialization
     tools.push(Box::new(QuickBashTool));
-    tools.push(Box::new(BashTool));
+    tools.push(Box::new(BashTool::new(tx_out)));
     tools.push(Box::new(AiderTool));
+    tools.push(Box::new(SqliteTool));
+    tools.push(Box::new(FilesystemTool::from_env()));
+    tools.push(Box::new(CsvTool));
+    tools.push(Box::new(JsonTool::from_env()));
+    tools.push(Box::new(DiffTool));
+    tools.push(Box::new(ArchiveTool::from_env()));
+    tools.push(Box::new(CodeSearchTool::from_env()));
+    tools.push(Box::new(HttpClientTool));
     
     // Note: LongRunningTaskTool is added separately in main.rs since it needs the manager
     
     Ok(tools)
 }
 
+/// The progress token of a call: `_meta.progressToken` of the params, or of the arguments as
+/// older clients sent it
+pub fn progress_token(params: &CallToolParams) -> Option<Value> {
+    params._meta.as_ref()
+        .or_else(|| params.arguments.get("_meta"))
+        .and_then(|meta| meta.get("progressToken"))
+        .cloned()
+}
+
 // Helper function to send progress notification
 pub async fn send_progress_notification(
     tx_out: &mpsc::UnboundedSender<JsonRpcResponse>,
@@ -338,30 +513,18 @@ pub async fn send_progress_notification(
     progress: u32,
     total: u32
 ) -> Result<()> {
-    if let Some(meta) = params.arguments.get("_meta") {
-        if let Some(token) = meta.get("progressToken") {
-            let notification = shared_protocol_objects::create_notification(
-                "notifications/progress",
-                json!({
-                    "progressToken": token,
-                    "progress": progress,
-                    "total": total
-                }),
-            );
-
-            let progress_notification = JsonRpcResponse {
-                jsonrpc: notification.jsonrpc,
-                id: Value::Null,
-                result: Some(json!({
-                    "method": notification.method,
-                    "params": notification.params
-                })),
-                error: None,
-            };
-            
-            tx_out.send(progress_notification)
-                .map_err(|e| anyhow!("Failed to send progress notification: {}", e))?;
-        }
+    if let Some(token) = progress_token(params) {
+        let progress_notification = shared_protocol_objects::notification_response(
+            "notifications/progress",
+            Some(json!({
+                "progressToken": token,
+                "progress": progress,
+                "total": total
+            })),
+        );
+        
+        tx_out.send(progress_notification)
+            .map_err(|e| anyhow!("Failed to send progress notification: {}", e))?;
     }
     
     Ok(())
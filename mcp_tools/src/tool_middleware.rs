@@ -0,0 +1,209 @@
+use anyhow::Result;
+use jsonschema::Validator;
+use serde::Serialize;
+use serde_json::{json, Value};
+use shared_protocol_objects::{CallToolParams, JsonRpcErrorCode, JsonRpcResponse, ToolInfo};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::tool_trait::{standard_error_response, ExecuteFuture, Tool};
+
+/// Hooks that run around every tool call
+pub trait ToolMiddleware: Send + Sync + Debug + 'static {
+    /// Runs first. Returning a response rejects the call: the tool is not executed and the
+    /// response goes straight back to the client.
+    fn check(&self, _tool: &dyn Tool, _params: &CallToolParams, _id: &Option<Value>) -> Option<JsonRpcResponse> {
+        None
+    }
+
+    fn before_execute(&self, _name: &str, _params: &CallToolParams) {}
+
+    fn after_execute(&self, _name: &str, _duration: Duration, _result: &Result<JsonRpcResponse>) {}
+}
+
+/// Whether a tool call failed, either outright, with a JSON-RPC error or with a tool
+/// result marked `is_error`
+fn is_failure(result: &Result<JsonRpcResponse>) -> bool {
+    match result {
+        Err(_) => true,
+        Ok(response) => {
+            response.error.is_some()
+                || response.result.as_ref()
+                    .and_then(|r| r.get("is_error"))
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false)
+        }
+    }
+}
+
+/// A tool whose calls go through `middleware`
+#[derive(Debug)]
+pub struct MiddlewareWrappedTool<M: ToolMiddleware> {
+    inner: Box<dyn Tool>,
+    middleware: Arc<M>,
+}
+
+impl<M: ToolMiddleware> MiddlewareWrappedTool<M> {
+    pub fn new(inner: Box<dyn Tool>, middleware: Arc<M>) -> Self {
+        Self { inner, middleware }
+    }
+}
+
+impl<M: ToolMiddleware> Tool for MiddlewareWrappedTool<M> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn info(&self) -> ToolInfo {
+        self.inner.info()
+    }
+
+    fn detection_hints(&self) -> Vec<Vec<&str>> {
+        self.inner.detection_hints()
+    }
+
+    fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture {
+        let name = self.inner.name().to_string();
+        let middleware = Arc::clone(&self.middleware);
+        if let Some(rejection) = middleware.check(self.inner.as_ref(), &params, &id) {
+            return Box::pin(async move { Ok(rejection) });
+        }
+        middleware.before_execute(&name, &params);
+        let future = self.inner.execute(params, id);
+
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = future.await;
+            middleware.after_execute(&name, start.elapsed(), &result);
+            result
+        })
+    }
+}
+
+/// Logs every call and its outcome through `tracing`
+#[derive(Debug, Default)]
+pub struct LoggingMiddleware;
+
+impl ToolMiddleware for LoggingMiddleware {
+    fn before_execute(&self, name: &str, params: &CallToolParams) {
+        tracing::info!("Calling tool {}", name);
+        tracing::debug!("Arguments for {}: {}", name, params.arguments);
+    }
+
+    fn after_execute(&self, name: &str, duration: Duration, result: &Result<JsonRpcResponse>) {
+        match result {
+            Err(e) => tracing::error!("Tool {} failed after {:?}: {}", name, duration, e),
+            Ok(_) if is_failure(result) => tracing::warn!("Tool {} returned an error after {:?}", name, duration),
+            Ok(_) => tracing::info!("Tool {} finished in {:?}", name, duration),
+        }
+    }
+}
+
+/// Call counters for one tool
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ToolStats {
+    pub calls: u64,
+    pub errors: u64,
+    pub total_duration_ms: u64,
+    pub max_duration_ms: u64,
+}
+
+/// Counts calls, errors and time spent per tool
+#[derive(Debug, Clone, Default)]
+pub struct MetricsMiddleware {
+    pub stats: Arc<Mutex<HashMap<String, ToolStats>>>,
+}
+
+impl MetricsMiddleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn snapshot(&self) -> HashMap<String, ToolStats> {
+        self.stats.lock().unwrap().clone()
+    }
+}
+
+impl ToolMiddleware for MetricsMiddleware {
+    fn after_execute(&self, name: &str, duration: Duration, result: &Result<JsonRpcResponse>) {
+        let millis = duration.as_millis() as u64;
+        let mut stats = self.stats.lock().unwrap();
+        let entry = stats.entry(name.to_string()).or_default();
+        entry.calls += 1;
+        if is_failure(result) {
+            entry.errors += 1;
+        }
+        entry.total_duration_ms += millis;
+        entry.max_duration_ms = entry.max_duration_ms.max(millis);
+    }
+}
+
+/// Rejects calls whose arguments don't match the tool's `input_schema`
+#[derive(Debug, Default)]
+pub struct SchemaValidationMiddleware {
+    /// Compiled schemas by tool name; `None` for a schema that doesn't compile
+    validators: Mutex<HashMap<String, Option<Arc<Validator>>>>,
+}
+
+impl SchemaValidationMiddleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn validator(&self, tool: &dyn Tool) -> Option<Arc<Validator>> {
+        let mut validators = self.validators.lock().unwrap();
+        validators.entry(tool.name().to_string())
+            .or_insert_with(|| match jsonschema::validator_for(&tool.info().input_schema) {
+                Ok(validator) => Some(Arc::new(validator)),
+                Err(e) => {
+                    tracing::warn!("Not validating calls to {}: invalid input schema: {}", tool.name(), e);
+                    None
+                }
+            })
+            .clone()
+    }
+}
+
+impl ToolMiddleware for SchemaValidationMiddleware {
+    fn check(&self, tool: &dyn Tool, params: &CallToolParams, id: &Option<Value>) -> Option<JsonRpcResponse> {
+        let validator = self.validator(tool)?;
+        // A call without arguments is treated like one with an empty object
+        let empty = json!({});
+        let arguments = if params.arguments.is_null() { &empty } else { &params.arguments };
+
+        let errors: Vec<Value> = validator.iter_errors(arguments)
+            .map(|e| json!({
+                "path": e.instance_path.to_string(),
+                "message": e.to_string(),
+            }))
+            .collect();
+        let first = errors.first()?;
+
+        let mut response = standard_error_response(
+            id.clone(),
+            JsonRpcErrorCode::InvalidParams,
+            &format!("Invalid parameters: {}", first["message"].as_str().unwrap_or_default()),
+        );
+        if let Some(error) = response.error.as_mut() {
+            error.data = Some(json!({ "errors": errors }));
+        }
+        Some(response)
+    }
+}
+
+/// Wrap every tool in logging, `metrics` and input schema validation
+pub fn with_default_middleware(tools: Vec<Box<dyn Tool>>, metrics: &MetricsMiddleware) -> Vec<Box<dyn Tool>> {
+    let logging = Arc::new(LoggingMiddleware);
+    let metrics = Arc::new(metrics.clone());
+    let validation = Arc::new(SchemaValidationMiddleware::new());
+    tools.into_iter()
+        .map(|tool| {
+            // Rejected calls still pass through the outer layers, so they are logged and counted
+            let validated: Box<dyn Tool> = Box::new(MiddlewareWrappedTool::new(tool, Arc::clone(&validation)));
+            let counted: Box<dyn Tool> = Box::new(MiddlewareWrappedTool::new(validated, Arc::clone(&metrics)));
+            Box::new(MiddlewareWrappedTool::new(counted, Arc::clone(&logging))) as Box<dyn Tool>
+        })
+        .collect()
+}
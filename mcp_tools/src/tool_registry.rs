@@ -0,0 +1,97 @@
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+use shared_protocol_objects::{notification_response, CallToolParams, JsonRpcResponse, ToolInfo};
+use std::sync::{Arc, RwLock};
+use tokio::sync::mpsc;
+use tracing::info;
+
+use crate::tool_middleware::{with_default_middleware, MetricsMiddleware};
+use crate::tool_trait::{ExecuteFuture, Tool};
+
+/// The tools the server offers. Tools can be added and removed while the server runs; the
+/// client is told through `notifications/tools/list_changed`.
+#[derive(Debug, Clone)]
+pub struct ToolRegistry {
+    /// Keyed by tool name, in registration order so `tools/list` stays stable
+    tools: Arc<RwLock<IndexMap<String, Box<dyn Tool>>>>,
+    /// Counts the calls of every tool, including those registered later
+    metrics: MetricsMiddleware,
+    tx_out: mpsc::UnboundedSender<JsonRpcResponse>,
+}
+
+impl ToolRegistry {
+    /// A registry holding the tools available at startup. No notification is sent for them.
+    /// Every tool, these and those registered later, gets the default middleware.
+    pub fn new(
+        tools: Vec<Box<dyn Tool>>,
+        metrics: &MetricsMiddleware,
+        tx_out: mpsc::UnboundedSender<JsonRpcResponse>,
+    ) -> Self {
+        let tools = with_default_middleware(tools, metrics).into_iter()
+            .map(|tool| (tool.name().to_string(), tool))
+            .collect();
+        Self {
+            tools: Arc::new(RwLock::new(tools)),
+            metrics: metrics.clone(),
+            tx_out,
+        }
+    }
+
+    /// Add a tool, replacing any tool with the same name
+    pub fn register(&self, tool: Box<dyn Tool>) {
+        let name = tool.name().to_string();
+        info!("Registering tool {}", name);
+        let tool = with_default_middleware(vec![tool], &self.metrics)
+            .pop()
+            .expect("one tool in, one tool out");
+        self.tools.write().unwrap().insert(name, tool);
+        self.notify_list_changed();
+    }
+
+    /// Remove a tool. Returns it, or `None` if no tool has that name.
+    pub fn deregister(&self, name: &str) -> Option<Box<dyn Tool>> {
+        let removed = self.tools.write().unwrap().shift_remove(name);
+        if removed.is_some() {
+            info!("Deregistered tool {}", name);
+            self.notify_list_changed();
+        }
+        removed
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.tools.read().unwrap().contains_key(name)
+    }
+
+    pub fn infos(&self) -> Vec<ToolInfo> {
+        self.tools.read().unwrap().values().map(|tool| tool.info()).collect()
+    }
+
+    /// The tool a malformed request was probably meant for, judged by which tool's
+    /// `detection_hints` its argument names match first
+    pub fn detect_intended_tool(&self, json: &Value) -> Option<String> {
+        let obj = json.as_object()?;
+        let tools = self.tools.read().unwrap();
+        tools.values()
+            .find(|tool| {
+                tool.detection_hints().iter().any(|params| {
+                    // Count how many of the hint parameters are present
+                    let matches = params.iter().filter(|param| obj.contains_key(**param)).count();
+                    // If we find more than half of the expected parameters, this is probably the intended tool
+                    !params.is_empty() && matches >= params.len().div_ceil(2)
+                })
+            })
+            .map(|tool| tool.name().to_string())
+    }
+
+    /// Start a call of the named tool, or `None` if there is no such tool. The returned
+    /// future doesn't borrow the registry, so tools can change while it runs.
+    pub fn execute(&self, params: CallToolParams, id: Option<Value>) -> Option<ExecuteFuture> {
+        let tools = self.tools.read().unwrap();
+        let tool = tools.get(&params.name)?;
+        Some(tool.execute(params, id))
+    }
+
+    fn notify_list_changed(&self) {
+        let _ = self.tx_out.send(notification_response("notifications/tools/list_changed", Some(json!({}))));
+    }
+}
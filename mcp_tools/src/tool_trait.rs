@@ -1,6 +1,6 @@
 use anyhow::Result;
 use serde_json::Value;
-use shared_protocol_objects::{CallToolParams, JsonRpcResponse};
+use shared_protocol_objects::{CallToolParams, JsonRpcErrorCode, JsonRpcResponse};
 use std::fmt::Debug;
 use std::future::Future;
 use std::pin::Pin;
@@ -21,6 +21,12 @@ pub trait Tool: Send + Sync + Debug {
     /// This returns a boxed future instead of being an async function
     /// to make the trait object-safe.
     fn execute(&self, params: CallToolParams, id: Option<Value>) -> ExecuteFuture;
+
+    /// Sets of argument names that identify a malformed request as an attempt to call this
+    /// tool. A set matches when at least half of its names are present.
+    fn detection_hints(&self) -> Vec<Vec<&str>> {
+        Vec::new()
+    }
 }
 
 /// Helper function to standardize ID handling
@@ -31,7 +37,7 @@ pub fn ensure_id(id: Option<Value>) -> Value {
 /// Helper function to create a standard error response
 pub fn standard_error_response(
     id: Option<Value>, 
-    code: i64, 
+    code: impl Into<JsonRpcErrorCode>, 
     message: &str
 ) -> JsonRpcResponse {
     shared_protocol_objects::error_response(Some(ensure_id(id)), code, message)
@@ -51,8 +57,7 @@ pub fn standard_tool_result(
     is_error: Option<bool>
 ) -> shared_protocol_objects::CallToolResult {
     shared_protocol_objects::CallToolResult {
-        content: vec![shared_protocol_objects::ToolResponseContent {
-            type_: "text".into(),
+        content: vec![shared_protocol_objects::ToolResponseContent::Text {
             text,
             annotations: None,
         }],
@@ -1,20 +1,88 @@
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::fmt;
 
 /// Core protocol version constants
 pub const LATEST_PROTOCOL_VERSION: &str = "2024-11-05";
 pub const SUPPORTED_PROTOCOL_VERSIONS: [&str; 2] = ["2024-11-05", "2024-10-07"];
 
 /// Standard JSON-RPC error codes
-pub const PARSE_ERROR: i64 = -32700;
-pub const INVALID_REQUEST: i64 = -32600;
-pub const METHOD_NOT_FOUND: i64 = -32601;
-pub const INVALID_PARAMS: i64 = -32602;
-pub const INTERNAL_ERROR: i64 = -32603;
+#[deprecated(note = "use JsonRpcErrorCode::ParseError")]
+pub const PARSE_ERROR: i64 = JsonRpcErrorCode::ParseError.code();
+#[deprecated(note = "use JsonRpcErrorCode::InvalidRequest")]
+pub const INVALID_REQUEST: i64 = JsonRpcErrorCode::InvalidRequest.code();
+#[deprecated(note = "use JsonRpcErrorCode::MethodNotFound")]
+pub const METHOD_NOT_FOUND: i64 = JsonRpcErrorCode::MethodNotFound.code();
+#[deprecated(note = "use JsonRpcErrorCode::InvalidParams")]
+pub const INVALID_PARAMS: i64 = JsonRpcErrorCode::InvalidParams.code();
+#[deprecated(note = "use JsonRpcErrorCode::InternalError")]
+pub const INTERNAL_ERROR: i64 = JsonRpcErrorCode::InternalError.code();
+
+/// JSON-RPC error codes, serialized as their integer value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JsonRpcErrorCode {
+    ParseError,
+    InvalidRequest,
+    MethodNotFound,
+    InvalidParams,
+    InternalError,
+    /// Server-defined code outside the standard set
+    Application(i64),
+}
+
+impl JsonRpcErrorCode {
+    pub const fn code(&self) -> i64 {
+        match self {
+            JsonRpcErrorCode::ParseError => -32700,
+            JsonRpcErrorCode::InvalidRequest => -32600,
+            JsonRpcErrorCode::MethodNotFound => -32601,
+            JsonRpcErrorCode::InvalidParams => -32602,
+            JsonRpcErrorCode::InternalError => -32603,
+            JsonRpcErrorCode::Application(code) => *code,
+        }
+    }
+}
 
+impl From<i64> for JsonRpcErrorCode {
+    fn from(code: i64) -> Self {
+        match code {
+            -32700 => JsonRpcErrorCode::ParseError,
+            -32600 => JsonRpcErrorCode::InvalidRequest,
+            -32601 => JsonRpcErrorCode::MethodNotFound,
+            -32602 => JsonRpcErrorCode::InvalidParams,
+            -32603 => JsonRpcErrorCode::InternalError,
+            other => JsonRpcErrorCode::Application(other),
+        }
+    }
+}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl From<JsonRpcErrorCode> for i64 {
+    fn from(code: JsonRpcErrorCode) -> Self {
+        code.code()
+    }
+}
+
+impl fmt::Display for JsonRpcErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl Serialize for JsonRpcErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonRpcErrorCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(JsonRpcErrorCode::from)
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum RequestId {
     Number(i64),
@@ -22,6 +90,29 @@ pub enum RequestId {
     Null,
 }
 
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestId::Number(n) => write!(f, "{}", n),
+            RequestId::String(s) => write!(f, "{:?}", s),
+            RequestId::Null => write!(f, "null"),
+        }
+    }
+}
+
+impl From<Value> for RequestId {
+    /// Ids that are neither integers nor strings (which the spec discourages) keep
+    /// their JSON text as a string id so they still compare consistently.
+    fn from(id: Value) -> Self {
+        match id {
+            Value::Number(n) if n.is_i64() => RequestId::Number(n.as_i64().unwrap_or_default()),
+            Value::String(s) => RequestId::String(s),
+            Value::Null => RequestId::Null,
+            other => RequestId::String(other.to_string()),
+        }
+    }
+}
+
 impl From<RequestId> for Value {
     fn from(id: RequestId) -> Self {
         match id {
@@ -48,7 +139,17 @@ pub struct JsonRpcRequest {
     pub method: String,
     #[serde(default)]
     pub params: Option<Value>,
-    pub id: Value,  // Required according to JSON-RPC spec
+    // Required according to JSON-RPC spec for requests; notifications omit it and parse as Null
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub id: Value,
+}
+
+/// A single line of JSON-RPC input: either one request or a batch of them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum JsonRpcMessage {
+    Single(JsonRpcRequest),
+    Batch(Vec<JsonRpcRequest>),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,7 +165,7 @@ pub struct JsonRpcResponse {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonRpcError {
-    pub code: i64,
+    pub code: JsonRpcErrorCode,
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<Value>,
@@ -84,14 +185,34 @@ pub struct ClientCapabilities {
     #[serde(default)]
     pub sampling: Option<Value>,
     #[serde(default)]
-    pub roots: Option<RootsCapability>,
+    pub roots: Option<ClientRootsCapability>,
+}
+
+/// Whether a client tells servers when its own roots change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientRootsCapability {
+    pub list_changed: bool,
 }
 
+/// A server answering `roots/list` with the file system roots it works in
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RootsCapability {
     pub list_changed: bool,
 }
 
+/// A file system root, `uri` being a `file://` URI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Root {
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListRootsResult {
+    pub roots: Vec<Root>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerCapabilities {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -104,6 +225,8 @@ pub struct ServerCapabilities {
     pub resources: Option<ResourcesCapability>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<ToolsCapability>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roots: Option<RootsCapability>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -111,10 +234,65 @@ pub struct PromptsCapability {
     pub list_changed: bool,
 }
 
+/// Prompt types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptArgument {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// A prompt offered through `prompts/list`. `template` holds the text with
+/// `{{argument_name}}` placeholders and is never sent to clients.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptDefinition {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<PromptArgument>,
+    #[serde(skip_serializing, default)]
+    pub template: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListPromptsResult {
+    pub prompts: Vec<PromptDefinition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetPromptParams {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptMessage {
+    pub role: Role,
+    pub content: ToolResponseContent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetPromptResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub messages: Vec<PromptMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _meta: Option<Value>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourcesCapability {
     pub list_changed: bool,
     pub subscribe: bool,
+    /// Whether the server accepts `resources/write`
+    #[serde(default)]
+    pub write: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -161,11 +339,79 @@ pub struct ResourceContent {
     pub blob: Option<String>,
 }
 
+/// A parameterised resource URI such as `file://{path}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceTemplate {
+    #[serde(rename = "uriTemplate")]
+    pub uri_template: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+impl ResourceTemplate {
+    /// Match `uri` against the template, returning the value of each `{variable}`.
+    /// The last variable of a template captures the rest of the URI, slashes included.
+    pub fn match_uri(&self, uri: &str) -> Option<HashMap<String, String>> {
+        let mut variables = HashMap::new();
+        let mut template = self.uri_template.as_str();
+        let mut rest = uri;
+
+        loop {
+            let Some(open) = template.find('{') else {
+                return (template == rest).then_some(variables);
+            };
+            let literal = &template[..open];
+            rest = rest.strip_prefix(literal)?;
+
+            let close = open + template[open..].find('}')?;
+            let name = &template[open + 1..close];
+            template = &template[close + 1..];
+
+            // The value runs until the next literal of the template, or to the end
+            let next_literal = &template[..template.find('{').unwrap_or(template.len())];
+            let end = if next_literal.is_empty() {
+                if template.is_empty() { rest.len() } else { return None }
+            } else {
+                rest.find(next_literal)?
+            };
+            if end == 0 {
+                return None;
+            }
+            variables.insert(name.to_string(), rest[..end].to_string());
+            rest = &rest[end..];
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListResourceTemplatesResult {
+    #[serde(rename = "resourceTemplates")]
+    pub resource_templates: Vec<ResourceTemplate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _meta: Option<Value>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReadResourceParams {
     pub uri: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WriteResourceParams {
+    pub uri: String,
+    pub content: String,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscribeResourceParams {
+    pub uri: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReadResourceResult {
     pub contents: Vec<ResourceContent>,
@@ -187,6 +433,9 @@ pub struct ToolInfo {
     pub description: Option<String>,
     #[serde(rename = "inputSchema")]
     pub input_schema: serde_json::Value,
+    /// JSON schema of the tool's result, so clients know what fields to expect
+    #[serde(rename = "outputSchema", default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -208,6 +457,9 @@ pub struct CallToolParams {
     pub name: String,
     #[serde(default)]
     pub arguments: Value,
+    /// Request metadata, e.g. the `progressToken` progress notifications should carry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub _meta: Option<Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -223,13 +475,33 @@ pub struct CallToolResult {
     pub total: Option<u32>,
 }
 
+/// A single content item in a tool result, tagged by its MCP `type`
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ToolResponseContent {
-    #[serde(rename = "type")]
-    pub type_: String,
-    pub text: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub annotations: Option<HashMap<String, Value>>,
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ToolResponseContent {
+    Text {
+        text: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        annotations: Option<HashMap<String, Value>>,
+    },
+    /// Base64-encoded image data
+    Image {
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        annotations: Option<HashMap<String, Value>>,
+    },
+}
+
+impl ToolResponseContent {
+    /// The MCP content type name of this item
+    pub fn type_(&self) -> &'static str {
+        match self {
+            ToolResponseContent::Text { .. } => "text",
+            ToolResponseContent::Image { .. } => "image",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -249,13 +521,13 @@ pub fn success_response(id: Option<Value>, result: Value) -> JsonRpcResponse {
     }
 }
 
-pub fn error_response(id: Option<Value>, code: i64, message: &str) -> JsonRpcResponse {
+pub fn error_response(id: Option<Value>, code: impl Into<JsonRpcErrorCode>, message: &str) -> JsonRpcResponse {
     JsonRpcResponse {
         jsonrpc: "2.0".to_string(),
         id: id.unwrap_or(Value::Null),
         result: None,
         error: Some(JsonRpcError {
-            code,
+            code: code.into(),
             message: message.to_string(),
             data: None,
         }),
@@ -263,12 +535,29 @@ pub fn error_response(id: Option<Value>, code: i64, message: &str) -> JsonRpcRes
 }
 
 /// Create a standard notification according to the JSON-RPC 2.0 specification.
-/// Unlike requests and responses, notifications don't have an ID.
-pub fn create_notification(method: &str, params: Value) -> JsonRpcNotification {
-    JsonRpcNotification {
+/// Unlike requests and responses, notifications don't have an ID: it is `Null` and left out
+/// when serialized.
+pub fn create_notification(method: &str, params: Option<Value>) -> JsonRpcRequest {
+    JsonRpcRequest {
         jsonrpc: "2.0".to_string(),
         method: method.to_string(),
         params,
+        id: Value::Null,
+    }
+}
+
+/// A notification in the form servers send it on their outgoing channel of responses: a
+/// response without an id whose result holds the method and params
+pub fn notification_response(method: &str, params: Option<Value>) -> JsonRpcResponse {
+    let notification = create_notification(method, params);
+    JsonRpcResponse {
+        jsonrpc: notification.jsonrpc,
+        id: Value::Null,
+        result: Some(json!({
+            "method": notification.method,
+            "params": notification.params
+        })),
+        error: None,
     }
 }
 
@@ -300,3 +589,44 @@ pub enum Notification {
     #[serde(rename = "resource_update")]
     ResourceUpdate(ResourceUpdateNotification),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(uri_template: &str) -> ResourceTemplate {
+        ResourceTemplate {
+            uri_template: uri_template.to_string(),
+            name: "test".to_string(),
+            description: None,
+            mime_type: None,
+        }
+    }
+
+    #[test]
+    fn match_uri_literal_template() {
+        let template = template("config://app");
+        assert_eq!(template.match_uri("config://app"), Some(HashMap::new()));
+        assert_eq!(template.match_uri("config://app/extra"), None);
+    }
+
+    #[test]
+    fn match_uri_multiple_variables() {
+        let variables = template("repo://{owner}/{name}/file/{path}")
+            .match_uri("repo://rust-lang/rust/file/src/lib.rs")
+            .unwrap();
+        assert_eq!(variables["owner"], "rust-lang");
+        assert_eq!(variables["name"], "rust");
+        // The last variable takes the rest, slashes included
+        assert_eq!(variables["path"], "src/lib.rs");
+    }
+
+    #[test]
+    fn match_uri_rejects_non_matching_uris() {
+        let web = template("web://{url}");
+        assert_eq!(web.match_uri("file://x"), None);
+        // Variables can't be empty
+        assert_eq!(web.match_uri("web://"), None);
+        assert_eq!(template("a://{x}/b").match_uri("a://y/c"), None);
+    }
+}